@@ -0,0 +1,43 @@
+//! Times the pivots of a whole solve of generated programs, on both tableau backends
+//!
+//! Run with `cargo bench --features testing`
+use simplex::generators::random_feasible_lp;
+use simplex::{PivotRule, Simplex, TableauBackend};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RUNS: u32 = 200;
+
+fn time(mut f: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        f();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let program = random_feasible_lp(100, 60, 1);
+    let solve = time(|| {
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        black_box(simplex);
+    });
+    println!("solve of 100 variables and 60 constraints: {solve:?}");
+
+    let program = random_feasible_lp(50, 50, 2);
+    let solve = |backend| {
+        let mut simplex = Simplex::from(program.clone()).with_backend(backend);
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        simplex.current_state().objective_value()
+    };
+    let (map, dense) = (solve(TableauBackend::Map), solve(TableauBackend::Dense));
+    assert!((map - dense).abs() <= 1e-3 * map.abs().max(1.0), "optima differ: {map} and {dense}");
+    let map = time(|| {
+        black_box(solve(TableauBackend::Map));
+    });
+    let dense = time(|| {
+        black_box(solve(TableauBackend::Dense));
+    });
+    println!("solve of 50 variables and 50 constraints: {map:?} on maps, {dense:?} dense");
+}
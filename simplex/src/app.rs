@@ -1,20 +1,74 @@
-use crate::constraint::Constraints;
-use crate::linear_function::LinearFunction;
+use crate::geometry::{contour_line, feasible_polygon};
 use crate::polyhedron::PolyhedronRenderer;
-use crate::{Simplex, SimplexError};
+use crate::{LinearProgram, ParseProblemError, PivotRule, Simplex, SimplexError, SimplexStatus, ValidationWarning};
 use eframe::{egui_glow, Frame};
+use egui::plot::{Line, LineStyle, Plot, PlotPoints, Points, Polygon};
 use egui::FontFamily::Proportional;
 use egui::TextStyle::{Body, Button, Heading, Monospace, Small};
-use egui::{Color32, Context, Style};
+use egui::{Color32, Context, Key, RichText, Style};
 use egui::{FontId, Sense};
 use std::sync::{Arc, Mutex};
 
+/// Number of contour lines of the objective drawn on 2D figures
+const CONTOUR_LINES: usize = 8;
+
+/// A move through the steps of the simplex, triggered by a button or a key
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StepAction {
+    Next,
+    Previous,
+    Reset,
+}
+
+impl StepAction {
+    /// Returns the action bound to the keys pressed during this frame, if any
+    fn from_keys(ctx: &Context) -> Option<StepAction> {
+        if ctx.wants_keyboard_input() {
+            return None;
+        }
+        ctx.input(|i| {
+            if i.key_pressed(Key::ArrowRight) || i.key_pressed(Key::Space) {
+                Some(StepAction::Next)
+            } else if i.key_pressed(Key::ArrowLeft) {
+                Some(StepAction::Previous)
+            } else if i.key_pressed(Key::R) {
+                Some(StepAction::Reset)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Applies the action to the simplex. Stepping past the optimum or before the first step
+    /// leaves the simplex unchanged
+    fn apply(self, simplex: &mut Simplex) -> Result<(), SimplexError> {
+        match self {
+            StepAction::Next => simplex.next_step(PivotRule::Bland),
+            StepAction::Previous => {
+                simplex.previous_step();
+                Ok(())
+            }
+            StepAction::Reset => {
+                simplex.reset();
+                Ok(())
+            }
+        }
+    }
+}
+
 pub struct SimplexVisualizer {
     maximize: bool,
     function_input: String,
     constraints_input: String,
+    show_contours: bool,
 
     simplex: Option<Result<Simplex, SimplexError>>,
+    /// The error met while parsing the program, if any
+    parse_error: Option<ParseProblemError>,
+    /// The likely mistakes found in the program when compiling it
+    warnings: Vec<ValidationWarning>,
+    /// The error returned by the last step, if any
+    step_error: Option<SimplexError>,
     polyhedron_renderer: Arc<Mutex<PolyhedronRenderer>>,
 }
 
@@ -31,8 +85,12 @@ x + y + z <= 400\n\
 y + 3z <= 600\n
             ",
             ),
+            show_contours: true,
 
             simplex: None,
+            parse_error: None,
+            warnings: vec![],
+            step_error: None,
             polyhedron_renderer: Arc::new(Mutex::new(
                 PolyhedronRenderer::init(cc.gl.as_ref().unwrap()).unwrap(),
             )),
@@ -60,6 +118,82 @@ y + 3z <= 600\n
         };
         ui.painter().add(callback);
     }
+
+    fn step(&mut self, action: StepAction) {
+        if let Some(Ok(simplex)) = &mut self.simplex {
+            self.step_error = action.apply(simplex).err();
+        }
+    }
+
+    /// Draws the feasible region of a program with two decision variables, along with the
+    /// current vertex and the contour lines of the objective
+    fn draw_figure_2d(&self, ui: &mut egui::Ui, simplex: &Simplex) {
+        let initial_state = simplex.initial_state();
+        let variables = initial_state.non_gap_variables();
+        let (x_var, y_var) = (&variables[0], &variables[1]);
+        let polygon = feasible_polygon(&initial_state.constraints, x_var, y_var);
+        let current_point = simplex.current_point();
+
+        // Bounding box of the figure, with a small margin around the feasible region
+        let mut min = [0f32; 2];
+        let mut max = [1f32; 2];
+        for vertex in polygon.iter() {
+            for i in 0..2 {
+                min[i] = min[i].min(vertex[i]);
+                max[i] = max[i].max(vertex[i]);
+            }
+        }
+        for i in 0..2 {
+            let margin = (max[i] - min[i]) * 0.1;
+            min[i] -= margin;
+            max[i] += margin;
+        }
+
+        let to_plot_points =
+            |points: &[[f32; 2]]| PlotPoints::from_iter(points.iter().map(|p| [p[0] as f64, p[1] as f64]));
+
+        Plot::new("figure").data_aspect(1.0).show(ui, |plot_ui| {
+            plot_ui.polygon(Polygon::new(to_plot_points(&polygon)).name("Feasible region"));
+
+            if self.show_contours {
+                let objective = &initial_state.linear_function;
+                let corner_values = [
+                    [min[0], min[1]],
+                    [min[0], max[1]],
+                    [max[0], min[1]],
+                    [max[0], max[1]],
+                ]
+                .map(|[x, y]| objective.evaluate(&[(x_var.clone(), x), (y_var.clone(), y)]));
+                let lowest = corner_values.iter().copied().fold(f32::INFINITY, f32::min);
+                let highest = corner_values.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+
+                for i in 1..CONTOUR_LINES {
+                    let value = lowest + (highest - lowest) * i as f32 / CONTOUR_LINES as f32;
+                    if let Some((p, q)) = contour_line(objective, x_var, y_var, value, min, max) {
+                        plot_ui.line(Line::new(to_plot_points(&[p, q])).color(Color32::GRAY));
+                    }
+                }
+
+                // The contour line going through the current vertex
+                let current_value = objective.evaluate(&simplex.current_values());
+                if let Some((p, q)) = contour_line(objective, x_var, y_var, current_value, min, max) {
+                    plot_ui.line(
+                        Line::new(to_plot_points(&[p, q]))
+                            .color(Color32::RED)
+                            .style(LineStyle::dashed_loose())
+                            .name("Current objective value"),
+                    );
+                }
+            }
+
+            plot_ui.points(
+                Points::new(to_plot_points(&[[current_point[0], current_point[1]]]))
+                    .radius(5.0)
+                    .color(Color32::RED)
+                    .name("Current vertex"),
+            );
+        });
+    }
 }
 
 impl eframe::App for SimplexVisualizer {
@@ -96,28 +230,38 @@ impl eframe::App for SimplexVisualizer {
                                 ui.text_edit_singleline(&mut self.function_input);
                             });
                             ui.text_edit_multiline(&mut self.constraints_input);
+                            ui.checkbox(&mut self.show_contours, "Show contour lines");
 
                             if ui.add(egui::Button::new("COMPILE")).clicked() {
-                                // Parse constraints
-                                let constraints =
-                                    Constraints::compile(&self.constraints_input).unwrap();
-                                // Parse linear function
-                                let function = self
-                                    .function_input
-                                    .parse()
-                                    .unwrap_or(LinearFunction::zero());
-
-                                // Create simplex
-                                self.simplex = Some(constraints.maximize(&if self.maximize {
-                                    function
-                                } else {
-                                    -function
-                                }));
-                                self.polyhedron_renderer
-                                    .lock()
-                                    .unwrap()
-                                    .polyhedron_from_constraints(&constraints);
-
+                                let problem = format!(
+                                    "{} {}\n{}",
+                                    if self.maximize { "max" } else { "min" },
+                                    self.function_input,
+                                    self.constraints_input
+                                );
+                                self.step_error = None;
+                                match problem.parse::<LinearProgram>() {
+                                    Ok(program) => {
+                                        self.parse_error = None;
+                                        self.warnings = program.validate().err().unwrap_or_default();
+                                        self.polyhedron_renderer
+                                            .lock()
+                                            .unwrap()
+                                            .polyhedron_from_constraints(&program.constraints);
+                                        self.simplex = Some(if program.is_unbounded() {
+                                            Err(SimplexError::Unbounded)
+                                        } else {
+                                            Ok(Simplex::from(program))
+                                        });
+                                    }
+                                    Err(error) => self.parse_error = Some(error),
+                                }
+                            }
+                            if let Some(error) = &self.parse_error {
+                                ui.colored_label(Color32::RED, error.to_string());
+                            }
+                            for warning in self.warnings.iter() {
+                                ui.colored_label(Color32::YELLOW, warning.to_string());
                             }
                         });
                     })
@@ -138,13 +282,58 @@ impl eframe::App for SimplexVisualizer {
                                         format!("{acc}{v} = {c}\n")
                                     }));
 
-                                    ui.heading("State");
+                                    if let Ok(basis) = simplex.optimal_basis() {
+                                        ui.label(format!("Optimal basis: {{{}}}", basis.join(", ")));
+                                    }
+
+                                    let status_color = match simplex.status() {
+                                        SimplexStatus::InProgress => Color32::WHITE,
+                                        SimplexStatus::Optimal => Color32::GREEN,
+                                        SimplexStatus::Unbounded | SimplexStatus::Infeasible => Color32::RED,
+                                    };
+                                    ui.heading(
+                                        RichText::new(format!("State ({})", simplex.current_phase())).color(status_color),
+                                    );
                                     let current_state = simplex.current_state();
                                     ui.colored_label(
                                         Color32::RED,
                                         format!("max {}", current_state.linear_function),
                                     );
                                     ui.label(current_state.constraints.to_string());
+
+                                    // How the current step was reached, and whether it is the optimum
+                                    let index = simplex.current_step_index();
+                                    if let Some(Ok(explanation)) = index.checked_sub(1).map(|i| simplex.explain_step(i)) {
+                                        ui.label(explanation);
+                                    }
+                                    if simplex.is_optimal() {
+                                        if let Ok(explanation) = simplex.explain_step(index) {
+                                            ui.colored_label(Color32::GREEN, explanation);
+                                        }
+                                    }
+
+                                    if let Some(var) = simplex.initial_state().quick_unbounded_check() {
+                                        ui.colored_label(
+                                            Color32::YELLOW,
+                                            format!("The objective grows forever along {var}: this program is unbounded"),
+                                        );
+                                    }
+
+                                    match &self.step_error {
+                                        Some(SimplexError::AlreadyOptimal) => {
+                                            ui.colored_label(Color32::GREEN, "The optimum has been reached");
+                                        }
+                                        Some(SimplexError::Unbounded) => {
+                                            ui.colored_label(Color32::RED, "This program is unbounded");
+                                        }
+                                        Some(SimplexError::Infeasible) => {
+                                            ui.colored_label(Color32::RED, "This program is infeasible");
+                                        }
+                                        Some(error) => {
+                                            ui.colored_label(Color32::RED, format!("Error: {error}"));
+                                        }
+                                        None => {}
+                                    }
                                 }
                                 Some(Err(SimplexError::Unbounded)) => {
                                     ui.colored_label(Color32::RED, "This program is unbounded");
@@ -152,32 +341,72 @@ impl eframe::App for SimplexVisualizer {
                                 None => {
                                     ui.label("Press RUN to start the algorithm");
                                 }
-                                _ => {
-                                    ui.label("How did we get there ?");
-
+                                Some(Err(error)) => {
+                                    ui.colored_label(Color32::RED, format!("Error: {error}"));
                                 }
                             }
                         });
 
+                        let (can_step_backward, can_step_forward) = match &self.simplex {
+                            Some(Ok(simplex)) => (simplex.can_step_backward(), simplex.can_step_forward()),
+                            _ => (false, false),
+                        };
                         ui.horizontal(|ui| {
                             // Previous button
-                            if ui.add(egui::Button::new("PREVIOUS")).clicked() {
-                                if let Some(Ok(simplex)) = &mut self.simplex {
-                                    simplex.previous_step();
-                                }
+                            if ui.add_enabled(can_step_backward, egui::Button::new("PREVIOUS")).clicked() {
+                                self.step(StepAction::Previous);
                             }
                             // Next button
-                            if ui.add(egui::Button::new("NEXT")).clicked() {
-                                if let Some(Ok(simplex)) = &mut self.simplex {
-                                    simplex.next_step(true);
-                                }
+                            if ui.add_enabled(can_step_forward, egui::Button::new("NEXT")).clicked() {
+                                self.step(StepAction::Next);
                             }
-                        })
+                        });
+                        ui.small("→ / Space: next step, ←: previous step, R: back to the first step");
                     })
             });
 
-        if self.simplex.is_some() {
-            egui::CentralPanel::default().show(ctx, |ui| self.draw_polyhedron(ui));
+        if let Some(action) = StepAction::from_keys(ctx) {
+            self.step(action);
+        }
+
+        match &self.simplex {
+            Some(Ok(simplex)) if simplex.initial_state().non_gap_variables().len() == 2 => {
+                egui::CentralPanel::default().show(ctx, |ui| self.draw_figure_2d(ui, simplex));
+            }
+            Some(_) => {
+                egui::CentralPanel::default().show(ctx, |ui| self.draw_polyhedron(ui));
+            }
+            None => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constraints;
+    use crate::linear_function::LinearFunction;
+
+    #[test]
+    fn test_step_action() {
+        let constraints = Constraints::compile("x <= 4\n y <= 3").unwrap();
+        let mut simplex = constraints
+            .maximize(&"x + y".parse::<LinearFunction>().unwrap())
+            .unwrap();
+
+        assert!(StepAction::Previous.apply(&mut simplex).is_ok());
+        assert_eq!(simplex.current_point(), vec![0.0, 0.0]);
+
+        assert!(StepAction::Next.apply(&mut simplex).is_ok());
+        assert!(StepAction::Next.apply(&mut simplex).is_ok());
+        assert_eq!(simplex.current_point(), vec![4.0, 3.0]);
+        assert!(matches!(
+            StepAction::Next.apply(&mut simplex),
+            Err(SimplexError::AlreadyOptimal)
+        ));
+        assert_eq!(simplex.current_point(), vec![4.0, 3.0]);
+
+        assert!(StepAction::Reset.apply(&mut simplex).is_ok());
+        assert_eq!(simplex.current_point(), vec![0.0, 0.0]);
+    }
+}
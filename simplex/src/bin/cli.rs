@@ -0,0 +1,35 @@
+//! Solves a linear program from the command line, see `simplex::cli`
+use simplex::cli::{run, CliOptions, USAGE};
+use std::io::Read;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let options = match CliOptions::from_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let input = match &options.path {
+        Some(path) => std::fs::read_to_string(path).map_err(|error| format!("cannot read {path}: {error}")),
+        None => {
+            let mut input = String::new();
+            std::io::stdin()
+                .read_to_string(&mut input)
+                .map(|_| input)
+                .map_err(|error| format!("cannot read the standard input: {error}"))
+        }
+    };
+
+    match input.and_then(|input| run(&input, &options)) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! The solver as a command line tool, for scripts and machines without a display
+//!
+//! The `cli` binary only reads its input and prints what [`run`] returns, so that the whole
+//! behavior can be tested without spawning a process
+use crate::linear_function::{Coefficient, Variable};
+use crate::{LinearProgram, PivotRule, Simplex, SimplexError};
+use itertools::Itertools;
+use serde_json::{json, Value};
+
+pub const USAGE: &str = "usage: cli [--bland] [--format text|lp|mps|json] [--steps] [FILE]
+
+Solves the program read from FILE, or from the standard input without it:
+a first `max ...` or `min ...` line followed by one constraint per line
+
+    --bland            use Bland's rule instead of Dantzig's
+    --format FORMAT    print the solution as text (the default) or json, or the
+                       program in LP or MPS format with the solution in comments
+    --steps            print every step of the simplex, not only the optimum";
+
+/// How the solution is printed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// `name = value` lines
+    #[default]
+    Text,
+    /// The program, see [`LinearProgram::to_lp`], preceded by the solution as `\` comments
+    Lp,
+    /// The program, see [`LinearProgram::to_mps`], preceded by the solution as `*` comments
+    Mps,
+    /// `{"objective": ..., "values": {...}}`, as the JSON entry point
+    Json,
+}
+
+/// The options given on the command line
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CliOptions {
+    pub rule: PivotRule,
+    pub format: OutputFormat,
+    /// Whether every step is printed
+    pub steps: bool,
+    /// The file to read, or `None` for the standard input
+    pub path: Option<String>,
+}
+
+impl CliOptions {
+    /// Reads the options from the arguments, without the name of the program
+    /// ```rust
+    /// use simplex::cli::{CliOptions, OutputFormat};
+    /// use simplex::PivotRule;
+    ///
+    /// let args = ["--bland", "--format", "json", "problem.txt"].map(String::from);
+    /// let options = CliOptions::from_args(args).unwrap();
+    /// assert_eq!(options.rule, PivotRule::Bland);
+    /// assert_eq!(options.format, OutputFormat::Json);
+    /// assert_eq!(options.path.as_deref(), Some("problem.txt"));
+    /// ```
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<CliOptions, String> {
+        let mut options = CliOptions::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bland" => options.rule = PivotRule::Bland,
+                "--steps" => options.steps = true,
+                "--format" => {
+                    options.format = match args.next().as_deref() {
+                        Some("text") => OutputFormat::Text,
+                        Some("lp") => OutputFormat::Lp,
+                        Some("mps") => OutputFormat::Mps,
+                        Some("json") => OutputFormat::Json,
+                        Some(format) => return Err(format!("unknown format `{format}`")),
+                        None => return Err("missing format after --format".to_string()),
+                    }
+                }
+                flag if flag.starts_with("--") => return Err(format!("unknown option `{flag}`")),
+                path if options.path.is_none() => options.path = Some(path.to_string()),
+                _ => return Err("only one file can be solved at once".to_string()),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Solves the program described by `input` and returns what the command prints, or the message
+/// of the error that stopped it
+/// ```rust
+/// use simplex::cli::{run, CliOptions};
+///
+/// let output = run("max x + y\n x + 2y <= 4\n x <= 3", &CliOptions::default()).unwrap();
+/// assert_eq!(output, "objective = 3.5\nx = 3\ny = 0.5\n");
+/// ```
+pub fn run(input: &str, options: &CliOptions) -> Result<String, String> {
+    let program = input.parse::<LinearProgram>().map_err(|error| error.to_string())?;
+    if program.is_unbounded() {
+        return Err(SimplexError::Unbounded.to_string());
+    }
+
+    let mut simplex = Simplex::from(program.clone());
+    simplex.solve(options.rule).map_err(|error| error.to_string())?;
+    let optimum = (simplex.current_state().objective_value(), simplex.current_values());
+
+    let mut steps = vec![];
+    if options.steps {
+        simplex.reset();
+        loop {
+            steps.push((simplex.current_state().objective_value(), simplex.current_values()));
+            if simplex.next_step(options.rule).is_err() {
+                break;
+            }
+        }
+    }
+
+    let text = || {
+        let mut output = String::new();
+        for (i, (objective, values)) in steps.iter().enumerate() {
+            let values = values.iter().map(|(var, value)| format!("{var} = {value}"));
+            output += &format!("step {i}: objective = {objective}, {}\n", values.format(", "));
+        }
+        output += &format!("objective = {}\n", optimum.0);
+        for (var, value) in optimum.1.iter() {
+            output += &format!("{var} = {value}\n");
+        }
+        output
+    };
+    let commented = |comment: &str| text().lines().map(|line| format!("{comment} {line}\n")).collect::<String>();
+
+    Ok(match options.format {
+        OutputFormat::Text => text(),
+        OutputFormat::Lp => commented("\\") + &program.to_lp(),
+        OutputFormat::Mps => commented("*") + &program.to_mps(),
+        OutputFormat::Json => {
+            let mut json = json_solution(optimum.0, &optimum.1);
+            if options.steps {
+                let steps = steps.iter().map(|(objective, values)| json_solution(*objective, values));
+                json["steps"] = steps.collect();
+            }
+            json.to_string() + "\n"
+        }
+    })
+}
+
+/// Builds the `{"objective": ..., "values": {...}}` object of a step
+fn json_solution(objective: Coefficient, values: &[(Variable, Coefficient)]) -> Value {
+    let values = values.iter().map(|(var, value)| (var.to_string(), json_number(*value)));
+    json!({ "objective": json_number(objective), "values": values.collect::<serde_json::Map<_, _>>() })
+}
+
+/// Converts a coefficient to JSON through its shortest decimal form, so that `0.1` isn't widened
+/// to `0.10000000149011612`. Infinities and NaN, which JSON lacks, give `null`
+fn json_number(value: Coefficient) -> Value {
+    format!("{value:?}").parse::<serde_json::Number>().map_or(Value::Null, Value::Number)
+}
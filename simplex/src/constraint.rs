@@ -1,18 +1,19 @@
 //! contraintes linéaire
 use crate::linear_function::LinearFunction;
-use crate::linear_function::Variable;
-use crate::linear_function::GAP_VARIABLE_IDENTIFIER;
-use crate::{LinearProgram, Simplex, SimplexError};
+use crate::linear_function::{Coefficient, Variable};
+use crate::linear_function::{GAP_VARIABLE_IDENTIFIER, ZERO_TOLERANCE};
+use crate::{LinearProgram, ObjectiveSense, PivotRule, Simplex, SimplexError, Tolerances, VariableOrder};
 use itertools::Itertools;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete::anychar;
 use nom::multi::many_till;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 
 // Variable globale
 
-#[derive(Debug, Clone, Default, PartialEq, Copy)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operator {
     #[default]
     Equal,
@@ -24,16 +25,205 @@ pub enum Operator {
 
 /// A Constraint is a linear function with an operator
 /// [linear_function] [operator] [0]
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constraint {
     pub left: LinearFunction,
     pub operator: Operator,
     pub right: LinearFunction,
+    /// An optional name given by the user (e.g. "budget"), kept along the row when pivoting
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Constraints {
     inner: Vec<Constraint>,
+    /// The constraints as they were given, before being turned into equations with gap variables
+    original: Vec<Constraint>,
+    /// Lower and upper bounds of single variables, kept apart from the constraints so that the
+    /// simplex can handle them without gap variables
+    bounds: HashMap<Variable, (Coefficient, Coefficient)>,
+    /// Variables which may be negative, each one being split into the difference of two non
+    /// negative variables when building a program, see [`free_variable_parts`]
+    #[cfg_attr(feature = "serde", serde(default))]
+    free: HashSet<Variable>,
+    /// The order of the decision variables in the programs built from those constraints
+    #[cfg_attr(feature = "serde", serde(default))]
+    variable_order: VariableOrder,
+    /// The variables which several names, as written, were normalized into by `compile`, along
+    /// with those names
+    #[cfg_attr(feature = "serde", serde(default))]
+    merged_names: Vec<(Variable, Vec<Variable>)>,
+}
+
+/// Options of [`Constraints::compile_with_options`], set with the `with_*` methods
+/// ```rust
+/// use simplex::constraint::{CompileOptions, Constraints};
+/// use simplex::SimplexError;
+///
+/// let options = CompileOptions::default().with_strict_inequalities(false);
+/// assert_eq!(
+///     Constraints::compile_with_options("x + y <= 4\n x < 3", &options),
+///     Err(SimplexError::StrictInequality(2))
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileOptions {
+    allow_strict: bool,
+    expand_chains: bool,
+    variable_order: VariableOrder,
+    reserved_names: Vec<String>,
+    trim_names: bool,
+    fold_case: bool,
+}
+
+impl Default for CompileOptions {
+    /// The options used by `compile`: strict inequalities and chained lines are accepted, the
+    /// variables are sorted alphabetically, no name is reserved and names are trimmed but keep
+    /// their case
+    fn default() -> Self {
+        CompileOptions {
+            allow_strict: true,
+            expand_chains: true,
+            variable_order: VariableOrder::Alphabetical,
+            reserved_names: vec![],
+            trim_names: true,
+            fold_case: false,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Sets whether `<` and `>` are accepted, or give `StrictInequality`
+    pub fn with_strict_inequalities(self, allow_strict: bool) -> CompileOptions {
+        CompileOptions { allow_strict, ..self }
+    }
+
+    /// Sets whether lines such as `0 <= x <= 10` or `0 <= x + y <= 10` are turned into bounds and
+    /// pairs of constraints, or are invalid
+    pub fn with_chains(self, expand_chains: bool) -> CompileOptions {
+        CompileOptions { expand_chains, ..self }
+    }
+
+    /// Sets the order of the decision variables in the programs built from the constraints
+    pub fn with_variable_order(self, variable_order: VariableOrder) -> CompileOptions {
+        CompileOptions { variable_order, ..self }
+    }
+
+    /// Sets names which the constraints can't use, giving `ReservedVariable`, e.g. the ones the
+    /// caller will add later. The names of the gap variables are always reserved
+    pub fn with_reserved_names(self, reserved_names: Vec<String>) -> CompileOptions {
+        CompileOptions { reserved_names, ..self }
+    }
+
+    /// Sets whether the whitespace around variable names is removed, so that `x ` and `x` are
+    /// the same variable. As the parser already splits the constraints on whitespace, this only
+    /// changes the reserved names in practice
+    /// ```rust
+    /// use simplex::constraint::{CompileOptions, Constraints};
+    ///
+    /// let options = CompileOptions::default().with_reserved_names(vec![" x ".to_string()]);
+    /// assert!(Constraints::compile_with_options("x <= 4", &options).is_err());
+    /// let options = options.with_trimmed_names(false);
+    /// assert!(Constraints::compile_with_options("x <= 4", &options).is_ok());
+    /// ```
+    pub fn with_trimmed_names(self, trim_names: bool) -> CompileOptions {
+        CompileOptions { trim_names, ..self }
+    }
+
+    /// Sets whether variable names are turned to lowercase, so that `X` and `x` are the same
+    /// variable. The reserved names are folded as well
+    ///
+    /// The names merged this way are reported by [`crate::LinearProgram::validate`]
+    /// ```rust
+    /// use simplex::constraint::{CompileOptions, Constraints};
+    ///
+    /// let options = CompileOptions::default().with_case_folding(true);
+    /// let constraints = Constraints::compile_with_options("X + y <= 4\n x <= 2", &options).unwrap();
+    /// let expected = Constraints::compile("x + y <= 4\n x <= 2").unwrap();
+    /// assert_eq!(constraints.original_constraints(), expected.original_constraints());
+    /// assert_eq!(constraints.merged_variables(), [("x".parse().unwrap(), vec!["X".parse().unwrap(), "x".parse().unwrap()])]);
+    /// ```
+    pub fn with_case_folding(self, fold_case: bool) -> CompileOptions {
+        CompileOptions { fold_case, ..self }
+    }
+
+    /// Returns the name `name` stands for once normalized
+    fn normalize(&self, name: &str) -> Variable {
+        let name = if self.trim_names { name.trim() } else { name };
+        Variable::unchecked(if self.fold_case { name.to_lowercase() } else { name.to_string() })
+    }
+}
+
+/// Returns the name of the level which `maximize_min` and `minimize_max` optimize, reserved like
+/// the names of the gap variables
+fn level_variable() -> Variable {
+    Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}t"))
+}
+
+/// Returns the names of the slacks loosening the `i`th constraint from below and from above in
+/// `minimal_relaxation`, reserved like the names of the gap variables
+fn relaxation_variables(i: usize) -> (Variable, Variable) {
+    (
+        Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}u{i}")),
+        Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}o{i}")),
+    )
+}
+
+/// Returns the names of the non negative variables whose difference stands for the free
+/// variable `var` in the tableau
+pub(crate) fn free_variable_parts(var: &str) -> (Variable, Variable) {
+    (Variable::unchecked(format!("{var}⁺")), Variable::unchecked(format!("{var}⁻")))
+}
+
+/// Why a row of a tableau is invalid, see [`Constraints::validity_report`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowViolation {
+    /// The row is an inequality
+    NotAnEquation { row: usize },
+    /// The left side of the row differs from a single variable with coefficient 1, its largest
+    /// difference being `residual`
+    NotBasic { row: usize, residual: Coefficient },
+    /// The basic variable of the row is negative at the current basic solution, i.e. the
+    /// constraint it measures the slack of is violated by `amount`
+    Violated { row: usize, amount: Coefficient },
+}
+
+/// Everything preventing a tableau from being a feasible basic solution
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidityReport {
+    pub violations: Vec<RowViolation>,
+}
+
+impl ValidityReport {
+    /// Returns true if the tableau is valid
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Returns true if the tableau can be read as a basic solution, however infeasible, as on
+    /// the first step of phase one
+    pub fn is_basic(&self) -> bool {
+        self.violations
+            .iter()
+            .all(|violation| matches!(violation, RowViolation::Violated { .. }))
+    }
+}
+
+impl std::fmt::Display for ValidityReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for violation in &self.violations {
+            match violation {
+                RowViolation::NotAnEquation { row } => writeln!(f, "row {row} is not an equation")?,
+                RowViolation::NotBasic { row, residual } => {
+                    writeln!(f, "row {row} is {residual} away from having a single basic variable")?
+                }
+                RowViolation::Violated { row, amount } => writeln!(f, "row {row} is violated by {amount}")?,
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Operator {
@@ -52,6 +242,40 @@ impl Operator {
             Operator::GreaterEqual => Operator::Less,
         }
     }
+
+    /// Returns true if `lhs [operator] rhs` holds up to `tolerance`: non strict comparisons and
+    /// equality accept values up to `tolerance` on the wrong side, while strict comparisons need
+    /// the values to be more than `tolerance` apart
+    /// ```rust
+    /// use simplex::constraint::Operator;
+    /// assert!(Operator::LessEqual.satisfied(1.05, 1.0, 0.1));
+    /// assert!(!Operator::Less.satisfied(0.95, 1.0, 0.1));
+    /// ```
+    pub fn satisfied(&self, lhs: Coefficient, rhs: Coefficient, tolerance: Coefficient) -> bool {
+        match self {
+            Operator::Equal => (lhs - rhs).abs() <= tolerance,
+            Operator::Less => lhs < rhs - tolerance,
+            Operator::Greater => lhs > rhs + tolerance,
+            Operator::LessEqual => lhs <= rhs + tolerance,
+            Operator::GreaterEqual => lhs >= rhs - tolerance,
+        }
+    }
+
+    /// Returns the operator obtained when swapping both sides of a constraint
+    /// ```rust
+    /// use simplex::constraint::Operator;
+    /// assert_eq!(Operator::Less.reverse(), Operator::Greater);
+    /// assert_eq!(Operator::GreaterEqual.reverse(), Operator::LessEqual);
+    /// ```
+    pub fn reverse(&self) -> Operator {
+        match self {
+            Operator::Equal => Operator::Equal,
+            Operator::Less => Operator::Greater,
+            Operator::Greater => Operator::Less,
+            Operator::LessEqual => Operator::GreaterEqual,
+            Operator::GreaterEqual => Operator::LessEqual,
+        }
+    }
 }
 
 impl Constraint {
@@ -62,13 +286,14 @@ impl Constraint {
     /// use simplex::constraint::{Constraint, Operator};
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let lhs = LinearFunction::new(0f32, HashMap::from([(String::from("x"), 32f32)]));
+    /// let lhs = LinearFunction::new(0f32, HashMap::from([("x".parse().unwrap(), 32f32)]));
     /// let rhs = LinearFunction::new(0f32, HashMap::new());
     /// let op = Operator::LessEqual;
     /// let expected = Constraint {
-    ///    left: LinearFunction::new(0f32, HashMap::from([(String::from("x"), 32f32)])),
+    ///    left: LinearFunction::new(0f32, HashMap::from([("x".parse().unwrap(), 32f32)])),
     ///    operator: Operator::LessEqual,
     ///    right: LinearFunction::new(0f32, HashMap::new()),
+    ///    label: None,
     /// };
     /// let n = Constraint::new(lhs, op, rhs);
     /// assert_eq!(n, expected)
@@ -78,6 +303,88 @@ impl Constraint {
             left,
             operator,
             right,
+            label: None,
+        }
+    }
+
+    /// Gives a label to this constraint
+    pub fn with_label(self, label: impl Into<String>) -> Constraint {
+        Constraint {
+            label: Some(label.into()),
+            ..self
+        }
+    }
+
+    /// Returns this constraint as `function [operator] 0`, where the operator is either `=`, `<`
+    /// or `<=`, and the function is scaled so that its first non-zero coefficient is 1 in absolute
+    /// value (or exactly 1 for an equation)
+    pub(crate) fn normalized(&self) -> (LinearFunction, Operator) {
+        let (mut function, operator) = match self.operator {
+            Operator::Greater | Operator::GreaterEqual => {
+                (self.right.clone() - self.left.clone(), self.operator.reverse())
+            }
+            _ => (self.left.clone() - self.right.clone(), self.operator),
+        };
+
+        let scale = function
+            .var_iter()
+            .sorted()
+            .next()
+            .map(|var| function[var])
+            .unwrap_or(function.constant);
+        if scale != 0.0 {
+            function /= if operator == Operator::Equal { scale } else { scale.abs() };
+        }
+        (function, operator)
+    }
+
+    /// Returns true if both constraints are equal once normalized, up to `epsilon`
+    pub fn approx_eq(&self, other: &Constraint, epsilon: f32) -> bool {
+        let (self_function, self_operator) = self.normalized();
+        let (other_function, other_operator) = other.normalized();
+        self_operator == other_operator && self_function.approx_eq(&other_function, epsilon)
+    }
+
+    /// Multiplies both sides of the constraint by `factor`, reversing the operator if `factor`
+    /// is negative so that the constraint still holds for the same points
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    ///
+    /// let c = "x - y <= 2".parse::<Constraint>().unwrap();
+    /// assert_eq!(c.scale_by(-2.0).to_string(), "-2.0x + 2.0y >= -4.0");
+    /// ```
+    pub fn scale_by(&self, factor: f32) -> Constraint {
+        Constraint {
+            left: self.left.clone() * factor,
+            operator: if factor < 0.0 { self.operator.reverse() } else { self.operator },
+            right: self.right.clone() * factor,
+            label: self.label.clone(),
+        }
+    }
+
+    /// Returns this constraint as `terms [operator] constant`, with every variable on the left
+    /// and a non negative constant on the right: both sides are multiplied by -1, reversing the
+    /// operator, if the constant would be negative
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    ///
+    /// let c = "x - y >= -3".parse::<Constraint>().unwrap();
+    /// assert_eq!(c.make_rhs_nonnegative().to_string(), "-x + y <= 3.0");
+    /// ```
+    pub fn make_rhs_nonnegative(&self) -> Constraint {
+        let mut terms = self.left.clone() - self.right.clone();
+        let constant = -terms.constant;
+        terms.constant = 0.0;
+        let constraint = Constraint {
+            left: terms,
+            operator: self.operator,
+            right: LinearFunction::constant(constant),
+            label: self.label.clone(),
+        };
+        if constant < 0.0 {
+            constraint.scale_by(-1.0)
+        } else {
+            constraint
         }
     }
 
@@ -89,8 +396,92 @@ impl Constraint {
         }
     }
 
-    pub fn is_valid_linear_program(&self) -> bool {
-        self.left.is_one_normalized_var() && self.operator == Operator::Equal
+    /// Returns true if the constraint holds for the given valuation
+    /// (variables absent from the valuation are considered to be 0)
+    pub fn is_satisfied_at(&self, valuation: &HashMap<Variable, Coefficient>) -> bool {
+        self.holds_at(valuation, 0.0)
+    }
+
+    /// Returns the signed slack of the constraint at `point`, i.e. how far it is from binding:
+    /// `right - left` for `<`, `<=` and `=`, and `left - right` for `>` and `>=`
+    ///
+    /// An inequality is satisfied when its residual is positive, or zero if it isn't strict, and
+    /// an equation when its residual is zero (variables absent from `point` are considered to be 0)
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    /// use std::collections::HashMap;
+    ///
+    /// let point = HashMap::from([("x".parse().unwrap(), 1.0), ("y".parse().unwrap(), 2.0)]);
+    /// assert_eq!("x + y <= 4".parse::<Constraint>().unwrap().residual(&point), 1.0);
+    /// assert_eq!("x + y >= 4".parse::<Constraint>().unwrap().residual(&point), -1.0);
+    /// ```
+    pub fn residual(&self, point: &HashMap<Variable, Coefficient>) -> Coefficient {
+        let (left, right) = (self.left.apply(point), self.right.apply(point));
+        match self.operator {
+            Operator::Less | Operator::LessEqual | Operator::Equal => right - left,
+            Operator::Greater | Operator::GreaterEqual => left - right,
+        }
+    }
+
+    /// Returns true if the constraint holds at `point` up to `tolerance`, as
+    /// [`Operator::satisfied`] does
+    fn holds_at(&self, point: &HashMap<Variable, Coefficient>, tolerance: Coefficient) -> bool {
+        let residual = self.residual(point);
+        match self.operator {
+            Operator::Equal => residual.abs() <= tolerance,
+            Operator::Less | Operator::Greater => residual > tolerance,
+            Operator::LessEqual | Operator::GreaterEqual => residual >= -tolerance,
+        }
+    }
+
+    /// Returns true if this constraint is a row of a tableau, i.e. `x = function`, up to the
+    /// `zero` tolerance
+    pub fn is_valid_linear_program(&self, tolerances: &Tolerances) -> bool {
+        self.operator == Operator::Equal && self.basic_residual() <= tolerances.zero
+    }
+
+    /// Returns how far the left side is from the closest single variable with coefficient 1, as
+    /// the largest difference between their coefficients and constants
+    fn basic_residual(&self) -> Coefficient {
+        let basic = self
+            .left
+            .var_iter()
+            .min_by(|a, b| (self.left[a] - 1.0).abs().total_cmp(&(self.left[b] - 1.0).abs()));
+        let basic_residual = basic.map_or(1.0, |var| (self.left[var] - 1.0).abs());
+        self.left
+            .var_iter()
+            .filter(|var| Some(*var) != basic)
+            .map(|var| self.left[var].abs())
+            .fold(basic_residual.max(self.left.constant.abs()), Coefficient::max)
+    }
+
+    /// Returns how much `var` can increase before the basic variable of this row reaches 0, i.e.
+    /// `constant / -coefficient`, or `None` if the coefficient of `var` isn't below `-zero` so
+    /// that the row doesn't bound it
+    pub(crate) fn ratio(&self, var: &str, zero: Coefficient) -> Option<Coefficient> {
+        let coefficient = self.right.coefficient_of(var);
+        (coefficient < -zero).then(|| self.right.constant / -coefficient)
+    }
+
+    /// Returns the coefficients `(a, b, c)` of the line `a * x + b * y = c` bounding the
+    /// constraint in the plane formed by `x_var` and `y_var`, or `None` if the constraint involves
+    /// other variables or neither of them
+    ///
+    /// The line can be clipped to the plotted area with [`crate::geometry::constraint_line`]
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    ///
+    /// let constraint = "2x + 3y <= 6".parse::<Constraint>().unwrap();
+    /// assert_eq!(constraint.as_line_2d("x", "y"), Some((2.0, 3.0, 6.0)));
+    /// assert_eq!(constraint.as_line_2d("x", "z"), None);
+    /// ```
+    pub fn as_line_2d(&self, x_var: &str, y_var: &str) -> Option<(f32, f32, f32)> {
+        let function = self.left.clone() - self.right.clone();
+        if function.var_iter().any(|var| var != x_var && var != y_var && function[var].abs() > ZERO_TOLERANCE) {
+            return None;
+        }
+        let (a, b) = (function.coefficient_of(x_var), function.coefficient_of(y_var));
+        (!function.is_constant(ZERO_TOLERANCE)).then_some((a, b, -function.constant))
     }
 
     pub fn non_gap_variables(&self) -> Vec<Variable> {
@@ -100,20 +491,224 @@ impl Constraint {
         }
         var_set.into_iter().collect()
     }
+
+    /// Returns the variable of a constraint on a single variable which no non negative value
+    /// satisfies, such as `x <= -5`, or `None` if there is no such variable
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    ///
+    /// assert_eq!("2x + 3 <= 1".parse::<Constraint>().unwrap().forces_negative(), Some("x".parse().unwrap()));
+    /// assert_eq!("x >= -5".parse::<Constraint>().unwrap().forces_negative(), None);
+    /// assert_eq!("x + y <= -5".parse::<Constraint>().unwrap().forces_negative(), None);
+    /// ```
+    pub fn forces_negative(&self) -> Option<Variable> {
+        let function = (self.left.clone() - self.right.clone()).simplified();
+        let var = function.var_iter().exactly_one().ok()?.clone();
+        // The constraint is linear in `var`, so it holds for some non negative value if and only
+        // if it holds at 0, at its root or past its root
+        let root = (-function.constant / function[&var]).max(0.0);
+        let holds = [0.0, root, root + 1.0]
+            .into_iter()
+            .any(|value| self.holds_at(&HashMap::from([(var.clone(), value)]), ZERO_TOLERANCE));
+        (!holds).then_some(var)
+    }
 }
 
 impl Constraints {
     /// Create a new vector of constraints
     pub fn new() -> Constraints {
-        Constraints { inner: Vec::new() }
+        Constraints {
+            inner: Vec::new(),
+            original: Vec::new(),
+            bounds: HashMap::new(),
+            free: HashSet::new(),
+            variable_order: VariableOrder::default(),
+            merged_names: Vec::new(),
+        }
     }
 
     pub fn maximize(&self, to_maximize: &LinearFunction) -> Result<Simplex, SimplexError> {
-        let program = LinearProgram {
-            linear_function: to_maximize.clone(),
-            constraints: self.clone()
+        self.optimize(to_maximize, ObjectiveSense::Max)
+    }
+
+    /// Creates a simplex minimizing the given function under those constraints
+    pub fn minimize(&self, to_minimize: &LinearFunction) -> Result<Simplex, SimplexError> {
+        self.optimize(to_minimize, ObjectiveSense::Min)
+    }
+
+    /// Solves the program maximizing `objective` under those constraints with Bland's rule, and
+    /// returns its optimal value
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y <= 4\n y <= 3").unwrap();
+    /// assert_eq!(constraints.max_value(&"x + 2y".parse().unwrap()).unwrap(), 7.0);
+    /// ```
+    pub fn max_value(&self, objective: &LinearFunction) -> Result<Coefficient, SimplexError> {
+        Self::optimal_value(self.maximize(objective)?)
+    }
+
+    /// Solves the program minimizing `objective` under those constraints with Bland's rule, and
+    /// returns its optimal value
+    pub fn min_value(&self, objective: &LinearFunction) -> Result<Coefficient, SimplexError> {
+        Self::optimal_value(self.minimize(objective)?)
+    }
+
+    /// Creates a simplex maximizing the smallest of `exprs` under those constraints, i.e. the
+    /// level `t` which no expression falls below, with `t <= expr` for each expression
+    ///
+    /// The level is a free variable named with the prefix of the gap variables, so it can't
+    /// collide with a variable of the constraints and isn't listed among the decision variables
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    /// use simplex::PivotRule;
+    ///
+    /// let constraints = Constraints::compile("x <= 10").unwrap();
+    /// let exprs = ["x".parse().unwrap(), "4 - x".parse().unwrap()];
+    /// let mut simplex = constraints.maximize_min(&exprs).unwrap();
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.current_state().objective_value(), 2.0);
+    /// assert_eq!(simplex.current_values(), vec![("x".parse().unwrap(), 2.0)]);
+    /// ```
+    pub fn maximize_min(&self, exprs: &[LinearFunction]) -> Result<Simplex, SimplexError> {
+        self.with_level(exprs, Operator::LessEqual)
+            .maximize(&LinearFunction::single_variable(level_variable()))
+    }
+
+    /// Creates a simplex minimizing the largest of `exprs` under those constraints, like
+    /// [`Constraints::maximize_min`] with `t >= expr` for each expression
+    pub fn minimize_max(&self, exprs: &[LinearFunction]) -> Result<Simplex, SimplexError> {
+        self.with_level(exprs, Operator::GreaterEqual)
+            .minimize(&LinearFunction::single_variable(level_variable()))
+    }
+
+    /// Returns those constraints along with `t [operator] expr` for each of `exprs`, where `t` is
+    /// the free variable given by `level_variable`
+    fn with_level(&self, exprs: &[LinearFunction], operator: Operator) -> Constraints {
+        let mut constraints = self.clone();
+        for expr in exprs {
+            constraints.add_constraint(Constraint::new(
+                LinearFunction::single_variable(level_variable()),
+                operator,
+                expr.clone(),
+            ));
+        }
+        constraints.declare_free(&level_variable());
+        constraints
+    }
+
+    fn optimal_value(mut simplex: Simplex) -> Result<Coefficient, SimplexError> {
+        simplex.solve(PivotRule::Bland)?;
+        Ok(simplex.current_state().objective_value())
+    }
+
+    /// Returns how much each constraint must be loosened, along with its index among the original
+    /// constraints, for the constraints to become feasible while loosening them by as little as
+    /// possible in total. The constraints which can stay as they are aren't listed, so the result
+    /// is empty if the constraints are already feasible
+    ///
+    /// This solves the elastic program where each constraint gets a penalized slack: `left <=
+    /// right` becomes `left - over <= right`, `left >= right` becomes `left + under >= right` and
+    /// an equation gets both, the sum of the slacks being minimized. The bounds aren't loosened,
+    /// so conflicting bounds still give `Infeasible`
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y <= 2\n x >= 3\n y <= 4").unwrap();
+    /// assert_eq!(constraints.minimal_relaxation(), Ok(vec![(0, 1.0)]));
+    /// assert_eq!(Constraints::compile("x <= 2").unwrap().minimal_relaxation(), Ok(vec![]));
+    /// ```
+    pub fn minimal_relaxation(&self) -> Result<Vec<(usize, Coefficient)>, SimplexError> {
+        let mut objective = LinearFunction::zero();
+        let elastic = self
+            .original
+            .iter()
+            .enumerate()
+            .map(|(i, constraint)| {
+                let (under, over) = relaxation_variables(i);
+                let mut left = constraint.left.clone();
+                if !matches!(constraint.operator, Operator::Less | Operator::LessEqual) {
+                    left += LinearFunction::single_variable(under.clone());
+                    objective += LinearFunction::single_variable(under);
+                }
+                if !matches!(constraint.operator, Operator::Greater | Operator::GreaterEqual) {
+                    left -= LinearFunction::single_variable(over.clone());
+                    objective += LinearFunction::single_variable(over);
+                }
+                Constraint { left, ..constraint.clone() }
+            })
+            .collect();
+        let mut relaxed = self.clone();
+        relaxed.rebuild_from(elastic);
+
+        let mut simplex = relaxed.minimize(&objective)?;
+        simplex.solve(PivotRule::Bland)?;
+        let state = simplex.current_state();
+        let valuation = state.valuation();
+        let value = |var: &Variable| valuation.get(var).copied().unwrap_or(0.0);
+        Ok((0..self.original.len())
+            .map(|i| {
+                let (under, over) = relaxation_variables(i);
+                (i, value(&under) + value(&over))
+            })
+            .filter(|(_, amount)| *amount > state.tolerances.zero)
+            .collect())
+    }
+
+    /// Returns some point satisfying all the constraints, found by phase one of the simplex
+    /// without any objective, or `Infeasible` if there is none
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y >= 2\n x <= 1").unwrap();
+    /// let point = constraints.find_feasible_point().unwrap();
+    /// assert!(point["x"] <= 1.0 && point["x"] + point["y"] >= 2.0);
+    /// ```
+    pub fn find_feasible_point(&self) -> Result<HashMap<Variable, Coefficient>, SimplexError> {
+        let mut simplex = Simplex::from(self.to_program(&LinearFunction::zero(), ObjectiveSense::Max));
+        simplex.solve(PivotRule::Bland)?;
+        Ok(simplex.current_values().into_iter().collect())
+    }
+
+    /// Returns the range of each decision variable over the feasible region, in alphabetical
+    /// order of the variables, or `None` if the region is empty
+    ///
+    /// Each end of a range is found by solving the program optimizing this variable, and is
+    /// infinite if the region is unbounded in that direction
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y <= 4\n y <= 3").unwrap();
+    /// assert_eq!(constraints.bounding_box(), Some(vec![(0.0, 4.0), (0.0, 3.0)]));
+    /// ```
+    pub fn bounding_box(&self) -> Option<Vec<(Coefficient, Coefficient)>> {
+        let optimum = |value: Result<Coefficient, SimplexError>, unbounded: Coefficient| {
+            match value {
+                Ok(value) => Some(value),
+                Err(SimplexError::Unbounded) => Some(unbounded),
+                Err(_) => None,
+            }
         };
 
+        self.non_gap_variables()
+            .into_iter()
+            .sorted()
+            .map(|var| {
+                let var = LinearFunction::single_variable(var);
+                let min = optimum(self.min_value(&var), Coefficient::NEG_INFINITY)?;
+                let max = optimum(self.max_value(&var), Coefficient::INFINITY)?;
+                Some((min, max))
+            })
+            .collect()
+    }
+
+    fn optimize(&self, objective: &LinearFunction, sense: ObjectiveSense) -> Result<Simplex, SimplexError> {
+        // Phase one would find it out, but only after building and solving the auxiliary program
+        if self.negativity_conflict().is_some() {
+            return Err(SimplexError::Infeasible);
+        }
+        let program = self.to_program(objective, sense);
+
         if program.is_unbounded() {
             Err(SimplexError::Unbounded)
         } else {
@@ -121,10 +716,114 @@ impl Constraints {
         }
     }
 
+    /// Builds the program optimizing `objective` in the given sense under those constraints, its
+    /// tableau maximizing the objective turned by [`ObjectiveSense::to_maximized`]
+    pub(crate) fn to_program(&self, objective: &LinearFunction, sense: ObjectiveSense) -> LinearProgram {
+        let mut program = LinearProgram {
+            linear_function: sense.to_maximized(objective),
+            constraints: self.clone(),
+            sense,
+            variable_order: self.variable_order,
+            ..Default::default()
+        };
+        for (var, (lower, upper)) in self.bounds.iter() {
+            program.add_bound(var, *lower, *upper);
+        }
+        for var in self.free.iter() {
+            let (positive, negative) = free_variable_parts(var);
+            let difference = LinearFunction::single_variable(positive) - LinearFunction::single_variable(negative);
+            program.constraints.replace_variable_with(var, &difference);
+            program.linear_function.replace(var, &difference);
+        }
+        program
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
         self.inner.iter()
     }
 
+    /// Returns the `i`th row of the tableau, or `None` if there is no such row
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x <= 4\n y <= 3").unwrap();
+    /// assert_eq!(constraints.get(1).unwrap().right, "3 - y".parse().unwrap());
+    /// assert!(constraints.get(2).is_none());
+    /// ```
+    pub fn get(&self, i: usize) -> Option<&Constraint> {
+        self.inner.get(i)
+    }
+
+    /// Returns a mutable reference to the `i`th row of the tableau, or `None` if there is no such row
+    pub fn get_mut(&mut self, i: usize) -> Option<&mut Constraint> {
+        self.inner.get_mut(i)
+    }
+
+    /// Returns the number of rows of the tableau
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the constraints as they were added, before the introduction of gap variables
+    pub fn original_constraints(&self) -> &[Constraint] {
+        &self.original
+    }
+
+    /// Returns the variables declared free, e.g. by `x free`
+    pub fn free_variables(&self) -> &HashSet<Variable> {
+        &self.free
+    }
+
+    /// Lets `var` take negative values, instead of being implicitly non negative
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let mut constraints = Constraints::compile("x <= -5").unwrap();
+    /// assert!(constraints.max_value(&"x".parse().unwrap()).is_err());
+    /// constraints.declare_free(&"x".parse().unwrap());
+    /// assert_eq!(constraints.max_value(&"x".parse().unwrap()), Ok(-5.0));
+    /// ```
+    pub fn declare_free(&mut self, var: &Variable) {
+        self.free.insert(var.clone());
+    }
+
+    /// Returns the first variable which a constraint on that variable alone forces below 0, such
+    /// as `x <= -5`, which makes the program infeasible since the variable is implicitly non
+    /// negative. Bounded and free variables may be negative, hence are ignored
+    ///
+    /// Optimizing such constraints fails with `Infeasible` right away, while
+    /// [`LinearProgram::validate`] names the variable
+    pub fn negativity_conflict(&self) -> Option<Variable> {
+        self.original
+            .iter()
+            .filter_map(Constraint::forces_negative)
+            .find(|var| !self.bounds.contains_key(var) && !self.free.contains(var))
+    }
+
+    /// Returns the bounds given to single variables, e.g. by `0 <= x <= 10`
+    pub fn bounds(&self) -> &HashMap<Variable, (Coefficient, Coefficient)> {
+        &self.bounds
+    }
+
+    /// Bounds `var` between `lower` and `upper`. If `var` was already bounded, it is then bounded
+    /// by the intersection of both ranges
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let mut constraints = Constraints::compile("0 <= x <= 10").unwrap();
+    /// constraints.add_bound(&"x".parse().unwrap(), 2.0, 12.0);
+    /// assert_eq!(constraints.bounds()["x"], (2.0, 10.0));
+    /// assert_eq!(constraints.gap_variables_count(), 0);
+    /// ```
+    pub fn add_bound(&mut self, var: &Variable, lower: Coefficient, upper: Coefficient) {
+        let bound = self.bounds.entry(var.clone()).or_insert((lower, upper));
+        *bound = (bound.0.max(lower), bound.1.min(upper));
+    }
+
     /// Add a constraint to the list of constraints
     /// The constraint added is in this form :
     ///
@@ -137,27 +836,30 @@ impl Constraints {
     ///
     /// let mut constraints = Constraints::new();
     /// let constraint = Constraint {
-    ///   left: LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)])),
+    ///   left: LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)])),
     ///   operator: Operator::LessEqual,
-    ///   right: LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]))
+    ///   right: LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)])),
+    ///   label: None,
     /// };
     /// constraints.add_constraint(constraint);
     /// assert_eq!(constraints.gap_variables_count(), 1);
     /// assert_eq!(constraints[0].operator, Operator::Equal);
-    /// assert_eq!(constraints[0].right, LinearFunction::new(-35f32, HashMap::from([(String::from("x"), -32f32), (String::from("y"), 12f32), (String::from("z"), 10f32)])));
+    /// assert_eq!(constraints[0].right, LinearFunction::new(-35f32, HashMap::from([("x".parse().unwrap(), -32f32), ("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 10f32)])));
     /// ```
     pub fn add_constraint(&mut self, constraint: Constraint) {
         let next_gap_var = || {
-            LinearFunction::single_variable(format!(
+            LinearFunction::single_variable(Variable::unchecked(format!(
                 "{GAP_VARIABLE_IDENTIFIER}{}",
                 self.gap_variables_count()
-            ))
+            )))
         };
 
+        let original = constraint.clone();
         let Constraint {
             left,
             operator,
             right,
+            label,
         } = constraint;
         match operator {
             Operator::LessEqual | Operator::Less => {
@@ -165,6 +867,7 @@ impl Constraints {
                     left: next_gap_var(),
                     operator: Operator::Equal,
                     right: right - left,
+                    label,
                 };
                 self.inner.push(constraint);
             }
@@ -173,56 +876,499 @@ impl Constraints {
                     left: next_gap_var(),
                     operator: Operator::Equal,
                     right: left - right,
+                    label,
                 };
                 self.inner.push(constraint);
             }
             Operator::Equal => {
+                // An equation is split into `left <= right` and `left >= right`, whose gap
+                // variables are both 0 once the origin has been left by phase one
+                let gap1 = next_gap_var();
+                let gap2 = LinearFunction::single_variable(Variable::unchecked(format!(
+                    "{GAP_VARIABLE_IDENTIFIER}{}",
+                    self.gap_variables_count() + 1
+                )));
                 let constraint1 = Constraint {
-                    left: next_gap_var(),
+                    left: gap1,
                     operator: Operator::Equal,
                     right: right.clone() - left.clone(),
+                    label: label.clone(),
                 };
                 let constraint2 = Constraint {
-                    left: next_gap_var(),
+                    left: gap2,
                     operator: Operator::Equal,
-                    right: right - left,
+                    right: left - right,
+                    label,
                 };
                 self.inner.push(constraint1);
                 self.inner.push(constraint2);
             }
         }
+        self.original.push(original);
+    }
+
+    /// Removes the constraints implied by another one, returning how many were removed
+    ///
+    /// Only parallel constraints are compared for now: `x <= 10` is removed when `x <= 5`, or
+    /// `2x <= 8`, also appears. Constraints made redundant by a combination of several others are
+    /// kept. The tableau is rebuilt from the remaining constraints, hence this should be called
+    /// before solving
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let mut constraints = Constraints::compile("x <= 10\n x + y <= 4\n 2x <= 8").unwrap();
+    /// assert_eq!(constraints.remove_redundant(), 1);
+    /// assert_eq!(constraints, Constraints::compile("x + y <= 4\n 2x <= 8").unwrap());
+    /// ```
+    pub fn remove_redundant(&mut self) -> usize {
+        // Every inequality as `function [<|<=] bound`, with a normalized function
+        let bounds = self
+            .original
+            .iter()
+            .map(|constraint| {
+                let (mut function, operator) = constraint.normalized();
+                let bound = -function.constant;
+                function.constant = 0.0;
+                (function, operator, bound)
+            })
+            .collect::<Vec<_>>();
+
+        // A constraint is dominated by a parallel one if the latter has a smaller bound, or the
+        // same bound with a strict operator. When both are identical, the first one is kept
+        let is_dominated = |i: usize| {
+            let (function, operator, bound) = &bounds[i];
+            *operator != Operator::Equal
+                && bounds.iter().enumerate().any(|(j, (other, other_operator, other_bound))| {
+                    j != i
+                        && *other_operator != Operator::Equal
+                        && other == function
+                        && (other_bound < bound
+                            || other_bound == bound
+                                && (*other_operator == Operator::Less || *operator == Operator::LessEqual)
+                                && (other_operator != operator || j < i))
+                })
+        };
+
+        let kept = (0..self.original.len())
+            .filter(|i| !is_dominated(*i))
+            .map(|i| self.original[i].clone())
+            .collect::<Vec<_>>();
+        let removed = self.original.len() - kept.len();
+
+        if removed > 0 {
+            self.rebuild_from(kept);
+        }
+        removed
+    }
+
+    /// Rewrites every constraint with a non negative right-hand side, see
+    /// [`Constraint::make_rhs_nonnegative`]
+    ///
+    /// The rows of the tableau describe the same gap variables afterwards, but the tableau is
+    /// rebuilt, hence this should be called before solving
+    pub fn normalize_rhs_nonnegative(&mut self) {
+        let normalized = self.original.iter().map(Constraint::make_rhs_nonnegative).collect();
+        self.rebuild_from(normalized);
+    }
+
+    /// Multiplies every constraint by the least common multiple of the denominators of its
+    /// coefficients, so that `0.5x + 0.25y <= 1` becomes `2x + y <= 4`. Constraints with a
+    /// coefficient that isn't a simple fraction are left as is
+    ///
+    /// The tableau is rebuilt from the scaled constraints, hence this should be called before
+    /// solving
+    pub fn scale_to_integers(&mut self) {
+        let scaled = self
+            .original
+            .iter()
+            .map(|constraint| {
+                let coefficients = [&constraint.left, &constraint.right]
+                    .into_iter()
+                    .flat_map(|f| f.var_iter().map(|var| f[var]).chain([f.constant]))
+                    .collect::<Vec<_>>();
+                let factor = coefficients
+                    .iter()
+                    .try_fold(1, |acc, c| denominator(*c).map(|d| lcm(acc, d)));
+                match factor {
+                    Some(factor) if factor > 1 => constraint.scale_by(factor as f32),
+                    _ => constraint.clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.rebuild_from(scaled);
+    }
+
+    /// Removes the original constraint at `index` and returns it, rebuilding the tableau from the
+    /// remaining constraints
+    ///
+    /// This is only possible before any pivot, as the other rows of a pivoted tableau may depend
+    /// on the gap variables of the removed one: a `Simplex` using those constraints must be
+    /// started over, and solved again as removing a binding constraint can improve the optimum
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let mut constraints = Constraints::compile("x <= 2\n x + y <= 4").unwrap();
+    /// assert_eq!(constraints.remove_constraint(0).unwrap().to_string(), "x <= 2.0");
+    /// assert_eq!(constraints, Constraints::compile("x + y <= 4").unwrap());
+    /// ```
+    pub fn remove_constraint(&mut self, index: usize) -> Result<Constraint, SimplexError> {
+        if index >= self.original.len() {
+            return Err(SimplexError::NoSuchConstraint(index));
+        }
+        if self.is_pivoted() {
+            return Err(SimplexError::PivotedTableau);
+        }
+
+        let mut remaining = self.original.clone();
+        let removed = remaining.remove(index);
+        self.rebuild_from(remaining);
+        Ok(removed)
+    }
+
+    /// Returns true if some pivot was performed on the tableau: before any pivot, the gap
+    /// variable of each row is its basic variable
+    pub(crate) fn is_pivoted(&self) -> bool {
+        self.inner.iter().enumerate().any(|(i, c)| {
+            c.left.name_single_variable() != Some(Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}{i}")))
+        })
+    }
+
+    /// Substitutes `factor * var` for `var` in the tableau and in the original constraints
+    pub(crate) fn scale_variable(&mut self, var: &Variable, factor: Coefficient) {
+        for constraint in self.inner.iter_mut().chain(self.original.iter_mut()) {
+            for function in [&mut constraint.left, &mut constraint.right] {
+                if function.contains(var) {
+                    function[var] *= factor;
+                }
+            }
+        }
+    }
+
+    /// Appends the constraints and bounds of `other`, rebuilding the tableau so that every row
+    /// gets its own gap variables. The bounds of a variable bounded in both sets are intersected
+    ///
+    /// Variables are matched by name: a variable of `other` standing for something else than the
+    /// variable of the same name in this set must be renamed first, see
+    /// [`Constraints::rename_variable`]. Pivoted sets can't be merged, as their tableaux would be
+    /// lost, and give `PivotedTableau`
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let mut constraints = Constraints::compile("x + y <= 4").unwrap();
+    /// constraints.merge(Constraints::compile("y + z <= 3\n 0 <= z <= 1").unwrap()).unwrap();
+    /// assert_eq!(constraints, Constraints::compile("x + y <= 4\n y + z <= 3\n 0 <= z <= 1").unwrap());
+    /// ```
+    pub fn merge(&mut self, other: Constraints) -> Result<(), SimplexError> {
+        if self.is_pivoted() || other.is_pivoted() {
+            return Err(SimplexError::PivotedTableau);
+        }
+        let mut merged = self.original.clone();
+        merged.extend(other.original);
+        self.rebuild_from(merged);
+        for (var, (lower, upper)) in other.bounds {
+            self.add_bound(&var, lower, upper);
+        }
+        self.free.extend(other.free);
+        self.merged_names.extend(other.merged_names);
+        Ok(())
+    }
+
+    /// Renames `from` to `to` in every constraint and bound, merging it with `to` if it already
+    /// appears, see [`LinearFunction::rename_variable`]
+    ///
+    /// When both variables are bounded, the renamed one gets the intersection of their bounds.
+    /// The tableau is rebuilt from the renamed original constraints, hence pivoted sets give
+    /// `PivotedTableau`
+    pub fn rename_variable(&mut self, from: &str, to: &str) -> Result<(), SimplexError> {
+        if from.starts_with(GAP_VARIABLE_IDENTIFIER) {
+            return Err(SimplexError::ReservedVariable(Variable::unchecked(from)));
+        }
+        let to = Variable::new(to)?;
+        if self.is_pivoted() {
+            return Err(SimplexError::PivotedTableau);
+        }
+        let mut original = std::mem::take(&mut self.original);
+        for constraint in original.iter_mut() {
+            constraint.left.rename_variable(from, &to)?;
+            constraint.right.rename_variable(from, &to)?;
+        }
+        self.rebuild_from(original);
+        if let Some((lower, upper)) = self.bounds.remove(from) {
+            let (to_lower, to_upper) = self
+                .bounds
+                .get(&to)
+                .copied()
+                .unwrap_or((Coefficient::NEG_INFINITY, Coefficient::INFINITY));
+            self.bounds
+                .insert(to.clone(), (lower.max(to_lower), upper.min(to_upper)));
+        }
+        if self.free.remove(from) {
+            self.free.insert(to);
+        }
+        Ok(())
+    }
+
+    /// Replaces the tableau with a fresh one built from the given constraints, keeping the bounds
+    /// and the free variables
+    pub(crate) fn rebuild_from(&mut self, constraints: Vec<Constraint>) {
+        *self = Constraints {
+            bounds: std::mem::take(&mut self.bounds),
+            free: std::mem::take(&mut self.free),
+            variable_order: self.variable_order,
+            merged_names: std::mem::take(&mut self.merged_names),
+            ..Constraints::from(constraints)
+        };
     }
 
     pub fn gap_variables_count(&self) -> usize {
         self.inner.len()
     }
 
+    /// Returns the gap variable measuring the slack of the original constraint at `index`, the
+    /// first of the two rows for an equation, or `None` if there is no such constraint
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y = 4\n y <= 3").unwrap();
+    /// assert_eq!(constraints.gap_variable_of(1).unwrap(), "ε2");
+    /// assert_eq!(constraints.gap_variable_of(2), None);
+    /// ```
+    pub fn gap_variable_of(&self, index: usize) -> Option<Variable> {
+        let row = self.gap_row_of(index)?;
+        Some(Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}{row}")))
+    }
+
+    /// Returns the number of the gap variable of the original constraint at `index`, an equation
+    /// having the following one as well
+    pub(crate) fn gap_row_of(&self, index: usize) -> Option<usize> {
+        let original = self.original.get(index)?;
+        let row: usize = self.original[..index]
+            .iter()
+            .map(|c| if c.operator == Operator::Equal { 2 } else { 1 })
+            .sum();
+        debug_assert!(row < self.inner.len(), "{original} has no row in the tableau");
+        Some(row)
+    }
+
     // parse a string into a Constraints
+    // a line of the form `lower <= x <= upper` bounds a variable instead of adding constraints
+    // while `lower <= expression <= upper` adds the two constraints bounding the expression, and
+    // `x free` lets x be negative
+    #[allow(clippy::result_unit_err)]
     pub fn compile(s: &str) -> Result<Self, ()> {
-        let mut constraints = Constraints::default();
-        for line in s.lines().filter(|l| !l.trim().is_empty()) {
-            constraints.add_constraint(line.parse::<Constraint>()?);
+        Constraints::compile_with_options(s, &CompileOptions::default()).map_err(|_| ())
+    }
+
+    /// Builds the constraints `a x [ops] b` from their matrix form, the `j`th column of `a` holding
+    /// the coefficients of the variable named `var_names[j]`
+    ///
+    /// Gives `DimensionMismatch` if `b`, `ops` and the rows of `a` don't all have one value per
+    /// row, respectively per variable, `ReservedVariable` if a name is the one of a gap variable
+    /// and `InvalidVariable` if it isn't a valid name
+    /// ```rust
+    /// use simplex::constraint::{Constraints, Operator};
+    ///
+    /// let a = [vec![1.0, 1.0], vec![1.0, -1.0]];
+    /// let ops = [Operator::LessEqual, Operator::GreaterEqual];
+    /// let constraints = Constraints::from_matrix(&a, &[4.0, 1.0], &ops, &["x", "y"]).unwrap();
+    /// assert_eq!(constraints, Constraints::compile("x + y <= 4\n x - y >= 1").unwrap());
+    /// ```
+    pub fn from_matrix(
+        a: &[Vec<Coefficient>],
+        b: &[Coefficient],
+        ops: &[Operator],
+        var_names: &[&str],
+    ) -> Result<Constraints, SimplexError> {
+        for found in [b.len(), ops.len()] {
+            if found != a.len() {
+                return Err(SimplexError::DimensionMismatch { expected: a.len(), found });
+            }
+        }
+        if let Some(row) = a.iter().find(|row| row.len() != var_names.len()) {
+            return Err(SimplexError::DimensionMismatch {
+                expected: var_names.len(),
+                found: row.len(),
+            });
+        }
+        // Gap variables names are rejected as reserved
+        let var_names = var_names
+            .iter()
+            .map(|name| Variable::new(*name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut constraints = Constraints::new();
+        for ((row, constant), operator) in a.iter().zip(b).zip(ops) {
+            let coefficients = var_names
+                .iter()
+                .zip(row)
+                .filter(|(_, coeff)| **coeff != 0.0)
+                .map(|(name, coeff)| (name.clone(), *coeff))
+                .collect();
+            constraints.add_constraint(Constraint {
+                left: LinearFunction::new(0.0, coefficients),
+                operator: *operator,
+                right: LinearFunction::new(*constant, HashMap::new()),
+                label: None,
+            });
         }
         Ok(constraints)
     }
 
+    /// Parses a string into constraints like `compile`, according to `options`
+    ///
+    /// A line which can't be parsed gives `InvalidLine` along with its number, starting from 1
+    /// ```rust
+    /// use simplex::constraint::{CompileOptions, Constraints};
+    /// use simplex::SimplexError;
+    ///
+    /// let options = CompileOptions::default().with_chains(false);
+    /// assert_eq!(Constraints::compile_with_options("x + y <= 4\n 0 <= x <= 2", &options), Err(SimplexError::InvalidLine(2)));
+    /// ```
+    pub fn compile_with_options(s: &str, options: &CompileOptions) -> Result<Self, SimplexError> {
+        let mut constraints = Constraints {
+            variable_order: options.variable_order,
+            ..Constraints::default()
+        };
+        // The names as written which were normalized into each variable
+        let mut raw_names: BTreeMap<Variable, BTreeSet<Variable>> = BTreeMap::new();
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            // Each line is checked on its own before joining the others
+            let mut parsed = Constraints::new();
+            parsed
+                .add_line(line, options.expand_chains)
+                .map_err(|_| SimplexError::InvalidLine(i + 1))?;
+
+            let is_strict = |c: &Constraint| matches!(c.operator, Operator::Less | Operator::Greater);
+            if !options.allow_strict && parsed.original.iter().any(is_strict) {
+                return Err(SimplexError::StrictInequality(i + 1));
+            }
+            let names = parsed
+                .original
+                .iter()
+                .flat_map(Constraint::non_gap_variables)
+                .chain(parsed.bounds.keys().cloned())
+                .chain(parsed.free.iter().cloned())
+                .collect::<HashSet<_>>();
+            for name in names {
+                let normalized = options.normalize(&name);
+                parsed.rename_variable(&name, &normalized)?;
+                raw_names.entry(normalized).or_default().insert(name);
+            }
+            let reserved = parsed
+                .original
+                .iter()
+                .flat_map(Constraint::non_gap_variables)
+                .chain(parsed.bounds.keys().cloned())
+                .chain(parsed.free.iter().cloned())
+                .find(|var| options.reserved_names.iter().any(|name| options.normalize(name) == *var));
+            if let Some(var) = reserved {
+                return Err(SimplexError::ReservedVariable(var));
+            }
+
+            for constraint in parsed.original {
+                constraints.add_constraint(constraint);
+            }
+            for (var, (lower, upper)) in parsed.bounds {
+                constraints.add_bound(&var, lower, upper);
+            }
+            constraints.free.extend(parsed.free);
+        }
+        constraints.merged_names = raw_names
+            .into_iter()
+            .filter(|(_, names)| names.len() > 1)
+            .map(|(var, names)| (var, names.into_iter().collect()))
+            .collect();
+        Ok(constraints)
+    }
+
+    /// Returns the variables which several names were normalized into by
+    /// [`Constraints::compile_with_options`], along with those names sorted alphabetically
+    pub fn merged_variables(&self) -> &[(Variable, Vec<Variable>)] {
+        &self.merged_names
+    }
+
+    /// Parses a single line, either a constraint, the declaration of a free variable such as
+    /// `x free` or, if `expand_chains` is set, a bound or a ranged constraint, and adds it
+    pub(crate) fn add_line(&mut self, line: &str, expand_chains: bool) -> Result<(), ()> {
+        if let Some((var, "free")) = line.split_whitespace().collect_tuple() {
+            let var = var.parse::<LinearFunction>()?.name_single_variable().ok_or(())?;
+            self.declare_free(&var);
+            return Ok(());
+        }
+        if !expand_chains {
+            let (_, operators) = split_on_operators(line);
+            if operators.len() > 1 {
+                return Err(());
+            }
+            self.add_constraint(line.parse::<Constraint>()?);
+            return Ok(());
+        }
+        match parse_chain(line)? {
+            Some(Chain::Bound(var, lower, upper)) => self.add_bound(&var, lower, upper),
+            Some(Chain::Range(lower, upper)) => {
+                self.add_constraint(lower);
+                self.add_constraint(upper);
+            }
+            None => self.add_constraint(line.parse::<Constraint>()?),
+        }
+        Ok(())
+    }
+
     /// Normalizes all constraints with respect to a variable
     pub fn normalize(&mut self, var: &Variable) {
         self.inner.iter_mut().for_each(|c| c.normalize(var))
     }
 
     /// Returns the index of the constraint that maximizes 'var' while minimising the corresponding constant
-    pub fn most_restrictive(&self, var: &Variable) -> Option<usize> {
+    ///
+    /// Coefficients within the `zero` tolerance are ignored, and restrictions within the `ratio`
+    /// tolerance of each other are considered tied, in which case the last constraint is chosen
+    pub fn most_restrictive(&self, var: &Variable, tolerances: &Tolerances) -> Option<usize> {
+        self.iter()
+            .enumerate()
+            .filter_map(|(i, c)| c.ratio(var, tolerances.zero).map(|ratio| (i, ratio)))
+            .fold(None, |best: Option<(usize, Coefficient)>, (i, ratio)| match best {
+                Some((_, best_ratio)) if ratio > best_ratio + tolerances.ratio => best,
+                _ => Some((i, ratio)),
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Returns the index of the most restrictive constraint for `var` like `most_restrictive`,
+    /// breaking ties as if the constant of the `i`th original row had been increased by `δ^(i+1)`
+    /// for an infinitely small `δ`. The rows are then compared lexicographically, and as no two
+    /// of them can be equal, the same base can't be visited twice
+    pub fn lexicographic_most_restrictive(&self, var: &Variable, tolerances: &Tolerances) -> Option<usize> {
+        // The constant of each row followed by its perturbation, i.e. its coefficients in `δ`,
+        // `δ²`, ..., all divided by the coefficient of `var`
+        let key = |constraint: &Constraint| {
+            let scale = -constraint.right[var];
+            let perturbation = (0..self.gap_variables_count()).map(|i| {
+                let gap = Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}{i}"));
+                if constraint.left.contains(&gap) {
+                    1.0
+                } else {
+                    -constraint.right[&gap]
+                }
+            });
+            std::iter::once(constraint.right.constant)
+                .chain(perturbation)
+                .map(|value| value / scale)
+                .collect::<Vec<_>>()
+        };
+
         self.iter()
             .enumerate()
-            .filter(|(_, c)| c.right.contains(var) && c.right[var] <= 0.0)
-            .max_by(
-                |(_, Constraint { right: a, .. }), (_, Constraint { right: b, .. })| {
-                    let restriction_a = a.constant / a[var];
-                    let restriction_b = b.constant / b[var];
-                    restriction_a.total_cmp(&restriction_b)
-                },
-            )
+            .filter(|(_, c)| c.right[var] < -tolerances.zero)
+            .map(|(i, c)| (i, key(c)))
+            .min_by(|(_, a), (_, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .map(|(x, y)| x.total_cmp(y))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
             .map(|(i, _)| i)
     }
 
@@ -233,22 +1379,48 @@ impl Constraints {
             let constraint = &mut self.inner[constraint_index];
             constraint.normalize(var);
             *constraint -= constraint.left.clone();
-            *constraint -= LinearFunction::single_variable(var.to_string());
+            *constraint -= LinearFunction::single_variable(var.clone());
             *constraint = -constraint.clone();
+            constraint.left.simplify();
+            constraint.right.simplify();
         }
         // And replace the variable by the new rhs in other constraints
         let func = self.inner[constraint_index].right.clone();
         self.replace_variable_with(var, &func);
     }
 
-    pub fn is_valid(&self) -> bool {
-        for constraint in self.inner.iter() {
-            if !constraint.is_valid_linear_program() {
-                return false;
+    pub fn is_valid(&self, tolerances: &Tolerances) -> bool {
+        self.validity_report(tolerances).is_empty()
+    }
+
+    /// Lists the rows of the tableau which aren't of the form `x = function`, and the ones whose
+    /// basic variable is negative at the current basic solution, up to the `zero` tolerance, and
+    /// by how much
+    /// ```rust
+    /// use simplex::Tolerances;
+    /// use simplex::constraint::{Constraints, RowViolation};
+    ///
+    /// let mut constraints = Constraints::compile("x + y <= 4").unwrap();
+    /// constraints[0] = constraints[0].scale_by(2.0);
+    /// let report = constraints.validity_report(&Tolerances::default());
+    /// assert_eq!(report.violations, vec![RowViolation::NotBasic { row: 0, residual: 1.0 }]);
+    /// ```
+    pub fn validity_report(&self, tolerances: &Tolerances) -> ValidityReport {
+        let mut violations = vec![];
+        for (row, constraint) in self.inner.iter().enumerate() {
+            if constraint.operator != Operator::Equal {
+                violations.push(RowViolation::NotAnEquation { row });
+            }
+            let residual = constraint.basic_residual();
+            if residual > tolerances.zero {
+                violations.push(RowViolation::NotBasic { row, residual });
+            } else if constraint.right.constant < -tolerances.zero {
+                violations.push(RowViolation::Violated { row, amount: -constraint.right.constant });
             }
         }
-        true
+        ValidityReport { violations }
     }
+
     pub fn non_gap_variables(&self) -> Vec<Variable> {
         let mut var_set = HashSet::new();
         for c in self.iter() {
@@ -259,12 +1431,183 @@ impl Constraints {
         var_set.into_iter().collect()
     }
 
-    fn replace_variable_with(&mut self, var: &Variable, value: &LinearFunction) {
+    /// Builds the objective `Σ weight * var` over the variables of `weights`, the variables of
+    /// the constraints without a weight getting 0
+    ///
+    /// A weighted variable which doesn't appear in the constraints is kept as well, so that the
+    /// objective is the one asked for: [`crate::LinearProgram::validate`] then reports it as
+    /// unconstrained
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y <= 4\n 0 <= z <= 2").unwrap();
+    /// let weights = HashMap::from([("x".parse().unwrap(), 2.0), ("z".parse().unwrap(), 1.0), ("w".parse().unwrap(), 5.0)]);
+    /// assert_eq!(constraints.weighted_objective(&weights), "2x + z + 5w".parse().unwrap());
+    /// ```
+    pub fn weighted_objective(&self, weights: &HashMap<Variable, Coefficient>) -> LinearFunction {
+        weights
+            .iter()
+            .fold(LinearFunction::zero(), |acc, (var, weight)| acc + LinearFunction::term(*weight, var.clone()))
+            .simplified()
+    }
+
+    /// Returns the indices of the original constraints holding with equality at `point`, up to
+    /// `tolerance`. At a vertex, those are the constraints defining it
+    /// (variables absent from `point` are considered to be 0)
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    /// use std::collections::HashMap;
+    ///
+    /// let constraints = Constraints::compile("x <= 2\n x + y <= 5\n y <= 4").unwrap();
+    /// let point = HashMap::from([("x".parse().unwrap(), 2.0), ("y".parse().unwrap(), 3.0)]);
+    /// assert_eq!(constraints.active_at(&point, 1e-6), vec![0, 1]);
+    /// ```
+    pub fn active_at(&self, point: &HashMap<Variable, Coefficient>, tolerance: Coefficient) -> Vec<usize> {
+        self.original
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.residual(point).abs() <= tolerance)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns every variable of the tableau, gap variables included, sorted in alphabetical order
+    pub fn all_variables(&self) -> Vec<Variable> {
+        self.iter()
+            .flat_map(|c| c.left.variables().into_iter().chain(c.right.variables()))
+            .unique()
+            .sorted()
+            .collect()
+    }
+
+    pub(crate) fn replace_variable_with(&mut self, var: &Variable, value: &LinearFunction) {
         for Constraint { right, .. } in &mut self.inner {
             right.replace(var, value)
         }
     }
 
+    /// Returns every vertex of the feasible region, each one giving the value of the decision
+    /// variables in alphabetical order, sorted lexicographically
+    ///
+    /// Each vertex is the intersection of as many hyperplanes as there are variables, among the
+    /// constraints and the bounds of the variables (0 for the unbounded ones), so this solves a
+    /// system for each of those `C(m + n, n)` subsets. See [`Constraints::vertices_parallel`]
+    /// for larger problems
+    /// ```rust
+    /// use simplex::constraint::Constraints;
+    ///
+    /// let constraints = Constraints::compile("x + y <= 4\n y <= 3").unwrap();
+    /// assert_eq!(
+    ///     constraints.vertices(),
+    ///     vec![vec![0.0, 0.0], vec![0.0, 3.0], vec![1.0, 3.0], vec![4.0, 0.0]]
+    /// );
+    /// ```
+    pub fn vertices(&self) -> Vec<Vec<Coefficient>> {
+        let (variables, hyperplanes) = self.vertex_hyperplanes();
+        let candidates = (0..hyperplanes.len())
+            .combinations(variables.len())
+            .filter_map(|subset| self.vertex_of(&variables, &hyperplanes, &subset))
+            .collect();
+        unique_sorted_vertices(candidates)
+    }
+
+    /// Computes the same vertices as [`Constraints::vertices`], solving the systems in parallel
+    #[cfg(feature = "parallel")]
+    pub fn vertices_parallel(&self) -> Vec<Vec<Coefficient>> {
+        use rayon::prelude::*;
+
+        let (variables, hyperplanes) = self.vertex_hyperplanes();
+        let subsets = (0..hyperplanes.len()).combinations(variables.len()).collect::<Vec<_>>();
+        // Collecting an indexed iterator keeps the order of the subsets, hence of the candidates
+        let candidates = subsets
+            .par_iter()
+            .filter_map(|subset| self.vertex_of(&variables, &hyperplanes, subset))
+            .collect();
+        unique_sorted_vertices(candidates)
+    }
+
+    /// Returns the values `var` may take: its bounds, any value for a free variable, or the non
+    /// negative ones by default
+    fn range_of(&self, var: &Variable) -> (Coefficient, Coefficient) {
+        match self.bounds.get(var) {
+            Some(bounds) => *bounds,
+            None if self.free.contains(var) => (Coefficient::NEG_INFINITY, Coefficient::INFINITY),
+            None => (0.0, Coefficient::INFINITY),
+        }
+    }
+
+    /// Returns the decision variables, sorted, and the hyperplanes a vertex can lie on, as the
+    /// coefficients of each variable and the right hand side
+    fn vertex_hyperplanes(&self) -> (Vec<Variable>, Vec<(Vec<f64>, f64)>) {
+        let variables = self
+            .original
+            .iter()
+            .flat_map(|c| c.non_gap_variables())
+            .chain(self.bounds.keys().cloned())
+            .unique()
+            .sorted()
+            .collect::<Vec<_>>();
+        let unit = |j: usize| (0..variables.len()).map(|k| if j == k { 1.0 } else { 0.0 }).collect();
+
+        let mut hyperplanes = vec![];
+        for constraint in self.original.iter() {
+            let function = constraint.left.clone() - constraint.right.clone();
+            let coefficients = variables.iter().map(|var| function[var] as f64).collect();
+            hyperplanes.push((coefficients, -function.constant as f64));
+        }
+        for (j, var) in variables.iter().enumerate() {
+            let (lower, upper) = self.range_of(var);
+            for bound in [lower, upper].into_iter().filter(|bound| bound.is_finite()) {
+                hyperplanes.push((unit(j), bound as f64));
+            }
+        }
+        (variables, hyperplanes)
+    }
+
+    /// Returns the intersection of the given hyperplanes if it is a single feasible point
+    fn vertex_of(
+        &self,
+        variables: &[Variable],
+        hyperplanes: &[(Vec<f64>, f64)],
+        subset: &[usize],
+    ) -> Option<Vec<Coefficient>> {
+        let n = variables.len();
+        let mut rows = subset
+            .iter()
+            .map(|&i| {
+                let (coefficients, rhs) = &hyperplanes[i];
+                coefficients.iter().copied().chain([*rhs]).collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        // Gaussian elimination with partial pivoting
+        for col in 0..n {
+            let pivot = (col..n).max_by(|a, b| rows[*a][col].abs().total_cmp(&rows[*b][col].abs()))?;
+            if rows[pivot][col].abs() < 1e-9 {
+                return None;
+            }
+            rows.swap(col, pivot);
+            let pivot_row = rows[col].clone();
+            for (_, values) in rows.iter_mut().enumerate().filter(|(row, _)| *row != col) {
+                let factor = values[col] / pivot_row[col];
+                for (value, pivot_value) in values.iter_mut().zip(&pivot_row).skip(col) {
+                    *value -= factor * pivot_value;
+                }
+            }
+        }
+        let point = (0..n).map(|j| (rows[j][n] / rows[j][j]) as Coefficient).collect::<Vec<_>>();
+
+        let valuation = variables.iter().cloned().zip(point.iter().copied()).collect();
+        let tolerance = ZERO_TOLERANCE * point.iter().fold(1.0, |acc: Coefficient, v| acc.max(v.abs()));
+        let in_bounds = variables.iter().zip(point.iter()).all(|(var, value)| {
+            let (lower, upper) = self.range_of(var);
+            *value >= lower - tolerance && *value <= upper + tolerance
+        });
+        let feasible = self.original.iter().all(|c| c.holds_at(&valuation, tolerance));
+        (in_bounds && feasible).then_some(point)
+    }
+
     /// Returns a list of vertices that can be used directly to render the polyhedron
     /// corresponding to the constraints in OpenGL
     /// This polyhedron is centered around (0, 0, 0)
@@ -277,20 +1620,15 @@ impl Constraints {
         let dummy_program = LinearProgram {
             linear_function: LinearFunction::new(
                 0.0,
-                HashMap::from_iter(variables.iter().map(|v| (v.to_string(), 1.0))),
+                HashMap::from_iter(variables.iter().map(|v| (v.clone(), 1.0))),
             ),
             constraints: self.clone(),
+            ..Default::default()
         };
 
         // Do a BFS on the dummy simplex instance
         let mut queue = VecDeque::from([dummy_program]);
         let mut seen = vec![];
-        let points_nearly_equal = |a: &Vec<f32>, b: &Vec<f32>| {
-            for (a, b) in a.iter().zip(b) {
-                if (a - b).abs() <= 0.000001 { return true }
-            }
-            false
-        };
         while let Some(program) = queue.pop_back() {
             let current_point = program.point();
             seen.push(current_point.clone());
@@ -348,8 +1686,67 @@ impl Constraints {
                 *value /= max_value;
             }
         }
-
-        polyhedron
+
+        polyhedron
+    }
+}
+
+/// Removes the vertices found several times, up to rounding errors, and sorts the others
+fn unique_sorted_vertices(candidates: Vec<Vec<Coefficient>>) -> Vec<Vec<Coefficient>> {
+    let mut vertices: Vec<Vec<Coefficient>> = vec![];
+    for candidate in candidates {
+        let tolerance = ZERO_TOLERANCE * candidate.iter().fold(1.0, |acc: Coefficient, v| acc.max(v.abs()));
+        let seen = vertices
+            .iter()
+            .any(|vertex| vertex.iter().zip(&candidate).all(|(a, b)| (a - b).abs() <= tolerance));
+        if !seen {
+            vertices.push(candidate);
+        }
+    }
+    vertices.sort_by(|a, b| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| a.total_cmp(b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    vertices
+}
+
+impl PartialEq for Constraint {
+    /// Two constraints are equal if they describe the same set of points once normalized, such
+    /// that `2x <= 10` equals `x <= 5` or `5 >= x`. Labels are ignored
+    fn eq(&self, other: &Self) -> bool {
+        self.normalized() == other.normalized()
+    }
+}
+
+/// Normalized constraints are compared exactly, so the equality is an equivalence as long as no
+/// coefficient is NaN
+impl Eq for Constraint {}
+
+impl std::hash::Hash for Constraint {
+    /// Hashes the normalized constraint, the coefficients by their bit pattern, so that equal
+    /// constraints such as `2x <= 10` and `x <= 5` get the same hash. Zero terms are skipped and
+    /// `-0.0` is hashed as `0.0`, as they are ignored by the equality
+    /// ```rust
+    /// use simplex::constraint::Constraint;
+    /// use std::collections::HashSet;
+    ///
+    /// let constraints: HashSet<Constraint> = ["2x <= 10", "x <= 5", "5 >= x", "x + 0y <= 5", "x >= 5"]
+    ///     .iter()
+    ///     .map(|c| c.parse().unwrap())
+    ///     .collect();
+    /// assert_eq!(constraints.len(), 2);
+    /// ```
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let (function, operator) = self.normalized();
+        operator.hash(state);
+        (function.constant + 0.0).to_bits().hash(state);
+        for (var, coeff) in function.terms() {
+            var.hash(state);
+            coeff.to_bits().hash(state);
+        }
     }
 }
 
@@ -365,6 +1762,33 @@ impl std::ops::IndexMut<usize> for Constraints {
     }
 }
 
+/// Builds the tableau of the given constraints, each getting its gap variables as by
+/// [`Constraints::add_constraint`]
+/// ```rust
+/// use simplex::constraint::{Constraint, Constraints};
+///
+/// let constraints: Constraints = ["x <= 2", "x + y <= 4"]
+///     .into_iter()
+///     .map(|c| c.parse::<Constraint>().unwrap())
+///     .collect();
+/// assert_eq!(constraints, Constraints::compile("x <= 2\n x + y <= 4").unwrap());
+/// ```
+impl FromIterator<Constraint> for Constraints {
+    fn from_iter<I: IntoIterator<Item = Constraint>>(iter: I) -> Self {
+        let mut constraints = Constraints::new();
+        for constraint in iter {
+            constraints.add_constraint(constraint);
+        }
+        constraints
+    }
+}
+
+impl From<Vec<Constraint>> for Constraints {
+    fn from(constraints: Vec<Constraint>) -> Self {
+        constraints.into_iter().collect()
+    }
+}
+
 
 impl std::fmt::Display for Operator {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -381,6 +1805,9 @@ impl std::fmt::Display for Operator {
 impl std::fmt::Display for Constraint {
     /// Display a constraint
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(label) = &self.label {
+            write!(f, "{label}: ")?;
+        }
         write!(f, "{} {} {}", self.left, self.operator, self.right)
     }
 }
@@ -394,12 +1821,143 @@ impl std::fmt::Display for Constraints {
     }
 }
 
+/// Largest denominator looked for when turning coefficients into fractions
+const MAX_DENOMINATOR: u64 = 1000;
+
+/// Returns the smallest denominator of a fraction equal to `value`, if there is one up to
+/// `MAX_DENOMINATOR`
+fn denominator(value: Coefficient) -> Option<u64> {
+    (1..=MAX_DENOMINATOR).find(|d| {
+        let scaled = value * *d as f32;
+        (scaled - scaled.round()).abs() <= 1e-4 * scaled.abs().max(1.0)
+    })
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
 /*
 PARSING
  */
+/// Recognizes a comparison operator, any of the spellings accepted by `Operator::from_str`
+///
+/// The longest spellings are tried first, so that `<=` isn't read as `<` followed by `=`
+fn parse_operator(s: &str) -> nom::IResult<&str, &str, ()> {
+    alt((
+        tag("<="),
+        tag(">="),
+        tag("=="),
+        tag("≤"),
+        tag("≥"),
+        tag("="),
+        tag("<"),
+        tag(">"),
+    ))(s)
+}
+
+/// Splits a string around every comparison operator it contains
+fn split_on_operators(s: &str) -> (Vec<String>, Vec<&str>) {
+    let mut parts = vec![];
+    let mut operators = vec![];
+    let mut rest = s;
+    while let Ok((remaining, (part, operator))) = many_till(anychar, parse_operator)(rest) {
+        parts.push(part.into_iter().collect());
+        operators.push(operator);
+        rest = remaining;
+    }
+    parts.push(rest.to_string());
+    (parts, operators)
+}
+
+/// A line chaining two operators of the same direction around a central expression
+#[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+enum Chain {
+    /// A single variable between two constants, such as `0 <= x <= 10`, with its lower and upper
+    /// bounds
+    Bound(Variable, Coefficient, Coefficient),
+    /// Any other expression between two constants, such as `0 <= x + y <= 10`, expanded into the
+    /// constraints bounding it from below and from above
+    Range(Constraint, Constraint),
+}
+
+/// Splits the `label:` prefix off a constraint, the label being a single word. A line with an
+/// empty label or more than one `:` is invalid
+fn split_label(s: &str) -> Result<(Option<&str>, &str), ()> {
+    match s.split_once(':') {
+        Some((label, rest)) => {
+            let label = label.trim();
+            if label.is_empty() || label.contains(char::is_whitespace) || rest.contains(':') {
+                return Err(());
+            }
+            Ok((Some(label), rest))
+        }
+        None => Ok((None, s)),
+    }
+}
+
+/// Parses a chained inequality such as `0 <= x <= 10`, `10 >= x >= 0` or `0 <= x + y <= 10`
+///
+/// Returns `None` if the line doesn't chain two operators, and an error if it does but the outer
+/// parts aren't constants or the operators don't go in the same direction
+fn parse_chain(s: &str) -> Result<Option<Chain>, ()> {
+    let (label, s) = split_label(s)?;
+    let (parts, operators) = split_on_operators(s);
+    if operators.len() != 2 {
+        return Ok(None);
+    }
+
+    let constant = |part: &str| {
+        let function = part.parse::<LinearFunction>()?;
+        if function.var_iter().next().is_some() {
+            return Err(());
+        }
+        Ok(function.constant)
+    };
+    let middle = parts[1].parse::<LinearFunction>()?;
+    let (first, last) = (constant(&parts[0])?, constant(&parts[2])?);
+    let (first_operator, last_operator): (Operator, Operator) = (operators[0].parse()?, operators[1].parse()?);
+
+    let (lower, upper) = match (first_operator, last_operator) {
+        (Operator::Less | Operator::LessEqual, Operator::Less | Operator::LessEqual) => (first, last),
+        (Operator::Greater | Operator::GreaterEqual, Operator::Greater | Operator::GreaterEqual) => (last, first),
+        _ => return Err(()),
+    };
+    if let Some(var) = middle.name_single_variable() {
+        return Ok(Some(Chain::Bound(var, lower, upper)));
+    }
+
+    // Both constraints keep the strictness of their operator, with the expression on the left
+    let labeled = |constraint: Constraint| match label {
+        Some(label) if label.is_empty() || label.contains(char::is_whitespace) => Err(()),
+        Some(label) => Ok(constraint.with_label(label)),
+        None => Ok(constraint),
+    };
+    let first = labeled(Constraint::new(
+        middle.clone(),
+        first_operator.reverse(),
+        LinearFunction::constant(first),
+    ))?;
+    let last = labeled(Constraint::new(middle, last_operator, LinearFunction::constant(last)))?;
+    match first_operator {
+        Operator::Less | Operator::LessEqual => Ok(Some(Chain::Range(first, last))),
+        _ => Ok(Some(Chain::Range(last, first))),
+    }
+}
+
 impl std::str::FromStr for Operator {
     type Err = ();
-    /// Parses a string into an operator
+    /// Parses a string into an operator, `==` being accepted for `=` and `≤` and `≥` for `<=` and
+    /// `>=`. The surrounding whitespace is ignored
     /// # Example
     /// ``` rust
     /// use simplex::constraint::Operator;
@@ -410,15 +1968,16 @@ impl std::str::FromStr for Operator {
     ///    Err(_) => panic!("Error")
     /// };
     /// let expected = Operator::LessEqual;
-    /// assert_eq!(operator, expected)
+    /// assert_eq!(operator, expected);
+    /// assert_eq!(Operator::from_str("≥"), Ok(Operator::GreaterEqual));
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.trim() {
-            "=" => Ok(Operator::Equal),
+            "=" | "==" => Ok(Operator::Equal),
             "<" => Ok(Operator::Less),
             ">" => Ok(Operator::Greater),
-            "<=" => Ok(Operator::LessEqual),
-            ">=" => Ok(Operator::GreaterEqual),
+            "<=" | "≤" => Ok(Operator::LessEqual),
+            ">=" | "≥" => Ok(Operator::GreaterEqual),
             _ => Err(()),
         }
     }
@@ -437,20 +1996,21 @@ impl std::str::FromStr for Constraint {
     /// use std::str::FromStr;
     ///
     /// let constraint = Constraint::from_str("25 -8x + 12y +3z <= 12").unwrap();
-    /// let expected_left = LinearFunction::new(25f32, HashMap::from([(String::from("x"), -8f32), (String::from("y"), 12f32), (String::from("z"), 3f32)]));
+    /// let expected_left = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), -8f32), ("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 3f32)]));
     /// let expected_right = LinearFunction::new(12f32, HashMap::new());
     /// let expected = Constraint::new(expected_left, Operator::LessEqual, expected_right);
-    /// assert_eq!(constraint, expected)
+    /// assert_eq!(constraint, expected);
+    ///
+    /// // A constraint can be given a label
+    /// let constraint = Constraint::from_str("budget: x + y <= 10").unwrap();
+    /// assert_eq!(constraint.label, Some("budget".parse().unwrap()));
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_op = alt((
-            tag::<&str, &str, ()>("<="),
-            tag::<&str, &str, ()>(">="),
-            tag::<&str, &str, ()>("="),
-            tag::<&str, &str, ()>("<"),
-            tag::<&str, &str, ()>(">"),
-        ));
-        if let Ok((rhs, (lhs, op))) = many_till(anychar, parse_op)(s) {
+        if let (Some(label), constraint) = split_label(s)? {
+            return Ok(constraint.parse::<Constraint>()?.with_label(label));
+        }
+
+        if let Ok((rhs, (lhs, op))) = many_till(anychar, parse_operator)(s) {
             let lhs = lhs
                 .iter()
                 .fold(String::new(), |acc, c| acc + &c.to_string());
@@ -475,9 +2035,9 @@ impl std::ops::Add<LinearFunction> for Constraint {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let c = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let l_f = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(25f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), 12f32), (String::from("z"), 0f32)]));
+    /// let c = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let l_f = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 0f32)]));
     /// assert_eq!(c + l_f, expected);
     /// ```
     fn add(self, rhs: LinearFunction) -> Self::Output {
@@ -485,6 +2045,7 @@ impl std::ops::Add<LinearFunction> for Constraint {
             left: self.left + rhs.clone(),
             operator: self.operator,
             right: self.right + rhs,
+            label: self.label,
         }
     }
 }
@@ -496,13 +2057,13 @@ impl std::ops::AddAssign<LinearFunction> for Constraint {
     /// use simplex::constraint::Constraint;
     /// use simplex::constraint::Operator;
     ///
-    /// let left = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 15f32), (String::from("y"), -5f32)]));
-    /// let right = LinearFunction::new(25f32, HashMap::from([(String::from("x"), -7f32), (String::from("y"), 12f32)]));
+    /// let left = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 15f32), ("y".parse().unwrap(), -5f32)]));
+    /// let right = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), -7f32), ("y".parse().unwrap(), 12f32)]));
     /// let mut c = Constraint::new(left, Operator::Equal, right);
-    /// let var_x = LinearFunction::new(-2f32, HashMap::from([(String::from("x"), 5f32)]));
+    /// let var_x = LinearFunction::new(-2f32, HashMap::from([("x".parse().unwrap(), 5f32)]));
     ///
-    /// let expected_left = LinearFunction::new(28f32, HashMap::from([(String::from("x"), 20f32), (String::from("y"), -5f32)]));
-    /// let expected_right = LinearFunction::new(23f32, HashMap::from([(String::from("x"), -2f32), (String::from("y"), 12f32)]));
+    /// let expected_left = LinearFunction::new(28f32, HashMap::from([("x".parse().unwrap(), 20f32), ("y".parse().unwrap(), -5f32)]));
+    /// let expected_right = LinearFunction::new(23f32, HashMap::from([("x".parse().unwrap(), -2f32), ("y".parse().unwrap(), 12f32)]));
     /// let expected = Constraint::new(expected_left, Operator::Equal, expected_right);
     /// c += var_x;
     /// assert_eq!(c, expected);
@@ -520,9 +2081,9 @@ impl std::ops::Sub<LinearFunction> for Constraint {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let c = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let l_f = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(35f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), -12f32), (String::from("z"), -10f32)]));
+    /// let c = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let l_f = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(35f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), -12f32), ("z".parse().unwrap(), -10f32)]));
     /// assert_eq!(c-l_f, expected)
     /// ```
     fn sub(self, rhs: LinearFunction) -> Self::Output {
@@ -530,6 +2091,7 @@ impl std::ops::Sub<LinearFunction> for Constraint {
             left: self.left - rhs.clone(),
             operator: self.operator,
             right: self.right - rhs,
+            label: self.label,
         }
     }
 }
@@ -541,13 +2103,13 @@ impl std::ops::SubAssign<LinearFunction> for Constraint {
     /// use simplex::constraint::Constraint;
     /// use simplex::constraint::Operator;
     ///
-    /// let left = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 15f32), (String::from("y"), -5f32)]));
-    /// let right = LinearFunction::new(25f32, HashMap::from([(String::from("x"), -7f32), (String::from("y"), 12f32)]));
+    /// let left = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 15f32), ("y".parse().unwrap(), -5f32)]));
+    /// let right = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), -7f32), ("y".parse().unwrap(), 12f32)]));
     /// let mut c = Constraint::new(left, Operator::Equal, right);
-    /// let var_x = LinearFunction::new(-2f32, HashMap::from([(String::from("x"), 5f32)]));
+    /// let var_x = LinearFunction::new(-2f32, HashMap::from([("x".parse().unwrap(), 5f32)]));
     ///
-    /// let expected_left = LinearFunction::new(32f32, HashMap::from([(String::from("x"), 10f32), (String::from("y"), -5f32)]));
-    /// let expected_right = LinearFunction::new(27f32, HashMap::from([(String::from("x"), -12f32), (String::from("y"), 12f32)]));
+    /// let expected_left = LinearFunction::new(32f32, HashMap::from([("x".parse().unwrap(), 10f32), ("y".parse().unwrap(), -5f32)]));
+    /// let expected_right = LinearFunction::new(27f32, HashMap::from([("x".parse().unwrap(), -12f32), ("y".parse().unwrap(), 12f32)]));
     /// let expected = Constraint::new(expected_left, Operator::Equal, expected_right);
     ///
     /// c -= var_x;
@@ -567,6 +2129,7 @@ impl std::ops::Div<f32> for Constraint {
             left: self.left / rhs,
             operator: self.operator,
             right: self.right / rhs,
+            label: self.label,
         }
     }
 }
@@ -586,6 +2149,7 @@ impl std::ops::Neg for Constraint {
             left: -self.left,
             right: -self.right,
             operator: self.operator.inverse(),
+            label: self.label,
         }
     }
 }
@@ -593,6 +2157,57 @@ impl std::ops::Neg for Constraint {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::linear_function::var;
+
+    #[test]
+    fn test_parse_operator() {
+        let cases = [
+            ("<=", Operator::LessEqual),
+            (">=", Operator::GreaterEqual),
+            ("<", Operator::Less),
+            (">", Operator::Greater),
+            ("=", Operator::Equal),
+            ("==", Operator::Equal),
+            ("≤", Operator::LessEqual),
+            (" ≥ ", Operator::GreaterEqual),
+        ];
+        for (s, operator) in cases {
+            assert_eq!(s.parse::<Operator>(), Ok(operator), "{s}");
+        }
+        for s in ["", "=<", "<==", "≠", "< =", "!="] {
+            assert!(s.parse::<Operator>().is_err(), "{s}");
+        }
+
+        // The constraint parser reads the longest operator
+        for (line, operator) in [("x <= 4", "<="), ("x == 4", "="), ("x ≤ 4", "<="), ("x ≥ 4", ">="), ("x < 4", "<")] {
+            let constraint = line.parse::<Constraint>().unwrap();
+            assert_eq!(constraint.operator.to_string(), operator, "{line}");
+            assert_eq!(constraint.right, LinearFunction::constant(4.0), "{line}");
+        }
+        let constraints = Constraints::compile("0 ≤ x ≤ 2\n x + y == 3").unwrap();
+        assert_eq!(constraints.bounds(), &HashMap::from([(var("x"), (0.0, 2.0))]));
+        assert_eq!(constraints.original_constraints()[0].operator, Operator::Equal);
+    }
+
+    #[test]
+    fn test_operator_satisfied() {
+        let tolerance = 0.01;
+        // On the boundary, just inside and just outside of the tolerance band
+        let cases = [
+            (Operator::Equal, [true, true, false]),
+            (Operator::Less, [false, false, false]),
+            (Operator::LessEqual, [true, true, false]),
+            (Operator::Greater, [false, false, true]),
+            (Operator::GreaterEqual, [true, true, true]),
+        ];
+        for (operator, expected) in cases {
+            let results = [1.0, 1.005, 1.02].map(|lhs| operator.satisfied(lhs, 1.0, tolerance));
+            assert_eq!(results, expected, "{operator}");
+        }
+        // Strict comparisons hold once the values are further apart than the tolerance
+        assert!(Operator::Less.satisfied(0.98, 1.0, tolerance));
+        assert!(!Operator::GreaterEqual.satisfied(0.98, 1.0, tolerance));
+    }
 
     #[test]
     fn test_inverse_operator() {
@@ -607,23 +2222,24 @@ mod tests {
 
         let lhs = LinearFunction::new(
             30f32,
-            HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]),
+            HashMap::from([(var("x"), 32f32), (var("z"), -5f32)]),
         );
         let rhs = LinearFunction::new(
             -5f32,
-            HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]),
+            HashMap::from([(var("y"), 12f32), (var("z"), 5f32)]),
         );
         let op = Operator::LessEqual;
         let expected = Constraint {
             left: LinearFunction::new(
                 30f32,
-                HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]),
+                HashMap::from([(var("x"), 32f32), (var("z"), -5f32)]),
             ),
             operator: Operator::LessEqual,
             right: LinearFunction::new(
                 -5f32,
-                HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]),
+                HashMap::from([(var("y"), 12f32), (var("z"), 5f32)]),
             ),
+            label: None,
         };
         let n = Constraint::new(lhs, op, rhs);
         assert_eq!(n, expected)
@@ -633,11 +2249,11 @@ mod tests {
     fn test_normalize() {
         let mut constraints =
             Constraints::compile("x - 2y >= 6 \n 12 + 9x + 3y <= 6\n 1 + 7x - y <= 0").unwrap();
-        constraints.normalize(&"y".to_string());
+        constraints.normalize(&var("y"));
 
-        assert_eq!(constraints.inner[0].right[&"y".to_string()], 1.0);
-        assert_eq!(constraints.inner[1].right[&"y".to_string()], 1.0);
-        assert_eq!(constraints.inner[2].right[&"y".to_string()], 1.0);
+        assert_eq!(constraints.inner[0].right[&var("y")], 1.0);
+        assert_eq!(constraints.inner[1].right[&var("y")], 1.0);
+        assert_eq!(constraints.inner[2].right[&var("y")], 1.0);
     }
 
     #[test]
@@ -646,10 +2262,722 @@ mod tests {
         use std::str::FromStr;
 
         let mut c = Constraint::from_str("0 = 200 - x - y").unwrap();
-        let l_f = LinearFunction::new(0f32, HashMap::from([(String::from("x"), -1f32)]));
+        let l_f = LinearFunction::new(0f32, HashMap::from([(var("x"), -1f32)]));
 
         let expected = Constraint::from_str("x = 200 - y + 0x").unwrap();
         c -= l_f;
         assert_eq!(c, expected);
     }
+
+    #[test]
+    fn test_normalized_equality() {
+        use std::str::FromStr;
+
+        let c = Constraint::from_str("2x <= 10").unwrap();
+        assert_eq!(c, Constraint::from_str("x <= 5").unwrap());
+        assert_eq!(c, Constraint::from_str("5 >= x").unwrap());
+        assert_eq!(c, Constraint::from_str("-x >= -5").unwrap());
+        assert_eq!(c, Constraint::from_str("bound: x - 5 <= 0").unwrap());
+        assert_ne!(c, Constraint::from_str("x < 5").unwrap());
+        assert_ne!(c, Constraint::from_str("x >= 5").unwrap());
+
+        let c = Constraint::from_str("2x + 4y = 6").unwrap();
+        assert_eq!(c, Constraint::from_str("-x - 2y = -3").unwrap());
+        assert!(c.approx_eq(&Constraint::from_str("x + 2.0000001y = 3").unwrap(), 1e-5));
+        assert!(!c.approx_eq(&Constraint::from_str("x + 2.1y = 3").unwrap(), 1e-5));
+    }
+
+    #[test]
+    fn test_remove_redundant() {
+        let mut constraints = Constraints::compile("x <= 5\n y <= 3\n x <= 10\n -x >= -7").unwrap();
+        assert_eq!(constraints.remove_redundant(), 2);
+        assert_eq!(constraints, Constraints::compile("x <= 5\n y <= 3").unwrap());
+
+        // A strict inequality is tighter than a large one with the same bound
+        let mut constraints = Constraints::compile("x <= 5\n 2x < 10").unwrap();
+        assert_eq!(constraints.remove_redundant(), 1);
+        assert_eq!(constraints.original_constraints()[0].operator, Operator::Less);
+
+        // Duplicated constraints are only kept once
+        let mut constraints = Constraints::compile("x + y <= 4\n x + y <= 4").unwrap();
+        assert_eq!(constraints.remove_redundant(), 1);
+        assert_eq!(constraints.gap_variables_count(), 1);
+
+        // Non parallel or opposed constraints are kept
+        let mut constraints = Constraints::compile("x + y <= 4\n x <= 3\n x >= 1\n x + 2y <= 4").unwrap();
+        assert_eq!(constraints.remove_redundant(), 0);
+        assert_eq!(constraints.original_constraints().len(), 4);
+    }
+
+    #[test]
+    fn test_labeled_constraint() {
+        use std::str::FromStr;
+
+        let c = Constraint::from_str("capacity: 2x + y <= 10").unwrap();
+        assert_eq!(c.label, Some("capacity".to_string()));
+        assert_eq!(c.operator, Operator::LessEqual);
+        assert_eq!(c.left, LinearFunction::from_str("2x + y").unwrap());
+        assert_eq!(c.to_string(), "capacity: 2.0x + y <= 10.0");
+
+        assert!(Constraint::from_str("my budget: x <= 10").is_err());
+
+        // The label stays with its row after pivoting
+        let mut constraints = Constraints::compile("capacity: 2x + y <= 10\n y <= 3").unwrap();
+        constraints.pivot(0, &var("x"));
+        assert_eq!(constraints[0].label, Some("capacity".to_string()));
+        assert_eq!(constraints[0].left.name_single_variable(), Some(var("x")));
+        assert_eq!(constraints[1].label, None);
+    }
+
+    #[test]
+    fn test_parse_bound() {
+        let constraints = Constraints::compile("x + y <= 6\n 0 <= x <= 4\n 5 >= y >= 1").unwrap();
+        assert_eq!(constraints.gap_variables_count(), 1);
+        assert_eq!(constraints.bounds()["x"], (0.0, 4.0));
+        assert_eq!(constraints.bounds()["y"], (1.0, 5.0));
+
+        assert_eq!(parse_chain("x <= 4"), Ok(None));
+        assert!(parse_chain("0 <= x >= 4").is_err());
+        assert!(parse_chain("0 <= x <= y").is_err());
+        assert!(Constraints::compile("10 >= x <= 20").is_err());
+    }
+
+    #[test]
+    fn test_forced_negative() {
+        use std::str::FromStr;
+
+        let objective = "x + y".parse::<LinearFunction>().unwrap();
+        let constraints = Constraints::compile("x + y <= 4\n x <= -5").unwrap();
+        assert_eq!(constraints.negativity_conflict(), Some(var("x")));
+        assert_eq!(constraints.maximize(&objective).err(), Some(SimplexError::Infeasible));
+
+        // Once free, x takes the negative value it needs
+        let mut free = constraints.clone();
+        free.declare_free(&var("x"));
+        assert_eq!(free, Constraints::compile("x + y <= 4\n x <= -5\n x free").unwrap());
+        assert_eq!(free.negativity_conflict(), None);
+        let mut simplex = free.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_values(), vec![(var("x"), -5.0), (var("y"), 9.0)]);
+        assert_eq!(simplex.current_state().objective_value(), 4.0);
+
+        // A bound may let the variable be negative as well
+        let bounded = Constraints::compile("x <= -5\n -10 <= x <= 10").unwrap();
+        assert_eq!(bounded.negativity_conflict(), None);
+        for satisfiable in ["x >= -5", "x < 1", "-x <= 0", "x = 0", "x + y <= -5"] {
+            assert_eq!(Constraint::from_str(satisfiable).unwrap().forces_negative(), None, "{satisfiable}");
+        }
+        for conflicting in ["x < 0", "-x >= 1", "2x = -4"] {
+            assert_eq!(
+                Constraint::from_str(conflicting).unwrap().forces_negative(),
+                Some(var("x")),
+                "{conflicting}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_compile_with_options() {
+        let text = "x + y < 4\n 0 <= x <= 2\n x2 + x10 >= 1";
+        let default = Constraints::compile_with_options(text, &CompileOptions::default()).unwrap();
+        assert_eq!(default, Constraints::compile(text).unwrap());
+
+        let strict = CompileOptions::default().with_strict_inequalities(false);
+        assert_eq!(Constraints::compile_with_options(text, &strict), Err(SimplexError::StrictInequality(1)));
+        let without_chains = CompileOptions::default().with_chains(false);
+        assert_eq!(Constraints::compile_with_options(text, &without_chains), Err(SimplexError::InvalidLine(2)));
+        let constraints = Constraints::compile_with_options("\n x + y <= 4\n x <= 2", &strict.with_chains(false)).unwrap();
+        assert_eq!(constraints, Constraints::compile("x + y <= 4\n x <= 2").unwrap());
+        assert_eq!(Constraints::compile_with_options("x + y <= 4\n x + y 4", &CompileOptions::default()), Err(SimplexError::InvalidLine(2)));
+
+        let reserved = CompileOptions::default().with_reserved_names(vec!["x".to_string()]);
+        assert_eq!(
+            Constraints::compile_with_options("y <= 4\n 0 <= x <= 2", &reserved),
+            Err(SimplexError::ReservedVariable(var("x")))
+        );
+
+        let natural = CompileOptions::default().with_variable_order(VariableOrder::Natural);
+        let constraints = Constraints::compile_with_options(text, &natural).unwrap();
+        let program = constraints.to_program(&"x".parse().unwrap(), ObjectiveSense::Max);
+        assert_eq!(program.non_gap_variables(), vec!["x", "x2", "x10", "y"]);
+        assert_eq!(default.to_program(&"x".parse().unwrap(), ObjectiveSense::Max).non_gap_variables(), vec!["x", "x10", "x2", "y"]);
+    }
+
+    #[test]
+    fn test_maximize_min() {
+        let solve = |simplex: Result<Simplex, SimplexError>| {
+            let mut simplex = simplex?;
+            simplex.solve(PivotRule::Bland)?;
+            Ok::<_, SimplexError>((simplex.current_state().objective_value(), simplex.current_values()))
+        };
+        let expr = |s: &str| s.parse::<LinearFunction>().unwrap();
+
+        // Both expressions meet at x = 2, where the smallest one is the largest
+        let constraints = Constraints::compile("x <= 10").unwrap();
+        let (value, point) = solve(constraints.maximize_min(&[expr("x"), expr("4 - x")])).unwrap();
+        assert_eq!(value, 2.0);
+        assert_eq!(point, vec![(var("x"), 2.0)]);
+        // The level may be negative
+        let (value, _) = solve(constraints.maximize_min(&[expr("x - 5"), expr("-x - 5")])).unwrap();
+        assert_eq!(value, -5.0);
+        assert_eq!(solve(constraints.maximize_min(&[])), Err(SimplexError::Unbounded));
+
+        // The largest expression is the smallest where they meet, unless the constraints forbid it
+        let (value, point) = solve(constraints.minimize_max(&[expr("x + 1"), expr("3 - x")])).unwrap();
+        assert_eq!(value, 2.0);
+        assert_eq!(point, vec![(var("x"), 1.0)]);
+        let constraints = Constraints::compile("x >= 3\n x <= 10").unwrap();
+        let (value, point) = solve(constraints.minimize_max(&[expr("x + 1"), expr("3 - x")])).unwrap();
+        assert_eq!(value, 4.0);
+        assert_eq!(point, vec![(var("x"), 3.0)]);
+    }
+
+    #[test]
+    fn test_minimal_relaxation() {
+        // Either constraint can be loosened by the 3 units between them
+        let constraints = Constraints::compile("x <= 2\n x >= 5").unwrap();
+        let relaxation = constraints.minimal_relaxation().unwrap();
+        assert_eq!(relaxation.len(), 1);
+        assert_eq!(relaxation[0].1, 3.0);
+
+        // Equations may be loosened in both directions
+        let constraints = Constraints::compile("x + y = 10\n x <= 3\n y <= 4").unwrap();
+        let relaxation = constraints.minimal_relaxation().unwrap();
+        assert!((relaxation.iter().map(|(_, amount)| amount).sum::<Coefficient>() - 3.0).abs() < 1e-4);
+        let constraints = Constraints::compile("x + y = 1\n x >= 3").unwrap();
+        assert_eq!(constraints.minimal_relaxation().unwrap().iter().map(|(_, amount)| amount).sum::<Coefficient>(), 2.0);
+
+        // A constraint forcing a variable below 0 is loosened, but the bounds aren't
+        assert_eq!(Constraints::compile("x <= -5").unwrap().minimal_relaxation(), Ok(vec![(0, 5.0)]));
+        let constraints = Constraints::compile("0 <= x <= 2\n x >= 4").unwrap();
+        assert_eq!(constraints.minimal_relaxation(), Ok(vec![(0, 2.0)]));
+        let constraints = Constraints::compile("x + y <= 3\n x + y >= 1\n x <= 2").unwrap();
+        assert_eq!(constraints.minimal_relaxation(), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_weighted_objective() {
+        let constraints = Constraints::compile("x + y <= 4\n x <= 3\n 0 <= z <= 1").unwrap();
+        let weights = HashMap::from([(var("x"), 1.0), (var("y"), 2.0), (var("z"), -1.0)]);
+        let objective = constraints.weighted_objective(&weights);
+        assert_eq!(objective, "x + 2y - z".parse().unwrap());
+
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 8.0);
+        assert_eq!(simplex.current_values(), vec![(var("x"), 0.0), (var("y"), 4.0), (var("z"), 0.0)]);
+
+        // Variables without a weight don't count
+        let objective = constraints.weighted_objective(&HashMap::from([(var("x"), 1.0)]));
+        assert_eq!(objective, "x".parse().unwrap());
+        assert_eq!(constraints.weighted_objective(&HashMap::new()), LinearFunction::zero());
+
+        // A weight on a variable out of the constraints isn't lost, which makes it unbounded here
+        let objective = constraints.weighted_objective(&HashMap::from([(var("x"), 1.0), (var("w"), 1.0)]));
+        assert_eq!(objective, "x + w".parse().unwrap());
+        assert_eq!(constraints.maximize(&objective).err(), Some(SimplexError::Unbounded));
+    }
+
+    #[test]
+    fn test_from_matrix() {
+        // The default problem of the visualizer
+        let a = [
+            vec![1.0, 0.0, 0.0],
+            vec![0.0, 1.0, 0.0],
+            vec![1.0, 1.0, 1.0],
+            vec![0.0, 1.0, 3.0],
+        ];
+        let b = [200.0, 300.0, 400.0, 600.0];
+        let ops = [Operator::LessEqual; 4];
+        let constraints = Constraints::from_matrix(&a, &b, &ops, &["x", "y", "z"]).unwrap();
+        let expected = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        assert_eq!(constraints, expected);
+        let objective = "x + 6y + 13z".parse::<LinearFunction>().unwrap();
+        assert_eq!(constraints.max_value(&objective), Ok(3100.0));
+
+        assert_eq!(
+            Constraints::from_matrix(&a, &b[..3], &ops, &["x", "y", "z"]),
+            Err(SimplexError::DimensionMismatch { expected: 4, found: 3 })
+        );
+        assert_eq!(
+            Constraints::from_matrix(&a, &b, &ops[..1], &["x", "y", "z"]),
+            Err(SimplexError::DimensionMismatch { expected: 4, found: 1 })
+        );
+        assert_eq!(
+            Constraints::from_matrix(&a, &b, &ops, &["x", "y"]),
+            Err(SimplexError::DimensionMismatch { expected: 2, found: 3 })
+        );
+        assert_eq!(
+            Constraints::from_matrix(&a, &b, &ops, &["x", "y", "ε0"]),
+            Err(SimplexError::ReservedVariable(var("ε0")))
+        );
+        assert_eq!(Constraints::from_matrix(&[], &[], &[], &["x"]), Ok(Constraints::new()));
+    }
+
+    #[test]
+    fn test_normalized_names() {
+        let text = "x  + y <= 4\n 2x <= 3\n X >= 1";
+        let constraints = Constraints::compile_with_options(text, &CompileOptions::default()).unwrap();
+        assert_eq!(constraints.non_gap_variables().into_iter().sorted().collect::<Vec<_>>(), vec!["X", "x", "y"]);
+        assert!(constraints.merged_variables().is_empty());
+
+        // The reserved names are trimmed unless told otherwise, ` x ` then reserving `x`
+        let reserved = CompileOptions::default().with_reserved_names(vec![" x ".to_string()]);
+        assert_eq!(
+            Constraints::compile_with_options(text, &reserved),
+            Err(SimplexError::ReservedVariable(var("x")))
+        );
+        let untrimmed = reserved.clone().with_trimmed_names(false);
+        assert_eq!(Constraints::compile_with_options(text, &untrimmed).unwrap(), Constraints::compile(text).unwrap());
+        let untrimmed = untrimmed.with_reserved_names(vec!["y".to_string()]);
+        assert_eq!(
+            Constraints::compile_with_options(text, &untrimmed),
+            Err(SimplexError::ReservedVariable(var("y")))
+        );
+
+        // With the case folded, `X` and `x` are merged, even within a line
+        let folded = CompileOptions::default().with_case_folding(true);
+        let constraints = Constraints::compile_with_options(text, &folded).unwrap();
+        assert_eq!(constraints.non_gap_variables().into_iter().sorted().collect::<Vec<_>>(), vec!["x", "y"]);
+        assert_eq!(constraints.merged_variables(), [(var("x"), vec![var("X"), var("x")])]);
+        let constraints = Constraints::compile_with_options("X + x <= 4\n 0 <= Y <= 1", &folded).unwrap();
+        assert_eq!(constraints.original_constraints(), Constraints::compile("2x <= 4").unwrap().original_constraints());
+        assert_eq!(constraints.bounds(), &HashMap::from([(var("y"), (0.0, 1.0))]));
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+        use std::str::FromStr;
+
+        let hash = |c: &str| {
+            let mut hasher = DefaultHasher::new();
+            Constraint::from_str(c).unwrap().hash(&mut hasher);
+            hasher.finish()
+        };
+        let equal = |a: &str, b: &str| Constraint::from_str(a).unwrap() == Constraint::from_str(b).unwrap();
+
+        for (a, b) in [("2x <= 10", "x <= 5"), ("x - y = 2", "-2x + 2y = -4"), ("x >= 1", "-x <= -1"), ("x - x + y < 1", "y < 1")] {
+            assert!(equal(a, b), "{a} and {b}");
+            assert_eq!(hash(a), hash(b), "{a} and {b}");
+        }
+        for (a, b) in [("x <= 5", "x < 5"), ("x <= 5", "x <= 6"), ("x <= 5", "y <= 5")] {
+            assert!(!equal(a, b), "{a} and {b}");
+        }
+
+        // Labels are ignored
+        let labeled = Constraint::from_str("cap: x <= 5").unwrap();
+        assert_eq!(hash("cap: x <= 5"), hash("x <= 5"));
+        assert!(HashSet::from([labeled]).contains(&Constraint::from_str("2x <= 10").unwrap()));
+    }
+
+    #[test]
+    fn test_find_feasible_point() {
+        let constraints = Constraints::compile("x + y >= 4\n x - y >= 1\n 2x + y >= 5\n x + 2y <= 10").unwrap();
+        let point = constraints.find_feasible_point().unwrap();
+        assert_eq!(point.keys().sorted().collect::<Vec<_>>(), vec!["x", "y"]);
+        assert!(constraints
+            .original_constraints()
+            .iter()
+            .all(|constraint| constraint.is_satisfied_at(&point)));
+
+        // The origin is returned when it is feasible
+        let constraints = Constraints::compile("x + y <= 4").unwrap();
+        assert_eq!(constraints.find_feasible_point().unwrap(), HashMap::from([(var("x"), 0.0), (var("y"), 0.0)]));
+
+        for contradictory in ["x + y >= 4\n x + y <= 2", "x <= -5", "x + y = 3\n x >= 2\n y >= 2"] {
+            let constraints = Constraints::compile(contradictory).unwrap();
+            assert_eq!(constraints.find_feasible_point(), Err(SimplexError::Infeasible), "{contradictory}");
+        }
+    }
+
+    #[test]
+    fn test_parse_range() {
+        use std::str::FromStr;
+
+        let constraints = Constraints::compile("range: 0 <= x + y <= 10
+ 8 > x - y >= 2").unwrap();
+        assert!(constraints.bounds().is_empty());
+        assert_eq!(
+            constraints.original_constraints(),
+            &[
+                Constraint::from_str("range: x + y >= 0").unwrap(),
+                Constraint::from_str("range: x + y <= 10").unwrap(),
+                Constraint::from_str("x - y >= 2").unwrap(),
+                Constraint::from_str("x - y < 8").unwrap(),
+            ]
+        );
+
+        assert!(Constraints::compile("0 <= x + y >= 5").is_err());
+        assert!(Constraints::compile("bad label: 0 <= x + y <= 5").is_err());
+    }
+
+    #[test]
+    fn test_checked_access() {
+        let mut constraints = Constraints::compile("budget: x + y <= 4\n x <= 3").unwrap();
+        assert_eq!(constraints.len(), 2);
+        assert!(!constraints.is_empty());
+        assert!(Constraints::new().is_empty());
+
+        assert_eq!(constraints.get(0).unwrap().label, Some("budget".to_string()));
+        assert!(constraints.get(2).is_none());
+
+        constraints.get_mut(1).unwrap().label = Some("capacity".to_string());
+        assert_eq!(constraints[1].label, Some("capacity".to_string()));
+        assert!(constraints.get_mut(2).is_none());
+    }
+
+    #[test]
+    fn test_scale_to_integers() {
+        use std::str::FromStr;
+
+        let mut constraints = Constraints::compile("0.5x + 0.25y <= 1\n 1.5x <= 6\n x - 0.2 >= 0.1y").unwrap();
+        let before = constraints.clone();
+        constraints.scale_to_integers();
+
+        assert_eq!(constraints.original_constraints()[0].to_string(), "2.0x + y <= 4.0");
+        for constraint in constraints.original_constraints() {
+            for f in [&constraint.left, &constraint.right] {
+                assert!(f.var_iter().all(|var| f[var].fract() == 0.0));
+                assert_eq!(f.constant.fract(), 0.0);
+            }
+        }
+
+        // The feasible set is unchanged
+        for x in [-1.0, 0.0, 0.5, 1.0, 2.0, 6.0, 7.0] {
+            for y in [-1.0, 0.0, 1.0, 2.0, 4.0] {
+                let valuation = HashMap::from([(var("x"), x), (var("y"), y)]);
+                for (a, b) in before.original_constraints().iter().zip(constraints.original_constraints()) {
+                    assert_eq!(a.is_satisfied_at(&valuation), b.is_satisfied_at(&valuation));
+                }
+            }
+        }
+
+        let c = Constraint::from_str("x - y <= 2").unwrap();
+        assert_eq!(c.scale_by(-1.0), Constraint::from_str("-x + y >= -2").unwrap());
+        assert_eq!(c.scale_by(-1.0).operator, Operator::GreaterEqual);
+        assert_eq!(c.scale_by(3.0).operator, Operator::LessEqual);
+    }
+
+    #[test]
+    fn test_all_variables() {
+        let constraints = Constraints::compile("x + y <= 4\n x >= 1").unwrap();
+        assert_eq!(constraints.all_variables(), vec!["x", "y", "ε0", "ε1"]);
+        assert_eq!(constraints.non_gap_variables().into_iter().sorted().collect::<Vec<_>>(), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn test_active_at() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let mut simplex = constraints
+            .maximize(&"x + 6y + 13z".parse::<LinearFunction>().unwrap())
+            .unwrap();
+        simplex.solve(crate::PivotRule::Bland).unwrap();
+
+        let optimum = simplex.current_state().valuation();
+        assert_eq!(constraints.active_at(&optimum, 1e-4), vec![1, 2, 3]);
+        // Only the non negativity constraints hold with equality at the origin
+        assert!(constraints.active_at(&HashMap::new(), 1e-4).is_empty());
+    }
+
+    #[test]
+    fn test_equality_constraint() {
+        use crate::PivotRule;
+        let constraints = Constraints::compile("x + y = 10\n y <= 4").unwrap();
+        assert_eq!(constraints.all_variables(), vec!["x", "y", "ε0", "ε1", "ε2"]);
+        assert_eq!(constraints[0].right, -constraints[1].right.clone());
+
+        let objective = "y - x".parse::<LinearFunction>().unwrap();
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_point(), vec![6.0, 4.0]);
+        assert_eq!(simplex.current_state().objective_value(), -2.0);
+
+        // As an inequality, the origin would be in the region
+        let constraints = Constraints::compile("x + y <= 10\n y <= 4").unwrap();
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_point(), vec![0.0, 4.0]);
+    }
+
+    #[test]
+    fn test_remove_constraint() {
+        use crate::PivotRule;
+        let objective = "x + 6y + 13z".parse::<LinearFunction>().unwrap();
+        let optimum = |constraints: &Constraints| {
+            let mut simplex = constraints.maximize(&objective).unwrap();
+            simplex.solve(PivotRule::Bland).unwrap();
+            simplex.current_state().objective_value()
+        };
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        assert_eq!(optimum(&constraints), 3100.0);
+
+        // x <= 200 doesn't hold with equality at the optimum
+        let mut removed = constraints.clone();
+        assert_eq!(removed.remove_constraint(0).unwrap().to_string(), "x <= 200.0");
+        assert_eq!(removed.original_constraints().len(), 3);
+        assert_eq!(removed.all_variables(), vec!["x", "y", "z", "ε0", "ε1", "ε2"]);
+        assert_eq!(optimum(&removed), 3100.0);
+
+        // y + 3z <= 600 does, and the optimum grows without it
+        let mut removed = constraints.clone();
+        removed.remove_constraint(3).unwrap();
+        assert_eq!(optimum(&removed), 5200.0);
+
+        let mut removed = constraints.clone();
+        assert!(matches!(removed.remove_constraint(4), Err(SimplexError::NoSuchConstraint(4))));
+
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        let mut pivoted = simplex.current_state().constraints.clone();
+        assert!(matches!(pivoted.remove_constraint(0), Err(SimplexError::PivotedTableau)));
+    }
+
+    #[test]
+    fn test_rename_variable() {
+        let mut constraints = Constraints::compile("x + 2y <= 4\n x - z >= 1").unwrap();
+        constraints.rename_variable("x", "y").unwrap();
+        assert_eq!(constraints, Constraints::compile("3y <= 4\n y - z >= 1").unwrap());
+
+        // Renaming a variable which doesn't appear changes nothing
+        let before = constraints.clone();
+        constraints.rename_variable("w", "x").unwrap();
+        assert_eq!(constraints, before);
+
+        assert!(matches!(
+            constraints.rename_variable("y", "ε0"),
+            Err(SimplexError::ReservedVariable(name)) if name == "ε0"
+        ));
+        // The new name is checked even without any constraint
+        assert!(matches!(
+            Constraints::new().rename_variable("y", "ε0"),
+            Err(SimplexError::ReservedVariable(name)) if name == "ε0"
+        ));
+        assert_eq!(Constraints::new().rename_variable("y", "2y"), Err(SimplexError::InvalidVariable("2y".to_string())));
+
+        // The tableau is rebuilt from the original constraints
+        let mut constraints = Constraints::compile("x + 2y <= 4\n x - z = 1").unwrap();
+        constraints.rename_variable("z", "w").unwrap();
+        assert_eq!(constraints, Constraints::compile("x + 2y <= 4\n x - w = 1").unwrap());
+        let mut program = constraints.to_program(&"x".parse().unwrap(), ObjectiveSense::Max);
+        program.pivot(var("x")).unwrap();
+        assert_eq!(program.constraints.rename_variable("x", "v"), Err(SimplexError::PivotedTableau));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        assert_eq!(
+            constraints.bounding_box(),
+            Some(vec![(0.0, 200.0), (0.0, 300.0), (0.0, 200.0)])
+        );
+
+        // y can grow along with x as long as x stays below 2
+        let constraints = Constraints::compile("x <= 2\n x - y <= 1").unwrap();
+        assert_eq!(
+            constraints.bounding_box(),
+            Some(vec![(0.0, 2.0), (0.0, Coefficient::INFINITY)])
+        );
+
+        let constraints = Constraints::compile("x <= 1\n x >= 2").unwrap();
+        assert_eq!(constraints.bounding_box(), None);
+    }
+
+    #[test]
+    fn test_max_min_value() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let objective = |s: &str| s.parse::<LinearFunction>().unwrap();
+        assert_eq!(constraints.max_value(&objective("x + 6y + 13z")).unwrap(), 3100.0);
+        assert_eq!(constraints.max_value(&objective("x + y")).unwrap(), 400.0);
+        assert_eq!(constraints.min_value(&objective("x + y + 1")).unwrap(), 1.0);
+        assert_eq!(constraints.min_value(&objective("-x - z")).unwrap(), -400.0);
+
+        let unbounded = Constraints::compile("x - y <= 1").unwrap();
+        assert!(matches!(unbounded.max_value(&objective("y")), Err(SimplexError::Unbounded)));
+        let infeasible = Constraints::compile("x <= 1\n x >= 2").unwrap();
+        assert!(matches!(infeasible.min_value(&objective("x")), Err(SimplexError::Infeasible)));
+    }
+
+    #[test]
+    fn test_validity_report() {
+        let tolerances = Tolerances::default();
+        let mut constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        assert!(constraints.validity_report(&tolerances).is_empty());
+
+        // A pivot which forgot to normalize the row of the entering variable
+        constraints[3] = Constraint::new(
+            LinearFunction::term(1.0, var("ε3")) + LinearFunction::term(-3.0, var("z")),
+            Operator::Equal,
+            "600 - y".parse().unwrap(),
+        );
+        constraints[2].operator = Operator::LessEqual;
+        let report = constraints.validity_report(&tolerances);
+        assert_eq!(
+            report.violations,
+            vec![
+                RowViolation::NotAnEquation { row: 2 },
+                RowViolation::NotBasic { row: 3, residual: 3.0 }
+            ]
+        );
+        assert!(!constraints.is_valid(&tolerances));
+        assert_eq!(
+            report.to_string(),
+            "row 2 is not an equation\nrow 3 is 3 away from having a single basic variable\n"
+        );
+
+        // Rounding errors are accepted up to the tolerance
+        constraints[2].operator = Operator::Equal;
+        constraints[3] = Constraint::new(
+            LinearFunction::term(1.0000001, var("ε3")),
+            Operator::Equal,
+            "600 - y - 3z".parse().unwrap(),
+        );
+        assert!(constraints.is_valid(&tolerances));
+        // A basic solution violating a constraint, as the first step of phase one
+        let constraints = Constraints::compile("x + y >= 2\n x <= 3").unwrap();
+        let report = constraints.validity_report(&tolerances);
+        assert_eq!(report.violations, vec![RowViolation::Violated { row: 0, amount: 2.0 }]);
+        assert!(report.is_basic());
+        assert_eq!(report.to_string(), "row 0 is violated by 2\n");
+    }
+
+    #[test]
+    fn test_vertices() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let vertices = constraints.vertices();
+        assert_eq!(vertices.len(), 8);
+        assert_eq!(vertices[0], vec![0.0, 0.0, 0.0]);
+        // The optimum of `x + 6y + 13z` and the vertex where z is the largest
+        assert!(vertices.contains(&vec![0.0, 300.0, 100.0]));
+        assert!(vertices.contains(&vec![200.0, 0.0, 200.0]));
+
+        // Bounds replace the non negativity of their variable
+        let constraints = Constraints::compile("-1 <= x <= 1\n x + y <= 2").unwrap();
+        assert_eq!(
+            constraints.vertices(),
+            vec![vec![-1.0, 0.0], vec![-1.0, 3.0], vec![1.0, 0.0], vec![1.0, 1.0]]
+        );
+
+        // And so does the declaration of a free variable
+        let constraints = Constraints::compile("x >= -2\n x <= 1\n x + y <= 2\n x free").unwrap();
+        assert_eq!(
+            constraints.vertices(),
+            vec![vec![-2.0, 0.0], vec![-2.0, 4.0], vec![1.0, 0.0], vec![1.0, 1.0]]
+        );
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_vertices_parallel() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        assert_eq!(constraints.vertices_parallel(), constraints.vertices());
+    }
+
+    #[test]
+    fn test_residual() {
+        let constraints =
+            Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600\n x + z >= 50").unwrap();
+        let residuals = |point: &HashMap<Variable, Coefficient>| {
+            constraints.original_constraints().iter().map(|c| c.residual(point)).collect::<Vec<_>>()
+        };
+
+        // The origin doesn't satisfy the last constraint
+        let origin = HashMap::new();
+        assert_eq!(residuals(&origin), vec![200.0, 300.0, 400.0, 600.0, -50.0]);
+
+        let optimum = HashMap::from([(var("y"), 300.0), (var("z"), 100.0)]);
+        assert_eq!(residuals(&optimum), vec![200.0, 0.0, 0.0, 0.0, 50.0]);
+        assert_eq!(constraints.active_at(&optimum, 1e-6), vec![1, 2, 3]);
+
+        // An equation is satisfied only when its residual is zero
+        let equation = "x - y = 1".parse::<Constraint>().unwrap();
+        assert_eq!(equation.residual(&optimum), 301.0);
+        assert!(!equation.is_satisfied_at(&optimum));
+    }
+
+    #[test]
+    fn test_as_line_2d() {
+        let constraint = "2x + 3y <= 6".parse::<Constraint>().unwrap();
+        assert_eq!(constraint.as_line_2d("x", "y"), Some((2.0, 3.0, 6.0)));
+        // The variables may be on both sides
+        let constraint = "2x + 1 >= 3y - 5".parse::<Constraint>().unwrap();
+        assert_eq!(constraint.as_line_2d("y", "x"), Some((-3.0, 2.0, -6.0)));
+        assert_eq!("x + y + z <= 1".parse::<Constraint>().unwrap().as_line_2d("x", "y"), None);
+        assert_eq!("x <= 1".parse::<Constraint>().unwrap().as_line_2d("y", "z"), None);
+    }
+
+    #[test]
+    fn test_from_constraints() {
+        let input = "x <= 200\n y <= 300\n x + y + z <= 400\n budget: y + 3z = 600\n x + z >= 50";
+        let compiled = Constraints::compile(input).unwrap();
+        let constraints: Vec<Constraint> = input.lines().map(|line| line.parse().unwrap()).collect();
+
+        let from_vec = Constraints::from(constraints.clone());
+        assert_eq!(from_vec, compiled);
+        assert_eq!(from_vec.original_constraints(), compiled.original_constraints());
+        assert_eq!(from_vec.gap_variables_count(), 6);
+
+        let collected: Constraints = constraints.into_iter().collect();
+        assert_eq!(collected, compiled);
+        // The origin violates the last two constraints, but the rows are all basic
+        assert!(collected.validity_report(&Tolerances::default()).is_basic());
+    }
+
+    #[test]
+    fn test_make_rhs_nonnegative() {
+        use std::str::FromStr;
+        let constraint = Constraint::from_str("x - y >= -3").unwrap();
+        let normalized = constraint.make_rhs_nonnegative();
+        assert_eq!(normalized.left, LinearFunction::from_str("-x + y").unwrap());
+        assert_eq!(normalized.operator, Operator::LessEqual);
+        assert_eq!(normalized.right, LinearFunction::constant(3.0));
+        // The same points satisfy both constraints
+        for point in [[0.0, 0.0], [1.0, 4.0], [0.0, 3.0], [0.0, 5.0]] {
+            let valuation = HashMap::from([(var("x"), point[0]), (var("y"), point[1])]);
+            assert_eq!(constraint.is_satisfied_at(&valuation), normalized.is_satisfied_at(&valuation));
+        }
+
+        // Constants are moved to the right, and kept if already non negative
+        let constraint = Constraint::from_str("2x + 1 <= y + 5").unwrap().with_label("c");
+        assert_eq!(constraint.make_rhs_nonnegative().to_string(), "c: 2.0x - y <= 4.0");
+
+        let mut constraints = Constraints::compile("x - y >= -3\n x + y = 2").unwrap();
+        let tableau = constraints.clone();
+        constraints.normalize_rhs_nonnegative();
+        assert!(constraints.original_constraints().iter().all(|c| c.right.constant >= 0.0));
+        assert_eq!(constraints.original_constraints()[0].operator, Operator::LessEqual);
+        for (row, expected) in constraints.iter().zip(tableau.iter()) {
+            assert!(row.right.approx_eq(&expected.right, 0.0));
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut production = Constraints::compile("x <= 200\n y <= 300\n 0 <= z <= 150").unwrap();
+        let resources = Constraints::compile("x + y + z <= 400\n y + 3z <= 600\n 0 <= z <= 120").unwrap();
+        production.merge(resources).unwrap();
+
+        assert_eq!(production.original_constraints().len(), 4);
+        assert_eq!(production.gap_variables_count(), 4);
+        assert_eq!(production.bounds()["z"], (0.0, 120.0));
+        assert!(production.is_valid(&Tolerances::default()));
+
+        let mut simplex = production.maximize(&"x + 6y + 13z".parse().unwrap()).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 3100.0);
+
+        let mut pivoted = Constraints::compile("x <= 2").unwrap();
+        pivoted.pivot(0, &var("x"));
+        let mut constraints = Constraints::compile("y <= 2").unwrap();
+        assert_eq!(constraints.merge(pivoted), Err(SimplexError::PivotedTableau));
+        assert_eq!(constraints.len(), 1);
+    }
 }
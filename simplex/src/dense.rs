@@ -0,0 +1,326 @@
+//! A dense tableau, stored as a row-major matrix, on which the pivots of large dense programs are
+//! much cheaper than on the hash maps of `LinearFunction`
+use crate::linear_function::{Coefficient, LinearFunction, Variable, ZERO_TOLERANCE};
+use crate::{LinearProgram, NumericalWarning, PivotRecord, PivotRule, Simplex, SimplexError, Tolerances};
+use std::cell::OnceCell;
+use std::collections::BTreeSet;
+
+/// The representation the pivots of [`Simplex::solve`] are computed on, see
+/// [`Simplex::with_backend`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TableauBackend {
+    /// Every row is a `LinearFunction`, which suits small and sparse programs, and every step is
+    /// recorded
+    #[default]
+    Map,
+    /// The rows form a dense matrix, which pivots faster on large dense programs. Every pivot is
+    /// recorded in the pivot log, but only the last step of a solve is written back from the
+    /// matrix: the steps before it are rebuilt on the maps the first time they are looked at, and
+    /// `solve_with` only calls back for the last one
+    ///
+    /// Only phase two and single phase solves with Dantzig's or Bland's rule, on programs without
+    /// bounded variables, are computed this way. Everything else falls back to `Map`
+    Dense,
+}
+
+/// A tableau in dictionary form, where `basic[i] = constants[i] + Σ matrix[i][j] * columns[j]`,
+/// maximizing `objective_constant + Σ objective[j] * columns[j]`
+///
+/// The columns are sorted alphabetically, and cover the basic variables as well, whose column is
+/// empty, so that the pivot rules break ties as on the maps
+#[derive(Debug, Clone)]
+pub(crate) struct DenseTableau {
+    columns: Vec<Variable>,
+    basic: Vec<Variable>,
+    constants: Vec<Coefficient>,
+    matrix: Vec<Coefficient>,
+    objective: Vec<Coefficient>,
+    objective_constant: Coefficient,
+}
+
+/// Drops the values which the maps would remove with `LinearFunction::simplify`
+fn simplify(values: &mut [Coefficient]) {
+    for value in values.iter_mut().filter(|value| value.abs() <= ZERO_TOLERANCE) {
+        *value = 0.0;
+    }
+}
+
+/// Builds a linear function from the constant and the non zero coefficients of `values`
+fn to_function(constant: Coefficient, columns: &[Variable], values: &[Coefficient]) -> LinearFunction {
+    LinearFunction::new(
+        constant,
+        columns
+            .iter()
+            .zip(values)
+            .filter(|(_, value)| **value != 0.0)
+            .map(|(var, value)| (var.clone(), *value))
+            .collect(),
+    )
+}
+
+impl DenseTableau {
+    /// Copies the tableau of `program`, or returns `None` if some row isn't of the form
+    /// `basic = function`
+    pub(crate) fn from_program(program: &LinearProgram) -> Option<DenseTableau> {
+        let basic = program
+            .constraints
+            .iter()
+            .map(|constraint| constraint.left.name_single_variable())
+            .collect::<Option<Vec<_>>>()?;
+        let columns: Vec<Variable> = program
+            .constraints
+            .iter()
+            .flat_map(|constraint| constraint.right.var_iter())
+            .chain(program.linear_function.var_iter())
+            .chain(basic.iter())
+            .cloned()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        let row = |function: &LinearFunction| columns.iter().map(|var| function[var]).collect::<Vec<_>>();
+        Some(DenseTableau {
+            basic,
+            constants: program.constraints.iter().map(|c| c.right.constant).collect(),
+            matrix: program.constraints.iter().flat_map(|c| row(&c.right)).collect(),
+            objective: row(&program.linear_function),
+            objective_constant: program.linear_function.constant,
+            columns,
+        })
+    }
+
+    fn row(&self, i: usize) -> &[Coefficient] {
+        &self.matrix[i * self.columns.len()..(i + 1) * self.columns.len()]
+    }
+
+    /// Returns the column entering the base according to `rule`, which must be Dantzig's or
+    /// Bland's rule, as [`PivotRule::entering_variable`] would
+    pub(crate) fn entering(&self, rule: PivotRule, zero: Coefficient) -> Option<usize> {
+        let improving = self.objective.iter().enumerate().filter(|(_, coeff)| **coeff > zero);
+        match rule {
+            PivotRule::Bland => improving.map(|(j, _)| j).next(),
+            _ => improving
+                .fold(None, |best: Option<(usize, Coefficient)>, (j, coeff)| match best {
+                    Some((_, best_coeff)) if best_coeff >= *coeff => best,
+                    _ => Some((j, *coeff)),
+                })
+                .map(|(j, _)| j),
+        }
+    }
+
+    /// Returns the row leaving the base when `column` enters it, as
+    /// [`crate::constraint::Constraints::most_restrictive`] would
+    pub(crate) fn leaving(&self, column: usize, tolerances: &Tolerances) -> Option<usize> {
+        (0..self.basic.len())
+            .filter_map(|i| {
+                let coefficient = self.row(i)[column];
+                (coefficient < -tolerances.zero).then(|| (i, self.constants[i] / -coefficient))
+            })
+            .fold(None, |best: Option<(usize, Coefficient)>, (i, ratio)| match best {
+                Some((_, best_ratio)) if ratio > best_ratio + tolerances.ratio => best,
+                _ => Some((i, ratio)),
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Pivots `column` into the base in place of the basic variable of `row`
+    pub(crate) fn pivot(&mut self, row: usize, column: usize) {
+        let width = self.columns.len();
+        let leaving = self.columns.binary_search(&self.basic[row]).expect("basic variables have a column");
+        let pivot_element = self.matrix[row * width + column];
+
+        // `entering = -constant / a - Σ (coefficient / a) * var + leaving / a`
+        let pivot_row = &mut self.matrix[row * width..(row + 1) * width];
+        for coefficient in pivot_row.iter_mut() {
+            *coefficient = -(*coefficient / pivot_element);
+        }
+        pivot_row[column] = 0.0;
+        pivot_row[leaving] = 1.0 / pivot_element;
+        simplify(pivot_row);
+        self.constants[row] = -(self.constants[row] / pivot_element);
+        self.basic[row] = self.columns[column].clone();
+
+        // Substitutes the new row for the entering variable everywhere else
+        let pivot_row = self.row(row).to_vec();
+        let pivot_constant = self.constants[row];
+        let substitute = |values: &mut [Coefficient], constant: &mut Coefficient| {
+            let factor = values[column];
+            if factor != 0.0 {
+                values[column] = 0.0;
+                for (value, pivot_value) in values.iter_mut().zip(pivot_row.iter()) {
+                    *value += pivot_value * factor;
+                }
+                *constant += pivot_constant * factor;
+                simplify(values);
+            }
+        };
+        for i in (0..self.basic.len()).filter(|i| *i != row) {
+            substitute(&mut self.matrix[i * width..(i + 1) * width], &mut self.constants[i]);
+        }
+        substitute(&mut self.objective, &mut self.objective_constant);
+    }
+
+    /// Writes the rows and the objective back into `program`, whose tableau this was copied from
+    pub(crate) fn write_to(&self, program: &mut LinearProgram) {
+        for (i, basic) in self.basic.iter().enumerate() {
+            program.constraints[i].left = LinearFunction::single_variable(basic.clone());
+            program.constraints[i].right = to_function(self.constants[i], &self.columns, self.row(i));
+        }
+        program.linear_function = to_function(self.objective_constant, &self.columns, &self.objective);
+    }
+}
+
+impl Simplex {
+    /// Computes every pivot from the current step, which must be the last one, to the optimum on
+    /// `tableau`, which stays the only copy of the tableau during the solve
+    ///
+    /// Each pivot is recorded in the pivot log along with an empty step of the historic, which
+    /// [`Simplex::state`] rebuilds on the maps if it is ever looked at. Only the last step is
+    /// written back from the matrix, and given to `on_step`
+    pub(crate) fn solve_dense(
+        &mut self,
+        mut tableau: DenseTableau,
+        rule: PivotRule,
+        on_step: &mut impl FnMut(&LinearProgram, usize),
+    ) -> Result<(), SimplexError> {
+        let tolerances = self.tolerances();
+        let (phase, sign) = (self.current_phase(), self.current_state().sense.sign());
+        let start = self.index;
+        let result = loop {
+            let Some(column) = tableau.entering(rule, tolerances.zero) else {
+                break Ok(());
+            };
+            let Some(row) = tableau.leaving(column, &tolerances) else {
+                break Err(SimplexError::Unbounded);
+            };
+            let entering = tableau.columns[column].clone();
+            let leaving = tableau.basic[row].clone();
+            let pivot_element = tableau.row(row)[column];
+            if pivot_element.abs() < tolerances.pivot {
+                self.warnings.push(NumericalWarning::SmallPivot {
+                    step: self.historic.len(),
+                    entering: entering.clone(),
+                    pivot_element,
+                });
+            }
+            let objective_before = tableau.objective_constant;
+            tableau.pivot(row, column);
+
+            self.log.push(PivotRecord {
+                entering,
+                leaving: Some(leaving),
+                pivot_element: Some(pivot_element),
+                objective_value: sign * tableau.objective_constant,
+            });
+            self.phases.push(phase);
+            self.degenerate.push((tableau.objective_constant - objective_before).abs() <= tolerances.zero);
+            self.historic.push(OnceCell::new());
+        };
+
+        let last = self.historic.len() - 1;
+        if last > start {
+            let mut state = self.state(start).clone();
+            tableau.write_to(&mut state);
+            let _ = self.historic[last].set(state);
+            self.index = last;
+            self.evict_old_steps();
+            on_step(self.current_state(), self.index);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_function::var;
+    use crate::generators::random_feasible_lp;
+    use crate::SimplexStatus;
+
+    /// Solves `program` with both backends, returning the optimal values and objective value
+    fn solve_both(program: &LinearProgram, rule: PivotRule) -> [(Vec<(Variable, Coefficient)>, Coefficient); 2] {
+        [TableauBackend::Map, TableauBackend::Dense].map(|backend| {
+            let mut simplex = Simplex::from(program.clone()).with_backend(backend);
+            simplex.solve(rule).unwrap();
+            assert_eq!(simplex.status(), SimplexStatus::Optimal);
+            (simplex.current_values(), simplex.current_state().objective_value())
+        })
+    }
+
+    #[test]
+    fn test_same_optimum() {
+        let programs = [
+            "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600",
+            "max 4x + 2y + z\n x <= 5\n 4x + y <= 25\n 8x + 4y + z <= 125",
+            // Phase one is done on the maps
+            "min x + y\n x + y >= 2\n x <= 3\n x - y = 1",
+            "min 3x + 2y\n x + y >= 4\n x <= 3\n y <= 3",
+        ];
+        for program in programs {
+            let program = program.parse::<LinearProgram>().unwrap();
+            for rule in [PivotRule::Dantzig, PivotRule::Bland] {
+                let [map, dense] = solve_both(&program, rule);
+                assert_eq!(map, dense, "{program}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_generated_problems() {
+        for seed in 0..4 {
+            let program = random_feasible_lp(50, 50, seed);
+            let [(map_values, map_objective), (dense_values, dense_objective)] =
+                solve_both(&program, PivotRule::Dantzig);
+            assert!((map_objective - dense_objective).abs() <= 1e-3 * map_objective.abs().max(1.0));
+            for ((var, map_value), (_, dense_value)) in map_values.iter().zip(dense_values.iter()) {
+                assert!((map_value - dense_value).abs() <= 1e-3, "{var}: {map_value} and {dense_value}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_dense_steps() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut map = Simplex::from(program.clone());
+        map.solve(PivotRule::Dantzig).unwrap();
+        let mut dense = Simplex::from(program.clone()).with_backend(TableauBackend::Dense);
+        let mut steps = vec![];
+        dense.solve_with(PivotRule::Dantzig, |_, index| steps.push(index)).unwrap();
+
+        // Every pivot is recorded as a step, as on the maps, but only the last one is reported
+        assert_eq!(steps, vec![map.pivot_log().len()]);
+        assert_eq!(dense.pivot_log(), map.pivot_log());
+        assert_eq!(dense.degenerate_step_indices(), map.degenerate_step_indices());
+        assert_eq!(dense.objective_history(), map.objective_history());
+        assert!(dense.current_state().is_valid());
+        assert_eq!(dense.next_step(PivotRule::Dantzig), Err(SimplexError::AlreadyOptimal));
+        let replayed = Simplex::replay(program.clone(), dense.pivot_log()).unwrap();
+        assert_eq!(replayed.current_values(), dense.current_values());
+
+        // The steps skipped on the matrix are rebuilt as the maps computed them
+        let states = |simplex: &Simplex| simplex.states().map(LinearProgram::to_string).collect::<Vec<_>>();
+        let map_states = states(&map);
+        dense.previous_step();
+        assert_eq!(dense.current_state().to_string(), map_states[map_states.len() - 2]);
+        assert_eq!(states(&dense), map_states);
+
+        // A history limit keeps the last steps, the oldest one kept being rebuilt
+        let mut limited = Simplex::from(program).with_backend(TableauBackend::Dense).with_history_limit(2);
+        limited.solve(PivotRule::Dantzig).unwrap();
+        assert_eq!(states(&limited), map_states[map_states.len() - 2..]);
+        assert_eq!(limited.pivot_log(), &map.pivot_log()[map.pivot_log().len() - 1..]);
+
+        // The steps computed before are kept when the program is unbounded
+        let program = "max x + y\n x - y <= 1".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program).with_backend(TableauBackend::Dense);
+        assert_eq!(simplex.solve(PivotRule::Dantzig), Err(SimplexError::Unbounded));
+        assert_eq!(simplex.current_values(), vec![(var("x"), 1.0), (var("y"), 0.0)]);
+
+        // Bounded variables fall back to the maps
+        let program = "max x + y\n x + y <= 4\n 0 <= x <= 1".parse::<LinearProgram>().unwrap();
+        let [map, dense] = solve_both(&program, PivotRule::Dantzig);
+        assert_eq!(map, dense);
+    }
+}
@@ -1,5 +1,196 @@
-#[derive(Debug)]
+use crate::linear_function::{Coefficient, Variable};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum SimplexError {
     Unbounded,
-    AlreadyOptimal
-}
\ No newline at end of file
+    AlreadyOptimal,
+    /// The current step isn't optimal yet, so it has no optimal basis
+    NotOptimal,
+    /// Phase one ended without finding any point satisfying all the constraints
+    Infeasible,
+    /// There is no constraint at this index
+    NoSuchConstraint(usize),
+    /// This step wasn't computed
+    NoSuchStep(usize),
+    /// The tableau was pivoted, so its rows can't be edited anymore
+    PivotedTableau,
+    /// This name is reserved to the gap variables
+    ReservedVariable(Variable),
+    /// The program can only be drawn with 2 decision variables, not this many
+    NotTwoDimensional(usize),
+    /// This line of the compiled constraints, starting from 1, can't be parsed
+    InvalidLine(usize),
+    /// This line of the compiled constraints, starting from 1, uses `<` or `>` while strict
+    /// inequalities aren't allowed
+    StrictInequality(usize),
+    /// Increasing this variable wouldn't improve the objective, so it can't enter the base
+    NotImproving(Variable),
+    /// The optimum wasn't reached within the allowed number of steps
+    IterationLimit,
+    /// The optimum wasn't reached within the allowed duration
+    TimedOut,
+    /// A matrix or vector was given this many values where that many were expected
+    DimensionMismatch { expected: usize, found: usize },
+    /// The pivot at this index of a replayed log isn't valid for the state it is applied to
+    InvalidPivot(usize),
+    /// This name isn't a valid variable name, see [`Variable::new`]
+    InvalidVariable(String),
+    /// This variable already has an integral value, so there is nothing to branch on
+    AlreadyIntegral(Variable),
+    /// This variable doesn't appear in the program
+    UnknownVariable(Variable),
+}
+
+impl std::fmt::Display for SimplexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimplexError::Unbounded => write!(f, "the program is unbounded"),
+            SimplexError::AlreadyOptimal => write!(f, "the optimum is already reached"),
+            SimplexError::NotOptimal => write!(f, "the optimum isn't reached yet"),
+            SimplexError::Infeasible => write!(f, "the program is infeasible"),
+            SimplexError::NoSuchConstraint(index) => write!(f, "there is no constraint #{index}"),
+            SimplexError::NoSuchStep(index) => write!(f, "step {index} wasn't computed"),
+            SimplexError::PivotedTableau => {
+                write!(f, "the tableau was pivoted, its constraints can't be edited anymore")
+            }
+            SimplexError::ReservedVariable(var) => write!(f, "the name {var} is reserved to gap variables"),
+            SimplexError::NotTwoDimensional(count) => {
+                write!(f, "only programs with 2 decision variables can be drawn, not {count}")
+            }
+            SimplexError::NotImproving(var) => write!(f, "increasing {var} doesn't improve the objective"),
+            SimplexError::IterationLimit => write!(f, "the optimum wasn't reached within the allowed steps"),
+            SimplexError::TimedOut => write!(f, "the optimum wasn't reached within the allowed time"),
+            SimplexError::DimensionMismatch { expected, found } => {
+                write!(f, "expected {expected} values, found {found}")
+            }
+            SimplexError::InvalidPivot(index) => write!(f, "pivot #{index} of the log isn't valid at that step"),
+            SimplexError::InvalidVariable(name) => write!(f, "`{name}` isn't a valid variable name"),
+            SimplexError::AlreadyIntegral(var) => write!(f, "{var} is already integral, there is nothing to branch on"),
+            SimplexError::UnknownVariable(var) => write!(f, "there is no variable {var} in the program"),
+            SimplexError::InvalidLine(line) => write!(f, "line {line} isn't a valid constraint"),
+            SimplexError::StrictInequality(line) => {
+                write!(f, "line {line} uses a strict inequality, which isn't allowed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SimplexError {}
+
+/// An error met while parsing a whole linear program, with the line it occurred on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseProblemError {
+    /// Number of the offending line, starting from 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseProblemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseProblemError {}
+
+/// A likely mistake in a linear program, which doesn't prevent solving it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// This variable appears in the objective but in no constraint nor bound, so the program may
+    /// be unbounded
+    UnconstrainedObjectiveVariable(Variable),
+    /// The constraint at this index has no variable left once its zero terms are removed, so it
+    /// is either always or never satisfied
+    EmptyConstraint(usize),
+    /// A constraint forces this variable below 0 while it isn't free, so the program is
+    /// infeasible, see [`crate::constraint::Constraints::negativity_conflict`]
+    ForcedNegative(Variable),
+    /// These names, as written, were turned into the same variable by the normalization of
+    /// [`crate::constraint::CompileOptions`], e.g. `X` and `x` when folding the case
+    MergedVariables { names: Vec<Variable>, into: Variable },
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::UnconstrainedObjectiveVariable(var) => {
+                write!(f, "{var} appears in the objective but in no constraint")
+            }
+            ValidationWarning::EmptyConstraint(index) => {
+                write!(f, "constraint #{index} doesn't involve any variable")
+            }
+            ValidationWarning::ForcedNegative(var) => {
+                write!(f, "{var} is forced below 0, declare it free with `{var} free` to allow it")
+            }
+            ValidationWarning::MergedVariables { names, into } => {
+                write!(f, "{} all stand for the variable {into}", names.join(", "))
+            }
+        }
+    }
+}
+
+/// A sign that the result of the simplex may be inaccurate, which doesn't stop the solve
+#[derive(Debug, Clone, PartialEq)]
+pub enum NumericalWarning {
+    /// The pivot of this step was on an element smaller than the `pivot` tolerance in absolute
+    /// value, which amplifies the rounding errors of the whole tableau
+    SmallPivot {
+        step: usize,
+        entering: Variable,
+        pivot_element: Coefficient,
+    },
+}
+
+impl std::fmt::Display for NumericalWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumericalWarning::SmallPivot {
+                step,
+                entering,
+                pivot_element,
+            } => write!(
+                f,
+                "step {step} pivoted {entering} on the small element {pivot_element}, \
+                 rescaling the problem may give more accurate results"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_function::var;
+
+    #[test]
+    fn test_simplex_error_display() {
+        let cases = [
+            (SimplexError::Unbounded, "unbounded"),
+            (SimplexError::AlreadyOptimal, "optimum"),
+            (SimplexError::NotOptimal, "yet"),
+            (SimplexError::Infeasible, "infeasible"),
+            (SimplexError::NoSuchConstraint(3), "#3"),
+            (SimplexError::NoSuchStep(4), "step 4"),
+            (SimplexError::PivotedTableau, "pivoted"),
+            (SimplexError::ReservedVariable(var("ε0")), "ε0"),
+            (SimplexError::NotTwoDimensional(3), "not 3"),
+            (SimplexError::NotImproving(var("x")), "increasing x"),
+            (SimplexError::IterationLimit, "steps"),
+            (SimplexError::TimedOut, "time"),
+            (SimplexError::DimensionMismatch { expected: 3, found: 2 }, "expected 3"),
+            (SimplexError::InvalidPivot(2), "pivot #2"),
+            (SimplexError::InvalidVariable("2x".to_string()), "`2x`"),
+            (SimplexError::AlreadyIntegral(var("x")), "x is already integral"),
+            (SimplexError::UnknownVariable(var("w")), "no variable w"),
+            (SimplexError::InvalidLine(2), "line 2"),
+            (SimplexError::StrictInequality(5), "line 5"),
+        ];
+        for (error, keyword) in cases {
+            assert!(error.to_string().contains(keyword), "{error}");
+        }
+
+        // Can be returned from any function returning a boxed error
+        let boxed: Box<dyn std::error::Error> = SimplexError::Infeasible.into();
+        assert_eq!(boxed.to_string(), "the program is infeasible");
+    }
+}
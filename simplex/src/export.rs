@@ -0,0 +1,519 @@
+//! Export of linear programs and simplex runs to other formats
+use crate::linear_function::{LinearFunction, Variable, GAP_VARIABLE_IDENTIFIER};
+use crate::constraint::{free_variable_parts, Operator};
+use crate::geometry::{constraint_line, contour_line, feasible_polygon};
+use crate::{LinearProgram, ObjectiveSense, Simplex, SimplexError};
+use itertools::Itertools;
+
+/// Quotes a CSV field if it contains a character that would break the row
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escapes a string to be used inside a quoted Graphviz label
+fn dot_escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl LinearProgram {
+    /// Dumps the current tableau as CSV, with one row per constraint followed by the objective row
+    ///
+    /// The header row is `basic,constant` followed by every variable of the tableau, sorted
+    /// alphabetically. Gap variables columns are only included if `include_gaps` is set
+    ///
+    /// The objective row is the one of the tableau, which is always maximized: a minimization
+    /// shows the negated objective there, see [`LinearProgram::objective`] for the user's one
+    pub fn tableau_to_csv(&self, include_gaps: bool) -> String {
+        let columns: Vec<Variable> = self
+            .constraints
+            .iter()
+            .flat_map(|c| c.right.var_iter())
+            .chain(self.linear_function.var_iter())
+            .filter(|v| include_gaps || !v.starts_with(GAP_VARIABLE_IDENTIFIER))
+            .cloned()
+            .unique()
+            .sorted()
+            .collect();
+
+        let mut csv = format!(
+            "basic,constant{}\n",
+            columns.iter().fold(String::new(), |acc, v| acc + "," + &csv_field(v))
+        );
+        for constraint in self.constraints.iter() {
+            let basic = constraint.left.name_single_variable().map(String::from).unwrap_or_default();
+            csv += &csv_field(&basic);
+            csv += &format!(",{}", constraint.right.constant);
+            for var in columns.iter() {
+                csv += &format!(",{}", constraint.right[var]);
+            }
+            csv.push('\n');
+        }
+        csv += &format!("objective,{}", self.linear_function.constant);
+        for var in columns.iter() {
+            csv += &format!(",{}", self.linear_function[var]);
+        }
+        csv.push('\n');
+        csv
+    }
+}
+
+impl LinearProgram {
+    /// Returns the objective in the user's sense over the decision variables, a free variable
+    /// standing for the difference of its parts
+    fn exported_objective(&self) -> LinearFunction {
+        let mut objective = self.objective();
+        for var in self.constraints.free_variables() {
+            let (positive, negative) = free_variable_parts(var);
+            objective.replace(&positive, &LinearFunction::single_variable(var.clone()));
+            objective.replace(&negative, &LinearFunction::zero());
+        }
+        objective
+    }
+
+    /// Returns the free variables, sorted
+    fn sorted_free_variables(&self) -> Vec<&Variable> {
+        self.constraints.free_variables().iter().sorted().collect()
+    }
+
+    /// Writes the program in CPLEX LP format
+    ///
+    /// As with [`LinearProgram::to_mps`], the original constraints are written with the objective
+    /// of the current tableau, so this is meant for a program which wasn't pivoted. Strict
+    /// inequalities are written as large ones, and the constant of the objective is dropped
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max x + 2y\n budget: x + y <= 4\n 0 <= y <= 3".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(
+    ///     program.to_lp(),
+    ///     "Maximize\n obj: x + 2 y\nSubject To\n budget: x + y <= 4\nBounds\n 0 <= y <= 3\nEnd\n"
+    /// );
+    /// ```
+    pub fn to_lp(&self) -> String {
+        // The terms of a function as `2 x - y`, without its constant
+        let terms = |function: &LinearFunction| {
+            let terms = function
+                .variables()
+                .into_iter()
+                .filter(|var| !var.starts_with(GAP_VARIABLE_IDENTIFIER))
+                .enumerate()
+                .map(|(i, var)| {
+                    let coeff = function[&var];
+                    let sign = match (i, coeff < 0.0) {
+                        (0, true) => "-",
+                        (0, false) => "",
+                        (_, true) => " - ",
+                        (_, false) => " + ",
+                    };
+                    match coeff.abs() {
+                        1.0 => format!("{sign}{var}"),
+                        abs => format!("{sign}{abs} {var}"),
+                    }
+                })
+                .collect::<String>();
+            if terms.is_empty() {
+                "0".to_string()
+            } else {
+                terms
+            }
+        };
+
+        let mut lp = match self.sense {
+            ObjectiveSense::Max => String::from("Maximize\n"),
+            ObjectiveSense::Min => String::from("Minimize\n"),
+        };
+        lp += &format!(" obj: {}\nSubject To\n", terms(&self.exported_objective()));
+        for (i, constraint) in self.constraints.original_constraints().iter().enumerate() {
+            let name = constraint.label.clone().unwrap_or_else(|| format!("c{i}"));
+            let function = constraint.left.clone() - constraint.right.clone();
+            let operator = match constraint.operator {
+                Operator::Less | Operator::LessEqual => "<=",
+                Operator::Greater | Operator::GreaterEqual => ">=",
+                Operator::Equal => "=",
+            };
+            lp += &format!(" {name}: {} {operator} {}\n", terms(&function), -function.constant + 0.0);
+        }
+        let bounds = self.bounds.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)).collect::<Vec<_>>();
+        let free = self.sorted_free_variables();
+        if !bounds.is_empty() || !free.is_empty() {
+            lp += "Bounds\n";
+            for (var, (lower, upper)) in bounds {
+                let lower = if lower.is_finite() { lower.to_string() } else { "-inf".to_string() };
+                let upper = if upper.is_finite() { upper.to_string() } else { "+inf".to_string() };
+                lp += &format!(" {lower} <= {var} <= {upper}\n");
+            }
+            for var in free {
+                lp += &format!(" {var} free\n");
+            }
+        }
+        lp += "End\n";
+        lp
+    }
+
+    /// Writes the program in free MPS format, with an `OBJSENSE` section for maximization
+    ///
+    /// The original constraints are written, and the objective of the current tableau, so this
+    /// is meant for a program which wasn't pivoted. Strict inequalities are written as large
+    /// ones, and the constant of the objective is dropped
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max x + 2y\n budget: x + y <= 4".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(
+    ///     program.to_mps(),
+    ///     "NAME simplex\nOBJSENSE\n    MAX\nROWS\n N  obj\n L  budget\nCOLUMNS\n    x  obj  1\n    x  budget  1\n    y  obj  2\n    y  budget  1\nRHS\n    RHS  budget  4\nENDATA\n"
+    /// );
+    /// ```
+    pub fn to_mps(&self) -> String {
+        let objective = self.exported_objective();
+        // Each row as `function operator 0`, named after its label or index
+        let rows = self
+            .constraints
+            .original_constraints()
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let name = c.label.clone().unwrap_or_else(|| format!("c{i}"));
+                (name, c.operator, c.left.clone() - c.right.clone())
+            })
+            .collect::<Vec<_>>();
+
+        let mut mps = String::from("NAME simplex\n");
+        if self.sense == ObjectiveSense::Max {
+            mps += "OBJSENSE\n    MAX\n";
+        }
+        mps += "ROWS\n N  obj\n";
+        for (name, operator, _) in rows.iter() {
+            let kind = match operator {
+                Operator::Less | Operator::LessEqual => "L",
+                Operator::Greater | Operator::GreaterEqual => "G",
+                Operator::Equal => "E",
+            };
+            mps += &format!(" {kind}  {name}\n");
+        }
+        mps += "COLUMNS\n";
+        let variables = self.non_gap_variables();
+        for var in variables.iter() {
+            let entries = std::iter::once(("obj", &objective)).chain(rows.iter().map(|(name, _, f)| (name.as_str(), f)));
+            for (name, function) in entries.filter(|(_, function)| function[var] != 0.0) {
+                mps += &format!("    {var}  {name}  {}\n", function[var]);
+            }
+        }
+        mps += "RHS\n";
+        for (name, _, function) in rows.iter().filter(|(_, _, function)| function.constant != 0.0) {
+            mps += &format!("    RHS  {name}  {}\n", -function.constant);
+        }
+        let bounds = self.bounds.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)).collect::<Vec<_>>();
+        let free = self.sorted_free_variables();
+        if !bounds.is_empty() || !free.is_empty() {
+            mps += "BOUNDS\n";
+            for (var, (lower, upper)) in bounds {
+                if lower.is_finite() {
+                    mps += &format!(" LO BND  {var}  {lower}\n");
+                } else {
+                    mps += &format!(" MI BND  {var}\n");
+                }
+                if upper.is_finite() {
+                    mps += &format!(" UP BND  {var}  {upper}\n");
+                }
+            }
+            for var in free {
+                mps += &format!(" FR BND  {var}\n");
+            }
+        }
+        mps += "ENDATA\n";
+        mps
+    }
+}
+
+impl Simplex {
+    /// Exports the values of the variables at the current step as CSV `variable,value` rows,
+    /// followed by an `objective` row
+    ///
+    /// Gap variables are only included if `include_gaps` is set
+    pub fn solution_to_csv(&self, include_gaps: bool) -> String {
+        let state = self.current_state();
+        let mut csv = String::from("variable,value\n");
+        for (var, value) in state.valuation().into_iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+            if include_gaps || !var.starts_with(GAP_VARIABLE_IDENTIFIER) {
+                csv += &format!("{},{value}\n", csv_field(&var));
+            }
+        }
+        csv += &format!("objective,{}\n", state.objective_value());
+        csv
+    }
+
+    /// Draws every computed step of a program with two decision variables as an SVG image of
+    /// `width` by `height` pixels, to be flipped through or stitched into an animation
+    ///
+    /// Each frame shows the feasible region, the constraints binding at the current vertex in
+    /// orange, the contour line of the objective through the vertex dashed in red, and the
+    /// vertex itself. Programs with another number of decision variables give
+    /// `NotTwoDimensional`
+    pub fn to_svg_frames(&self, width: u32, height: u32) -> Result<Vec<String>, SimplexError> {
+        let initial_state = self.initial_state();
+        let variables = initial_state.non_gap_variables();
+        if variables.len() != 2 {
+            return Err(SimplexError::NotTwoDimensional(variables.len()));
+        }
+        let (x_var, y_var) = (&variables[0], &variables[1]);
+        let constraints = &initial_state.constraints;
+        let objective = self.phase_two_objective.as_ref().unwrap_or(&initial_state.linear_function);
+        let polygon = feasible_polygon(constraints, x_var, y_var);
+
+        // Bounding box of the frames, with a small margin around the feasible region
+        let (mut min, mut max) = ([0f32; 2], [1f32; 2]);
+        for vertex in polygon.iter() {
+            for i in 0..2 {
+                min[i] = min[i].min(vertex[i]);
+                max[i] = max[i].max(vertex[i]);
+            }
+        }
+        for i in 0..2 {
+            let margin = (max[i] - min[i]) * 0.1;
+            min[i] -= margin;
+            max[i] += margin;
+        }
+        let (width_f, height_f) = (width as f32, height as f32);
+        let to_svg = |p: [f32; 2]| {
+            let x = (p[0] - min[0]) / (max[0] - min[0]) * width_f;
+            let y = height_f - (p[1] - min[1]) / (max[1] - min[1]) * height_f;
+            (x, y)
+        };
+        let svg_line = |(p, q): ([f32; 2], [f32; 2]), style: &str| {
+            let ((x1, y1), (x2, y2)) = (to_svg(p), to_svg(q));
+            format!("  <line x1=\"{x1}\" y1=\"{y1}\" x2=\"{x2}\" y2=\"{y2}\" {style}/>\n")
+        };
+        let region = polygon
+            .iter()
+            .map(|p| {
+                let (x, y) = to_svg(*p);
+                format!("{x},{y}")
+            })
+            .join(" ");
+
+        let frames = self
+            .states()
+            .enumerate()
+            .map(|(step, state)| {
+                let values = state.values();
+                let value = |var: &Variable| values.iter().find(|(v, _)| v == var).map_or(0.0, |(_, value)| *value);
+                let vertex = [value(x_var), value(y_var)];
+                let valuation = values.iter().cloned().collect();
+
+                let mut svg = format!(
+                    "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+                );
+                svg += &format!("  <polygon points=\"{region}\" fill=\"lightblue\" stroke=\"steelblue\"/>\n");
+                for index in constraints.active_at(&valuation, 1e-4) {
+                    let constraint = &constraints.original_constraints()[index];
+                    if let Some(segment) = constraint_line(constraint, x_var, y_var, min, max) {
+                        svg += &svg_line(segment, "stroke=\"orange\" stroke-width=\"2\"");
+                    }
+                }
+                let current_value = objective.evaluate(&values);
+                if let Some(segment) = contour_line(objective, x_var, y_var, current_value, min, max) {
+                    svg += &svg_line(segment, "stroke=\"red\" stroke-dasharray=\"6 4\"");
+                }
+                let (cx, cy) = to_svg(vertex);
+                svg += &format!("  <circle cx=\"{cx}\" cy=\"{cy}\" r=\"5\" fill=\"red\"/>\n");
+                svg += &format!(
+                    "  <text x=\"8\" y=\"20\">step {step}: objective = {}</text>\n</svg>\n",
+                    state.objective_value()
+                );
+                svg
+            })
+            .collect();
+        Ok(frames)
+    }
+
+    /// Draws the path followed by the simplex as a Graphviz digraph: each computed step is a
+    /// node labeled with its vertex and objective value, and each pivot an edge labeled with the
+    /// variable entering the base
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph simplex {\n");
+        for (i, state) in self.states().enumerate() {
+            let point = state.point().iter().map(|value| value.to_string()).join(", ");
+            dot += &format!(
+                "    step{i} [label=\"({})\\nobjective: {}\"];\n",
+                dot_escape(&point),
+                state.objective_value()
+            );
+        }
+        for (i, record) in self.pivot_log().iter().enumerate() {
+            dot += &format!(
+                "    step{i} -> step{} [label=\"{}\"];\n",
+                i + 1,
+                dot_escape(&record.entering)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint::Constraints;
+    use crate::PivotRule;
+    use std::str::FromStr;
+
+    fn solved_default_problem() -> Simplex {
+        let constraints = Constraints::compile(
+            "x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600",
+        )
+        .unwrap();
+        let mut simplex = constraints
+            .maximize(&LinearFunction::from_str("x + 6y + 13z").unwrap())
+            .unwrap();
+        while simplex.next_step(PivotRule::Bland).is_ok() {}
+        simplex
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("x"), "x");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[test]
+    fn test_solution_to_csv() {
+        let csv = solved_default_problem().solution_to_csv(false);
+        let rows = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(rows[0], "variable,value");
+        assert_eq!(rows[1], "x,0");
+        assert_eq!(rows[2], "y,300");
+        assert_eq!(rows[3], "z,100");
+        assert_eq!(rows[4], "objective,3100");
+        assert_eq!(rows.len(), 5);
+
+        let csv = solved_default_problem().solution_to_csv(true);
+        assert!(csv.lines().any(|row| row == "ε0,200"));
+
+        // Minimizations export the objective value in the user's sense
+        let program = "min x + y\n x + y >= 2".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert!(simplex.solution_to_csv(false).ends_with("objective,2\n"));
+    }
+
+    #[test]
+    fn test_tableau_to_csv() {
+        let simplex = solved_default_problem();
+        let csv = simplex.current_state().tableau_to_csv(false);
+        let rows = csv.lines().collect::<Vec<_>>();
+
+        // Every decision variable is in the base at the optimum
+        assert_eq!(rows[0], "basic,constant");
+        assert_eq!(rows[2], "ε0,200");
+        assert_eq!(rows.len(), 6);
+        assert!(rows[5].starts_with("objective,3100"));
+
+        let csv = simplex.current_state().tableau_to_csv(true);
+        assert!(csv.lines().next().unwrap().contains("ε"));
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let simplex = solved_default_problem();
+        let dot = simplex.to_dot();
+        let steps = simplex.pivot_log().len() + 1;
+
+        assert!(dot.starts_with("digraph simplex {\n"));
+        assert_eq!(dot.matches("[label=\"(").count(), steps);
+        assert_eq!(dot.matches(" -> ").count(), steps - 1);
+        assert!(dot.contains("step0 [label=\"(0, 0, 0)\\nobjective: 0\"];"));
+        assert!(dot.contains("step0 -> step1 [label=\"x\"];"));
+
+        // Already optimal: a single node
+        let simplex = Constraints::compile("x <= 1")
+            .unwrap()
+            .maximize(&LinearFunction::from_str("-x").unwrap())
+            .unwrap();
+        assert_eq!(
+            simplex.to_dot(),
+            "digraph simplex {\n    step0 [label=\"(0)\\nobjective: 0\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_to_lp() {
+        let program = "min 3x - y + 1\n x + y >= 2\n x - y = 0\n 0 <= y <= 5".parse::<LinearProgram>().unwrap();
+        assert_eq!(
+            program.to_lp(),
+            "Minimize\n obj: 3 x - y\nSubject To\n c0: x + y >= 2\n c1: x - y = 0\nBounds\n 0 <= y <= 5\nEnd\n"
+        );
+        let program = "max 0.5x\n -x <= 1".parse::<LinearProgram>().unwrap();
+        assert_eq!(program.to_lp(), "Maximize\n obj: 0.5 x\nSubject To\n c0: -x <= 1\nEnd\n");
+
+        // A free variable is written as itself, not as the parts standing for it in the tableau
+        let program = "max 2x + y\n x + y <= 4\n x <= -1\n x free".parse::<LinearProgram>().unwrap();
+        assert_eq!(
+            program.to_lp(),
+            "Maximize\n obj: 2 x + y\nSubject To\n c0: x + y <= 4\n c1: x <= -1\nBounds\n x free\nEnd\n"
+        );
+    }
+
+    #[test]
+    fn test_to_mps() {
+        let program = "min 3x - y + 1\n x + y >= 2\n x - y = 0\n 0 <= y <= 5".parse::<LinearProgram>().unwrap();
+        let mps = program.to_mps();
+        assert!(!mps.contains("OBJSENSE"));
+        assert!(mps.contains(" G  c0\n E  c1\n"));
+        assert!(mps.contains("    x  obj  3\n    x  c0  1\n    x  c1  1\n"));
+        assert!(mps.contains("    y  obj  -1\n    y  c0  1\n    y  c1  -1\n"));
+        assert!(mps.contains("RHS\n    RHS  c0  2\nBOUNDS\n LO BND  y  0\n UP BND  y  5\nENDATA\n"));
+
+        let program = "max 2x + y\n x + y <= 4\n x <= -1\n x free".parse::<LinearProgram>().unwrap();
+        let mps = program.to_mps();
+        assert!(mps.contains("COLUMNS\n    x  obj  2\n    x  c0  1\n    x  c1  1\n    y  obj  1\n"));
+        assert!(mps.contains("BOUNDS\n FR BND  x\nENDATA\n"));
+        assert!(!mps.contains('⁺') && !mps.contains('⁻'));
+    }
+
+    /// Returns true if every opened tag of `xml` is closed in the right order
+    fn is_well_formed(xml: &str) -> bool {
+        let mut open_tags = vec![];
+        for tag in xml.split('<').skip(1).map(|rest| rest.split('>').next().unwrap_or_default()) {
+            if let Some(name) = tag.strip_prefix('/') {
+                if open_tags.pop() != Some(name.to_string()) {
+                    return false;
+                }
+            } else if !tag.ends_with('/') {
+                open_tags.push(tag.split_whitespace().next().unwrap_or_default().to_string());
+            }
+        }
+        open_tags.is_empty() && xml.matches('<').count() == xml.matches('>').count()
+    }
+
+    #[test]
+    fn test_to_svg_frames() {
+        let mut simplex = Constraints::compile("x <= 2\n y <= 3\n x + y <= 4")
+            .unwrap()
+            .maximize(&LinearFunction::from_str("x + 2y").unwrap())
+            .unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        let frames = simplex.to_svg_frames(400, 300).unwrap();
+        assert_eq!(frames.len(), simplex.pivot_log().len() + 1);
+        for frame in frames.iter() {
+            assert!(frame.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"400\" height=\"300\""));
+            assert!(is_well_formed(frame), "{frame}");
+            assert_eq!(frame.matches("<circle").count(), 1);
+        }
+        // No constraint is binding at the origin, two of them are at the optimum
+        assert!(!frames[0].contains("orange"));
+        assert_eq!(frames.last().unwrap().matches("orange").count(), 2);
+        assert!(frames.last().unwrap().contains("objective = 7"));
+
+        let simplex = solved_default_problem();
+        assert_eq!(simplex.to_svg_frames(400, 300), Err(SimplexError::NotTwoDimensional(3)));
+    }
+}
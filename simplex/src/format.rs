@@ -0,0 +1,188 @@
+//! Configurable text formatting of linear programs, shared by `Display`, the GUI and reports
+use crate::linear_function::{Variable, GAP_VARIABLE_IDENTIFIER};
+use crate::{LinearProgram, ObjectiveSense};
+use itertools::Itertools;
+
+/// The layout of a formatted linear program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatStyle {
+    /// The objective and one `basic = constant + terms` equation per row, as in a dictionary
+    #[default]
+    Algebraic,
+    /// A table with one column per variable and one line per row, the objective coming last
+    Tableau,
+}
+
+/// Formats a linear program according to some options, set with the `with_*` methods
+/// ```rust
+/// use simplex::{FormatStyle, LinearProgram, LinearProgramFormatter};
+///
+/// let program = "max x + 0.25y\n x + y <= 4".parse::<LinearProgram>().unwrap();
+/// let formatter = LinearProgramFormatter::default().with_precision(2);
+/// assert_eq!(formatter.format(&program), "max x + 0.25y\nε0 = 4.00 - x - y\n");
+///
+/// let formatter = formatter.with_style(FormatStyle::Tableau);
+/// assert!(formatter.format(&program).starts_with("basic | constant | x     | y"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinearProgramFormatter {
+    precision: usize,
+    show_slacks: bool,
+    style: FormatStyle,
+}
+
+impl Default for LinearProgramFormatter {
+    /// The formatter used by `Display`: one decimal, with the gap variables, in algebraic style
+    fn default() -> Self {
+        LinearProgramFormatter {
+            precision: 1,
+            show_slacks: true,
+            style: FormatStyle::Algebraic,
+        }
+    }
+}
+
+impl LinearProgramFormatter {
+    /// Sets the number of decimals of the coefficients
+    pub fn with_precision(self, precision: usize) -> LinearProgramFormatter {
+        LinearProgramFormatter { precision, ..self }
+    }
+
+    /// Sets whether the terms of the gap variables out of the base are shown
+    pub fn with_slacks(self, show_slacks: bool) -> LinearProgramFormatter {
+        LinearProgramFormatter { show_slacks, ..self }
+    }
+
+    pub fn with_style(self, style: FormatStyle) -> LinearProgramFormatter {
+        LinearProgramFormatter { style, ..self }
+    }
+
+    /// Formats `lp` with those options
+    pub fn format(&self, lp: &LinearProgram) -> String {
+        let mut s = match self.style {
+            FormatStyle::Algebraic => self.format_algebraic(lp),
+            FormatStyle::Tableau => self.format_tableau(lp),
+        };
+        for (var, (lower, upper)) in lp.bounds.iter().sorted_by(|(a, _), (b, _)| a.cmp(b)) {
+            s += &format!("{lower} <= {var} <= {upper}\n");
+        }
+        s
+    }
+
+    /// Whether the terms of `var` are shown
+    fn keep(&self, var: &Variable) -> bool {
+        self.show_slacks || !var.starts_with(GAP_VARIABLE_IDENTIFIER)
+    }
+
+    /// Returns the objective in the sense given by the user, with its name
+    fn objective(lp: &LinearProgram) -> (&'static str, crate::LinearFunction) {
+        let name = match lp.sense {
+            ObjectiveSense::Max => "max",
+            ObjectiveSense::Min => "min",
+        };
+        (name, lp.objective())
+    }
+
+    fn format_algebraic(&self, lp: &LinearProgram) -> String {
+        let precision = Some(self.precision);
+        let keep = |var: &Variable| self.keep(var);
+        let (name, objective) = Self::objective(lp);
+
+        let mut s = format!("{name} {}\n", objective.format_with(precision, keep));
+        for constraint in lp.constraints.iter() {
+            if let Some(label) = &constraint.label {
+                s += &format!("{label}: ");
+            }
+            s += &format!(
+                "{} {} {}\n",
+                constraint.left.format_with(precision, |_| true),
+                constraint.operator,
+                constraint.right.format_with(precision, keep)
+            );
+        }
+        s
+    }
+
+    fn format_tableau(&self, lp: &LinearProgram) -> String {
+        let precision = self.precision;
+        let (name, objective) = Self::objective(lp);
+        let columns: Vec<Variable> = lp
+            .constraints
+            .iter()
+            .flat_map(|c| c.right.var_iter())
+            .chain(objective.var_iter())
+            .filter(|var| self.keep(var))
+            .unique()
+            .sorted()
+            .cloned()
+            .collect();
+
+        let header = ["basic".to_string(), "constant".to_string()]
+            .into_iter()
+            .chain(columns.iter().map(Variable::to_string));
+        let row = |basic: String, function: &crate::LinearFunction| {
+            [basic, format!("{:.precision$}", function.constant)]
+                .into_iter()
+                .chain(columns.iter().map(|var| format!("{:.precision$}", function[var])))
+                .collect::<Vec<_>>()
+        };
+        let mut lines = vec![header.collect::<Vec<_>>()];
+        for constraint in lp.constraints.iter() {
+            let basic = constraint.left.name_single_variable().map(String::from).unwrap_or_default();
+            lines.push(row(basic, &constraint.right));
+        }
+        lines.push(row(name.to_string(), &objective));
+
+        let widths = (0..lines[0].len())
+            .map(|i| lines.iter().map(|line| line[i].chars().count()).max().unwrap_or(0))
+            .collect::<Vec<_>>();
+        lines
+            .iter()
+            .map(|line| {
+                let cells = line.iter().zip(widths.iter()).map(|(cell, width)| format!("{cell:width$}"));
+                format!("{}\n", cells.collect::<Vec<_>>().join(" | ").trim_end())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_function::var;
+
+    #[test]
+    fn test_precision() {
+        let program = "max 0.123456x\n x <= 2.5".parse::<LinearProgram>().unwrap();
+        let format = |precision| LinearProgramFormatter::default().with_precision(precision).format(&program);
+        assert_eq!(format(0), "max 0x\nε0 = 2 - x\n");
+        assert_eq!(format(3), "max 0.123x\nε0 = 2.500 - x\n");
+        // The default formatter is the one of `Display`
+        assert_eq!(format(1), program.to_string());
+    }
+
+    #[test]
+    fn test_slacks() {
+        let mut program = "max 2x + y\n budget: x + y <= 4\n y <= 3".parse::<LinearProgram>().unwrap();
+        program.pivot(var("x")).unwrap();
+        let formatter = LinearProgramFormatter::default();
+        assert_eq!(
+            formatter.format(&program),
+            "max 8.0 - y - 2.0ε0\nbudget: x = 4.0 - y - ε0\nε1 = 3.0 - y\n"
+        );
+        assert_eq!(
+            formatter.with_slacks(false).format(&program),
+            "max 8.0 - y\nbudget: x = 4.0 - y\nε1 = 3.0 - y\n"
+        );
+
+        let formatter = formatter.with_style(FormatStyle::Tableau);
+        assert_eq!(
+            formatter.format(&program),
+            "basic | constant | y    | ε0\nx     | 4.0      | -1.0 | -1.0\nε1    | 3.0      | -1.0 | 0.0\nmax   | 8.0      | -1.0 | -2.0\n"
+        );
+        assert_eq!(
+            formatter.with_slacks(false).format(&program),
+            "basic | constant | y\nx     | 4.0      | -1.0\nε1    | 3.0      | -1.0\nmax   | 8.0      | -1.0\n"
+        );
+    }
+}
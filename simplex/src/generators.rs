@@ -0,0 +1,136 @@
+//! Generators of linear programs, to test and benchmark the solver on problems of any size
+use crate::constraint::{Constraint, Constraints, Operator};
+use crate::linear_function::{Coefficient, LinearFunction, Variable};
+use crate::{LinearProgram, ObjectiveSense};
+
+/// A small deterministic pseudo random generator (splitmix64), so that a seed always gives the
+/// same problem without depending on a random crate
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns an integer between `low` and `high` included, as a coefficient
+    fn integer(&mut self, low: u64, high: u64) -> Coefficient {
+        (low + self.next() % (high - low + 1)) as Coefficient
+    }
+}
+
+/// Generates a random maximization problem with `vars` variables `x0, x1, ...` and `constraints`
+/// constraints `a0 x0 + a1 x1 + ... <= b`, which is always feasible and bounded
+///
+/// Every coefficient is a small non negative integer and every right hand side is positive, so the
+/// origin is feasible. Each variable has a positive coefficient in at least one constraint, so the
+/// problem is bounded, hence `constraints` must not be 0
+/// ```rust
+/// use simplex::generators::random_feasible_lp;
+///
+/// let program = random_feasible_lp(3, 2, 42);
+/// assert_eq!(program.non_gap_variables(), vec!["x0", "x1", "x2"]);
+/// assert_eq!(program.constraints.len(), 2);
+/// ```
+pub fn random_feasible_lp(vars: usize, constraints: usize, seed: u64) -> LinearProgram {
+    assert!(constraints > 0, "a program without constraints is unbounded");
+    let mut rng = SplitMix64(seed);
+    let variable = |j: usize| Variable::unchecked(format!("x{j}"));
+
+    let objective = (0..vars).fold(LinearFunction::zero(), |acc, j| {
+        acc + LinearFunction::term(rng.integer(1, 10), variable(j))
+    });
+
+    let mut rows = Constraints::new();
+    for i in 0..constraints {
+        let left = (0..vars).fold(LinearFunction::zero(), |acc, j| {
+            // Makes sure every variable is restricted by some constraint
+            let low = if j % constraints == i { 1 } else { 0 };
+            acc + LinearFunction::term(rng.integer(low, 10), variable(j))
+        });
+        let right = LinearFunction::constant(rng.integer(10, 100));
+        rows.add_constraint(Constraint::new(left.simplified(), Operator::LessEqual, right));
+    }
+    rows.to_program(&objective, ObjectiveSense::Max)
+}
+
+/// Generates the transportation problem shipping goods from sources to destinations at the
+/// lowest cost, the `i`th source supplying at most `supplies[i]` and the `j`th destination
+/// demanding at least `demands[j]`
+///
+/// The amount shipped from `i` to `j` is the variable `x{i}_{j}`, and costs `|i - j| + 1` per
+/// unit. The problem is feasible as long as the total supply covers the total demand
+/// ```rust
+/// use simplex::generators::transportation_problem;
+///
+/// let program = transportation_problem(&[20.0, 30.0], &[10.0, 25.0, 15.0]);
+/// assert_eq!(program.non_gap_variables().len(), 6);
+/// assert_eq!(program.constraints.len(), 5);
+/// ```
+pub fn transportation_problem(supplies: &[Coefficient], demands: &[Coefficient]) -> LinearProgram {
+    let variable = |i: usize, j: usize| Variable::unchecked(format!("x{i}_{j}"));
+
+    let mut cost = LinearFunction::zero();
+    for i in 0..supplies.len() {
+        for j in 0..demands.len() {
+            cost += LinearFunction::term((i.abs_diff(j) + 1) as Coefficient, variable(i, j));
+        }
+    }
+
+    let mut rows = Constraints::new();
+    for (i, supply) in supplies.iter().enumerate() {
+        let shipped = (0..demands.len()).fold(LinearFunction::zero(), |acc, j| {
+            acc + LinearFunction::single_variable(variable(i, j))
+        });
+        rows.add_constraint(
+            Constraint::new(shipped, Operator::LessEqual, LinearFunction::constant(*supply))
+                .with_label(format!("supply {i}")),
+        );
+    }
+    for (j, demand) in demands.iter().enumerate() {
+        let received = (0..supplies.len()).fold(LinearFunction::zero(), |acc, i| {
+            acc + LinearFunction::single_variable(variable(i, j))
+        });
+        rows.add_constraint(
+            Constraint::new(received, Operator::GreaterEqual, LinearFunction::constant(*demand))
+                .with_label(format!("demand {j}")),
+        );
+    }
+    rows.to_program(&cost, ObjectiveSense::Min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PivotRule, Simplex};
+
+    #[test]
+    fn test_random_feasible_lp() {
+        let program = random_feasible_lp(5, 5, 7);
+        assert_eq!(program.non_gap_variables().len(), 5);
+        assert!(!program.is_unbounded());
+
+        let mut simplex = Simplex::from(program);
+        assert!(simplex.solve(PivotRule::Bland).is_ok());
+        assert!(simplex.current_state().objective_value() > 0.0);
+
+        // The same seed always gives the same problem
+        assert_eq!(
+            random_feasible_lp(5, 5, 7).to_string(),
+            random_feasible_lp(5, 5, 7).to_string()
+        );
+    }
+
+    #[test]
+    fn test_transportation_problem() {
+        let mut simplex = Simplex::from(transportation_problem(&[20.0, 30.0], &[10.0, 25.0, 15.0]));
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        // The first destination is served by the first source and the second one by the second
+        // source at cost 1, then the rest costs 2 from the second source and 3 from the first one
+        assert!((simplex.current_state().objective_value() - 75.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,216 @@
+//! 2D geometry used to draw the feasible region of a program with two decision variables
+use crate::constraint::{Constraint, Constraints, Operator};
+use crate::linear_function::{LinearFunction, Variable};
+use itertools::Itertools;
+
+/// Tolerance used when checking if a point lies on a line or inside a half-plane
+const EPSILON: f32 = 1e-4;
+
+/// Clips the line `a * x + b * y = c` to the box going from `min` to `max`, returning the two
+/// endpoints of the resulting segment, or `None` if the line doesn't cross the box
+/// ```rust
+/// use simplex::geometry::clip_line;
+/// // The diagonal x = y of the unit square
+/// assert_eq!(clip_line(1.0, -1.0, 0.0, [0.0, 0.0], [1.0, 1.0]), Some(([0.0, 0.0], [1.0, 1.0])));
+/// ```
+pub fn clip_line(a: f32, b: f32, c: f32, min: [f32; 2], max: [f32; 2]) -> Option<([f32; 2], [f32; 2])> {
+    let mut points: Vec<[f32; 2]> = vec![];
+    let in_range = |v: f32, lo: f32, hi: f32| v >= lo - EPSILON && v <= hi + EPSILON;
+
+    // Intersections with the vertical sides of the box
+    if b != 0.0 {
+        for x in [min[0], max[0]] {
+            let y = (c - a * x) / b;
+            if in_range(y, min[1], max[1]) {
+                points.push([x, y.clamp(min[1], max[1])]);
+            }
+        }
+    }
+    // Intersections with the horizontal sides of the box
+    if a != 0.0 {
+        for y in [min[1], max[1]] {
+            let x = (c - b * y) / a;
+            if in_range(x, min[0], max[0]) {
+                points.push([x.clamp(min[0], max[0]), y]);
+            }
+        }
+    }
+
+    // The line may cross a corner of the box, giving duplicated points: keep the farthest ones
+    let distance = |p: &[f32; 2], q: &[f32; 2]| (p[0] - q[0]).powi(2) + (p[1] - q[1]).powi(2);
+    points
+        .iter()
+        .tuple_combinations()
+        .max_by(|(a, b), (c, d)| distance(a, b).total_cmp(&distance(c, d)))
+        .filter(|(p, q)| distance(p, q) > EPSILON * EPSILON)
+        .map(|(p, q)| (*p, *q))
+}
+
+/// Returns the segment of the contour line `objective = value` which lies in the box going from
+/// `min` to `max`, in the plane formed by `x_var` and `y_var`
+pub fn contour_line(
+    objective: &LinearFunction,
+    x_var: &Variable,
+    y_var: &Variable,
+    value: f32,
+    min: [f32; 2],
+    max: [f32; 2],
+) -> Option<([f32; 2], [f32; 2])> {
+    clip_line(
+        objective[x_var],
+        objective[y_var],
+        value - objective.constant,
+        min,
+        max,
+    )
+}
+
+/// Returns the segment of the line bounding `constraint` which lies in the box going from `min`
+/// to `max`, in the plane formed by `x_var` and `y_var`, see [`Constraint::as_line_2d`]
+/// ```rust
+/// use simplex::constraint::Constraint;
+/// use simplex::geometry::constraint_line;
+///
+/// let constraint = "x + y <= 1".parse::<Constraint>().unwrap();
+/// assert_eq!(constraint_line(&constraint, "x", "y", [0.0, 0.0], [2.0, 2.0]), Some(([0.0, 1.0], [1.0, 0.0])));
+/// ```
+pub fn constraint_line(
+    constraint: &Constraint,
+    x_var: &str,
+    y_var: &str,
+    min: [f32; 2],
+    max: [f32; 2],
+) -> Option<([f32; 2], [f32; 2])> {
+    let (a, b, c) = constraint.as_line_2d(x_var, y_var)?;
+    clip_line(a, b, c, min, max)
+}
+
+/// Returns the vertices of the feasible region of the given constraints, in the plane formed by
+/// `x_var` and `y_var`, sorted counterclockwise
+///
+/// Every variable is supposed to be non negative. Only the bounded part of the region is returned
+pub fn feasible_polygon(constraints: &Constraints, x_var: &Variable, y_var: &Variable) -> Vec<[f32; 2]> {
+    // Every constraint as `a * x + b * y [op] c`
+    let half_planes = constraints
+        .original_constraints()
+        .iter()
+        .map(|constraint| {
+            let function = constraint.left.clone() - constraint.right.clone();
+            (function[x_var], function[y_var], -function.constant, constraint.operator)
+        })
+        .chain([
+            (1.0, 0.0, 0.0, Operator::GreaterEqual),
+            (0.0, 1.0, 0.0, Operator::GreaterEqual),
+        ])
+        .chain(constraints.bounds().iter().flat_map(|(var, (lower, upper))| {
+            let (a, b) = if var == x_var { (1.0, 0.0) } else if var == y_var { (0.0, 1.0) } else { (0.0, 0.0) };
+            [(a, b, *lower, Operator::GreaterEqual), (a, b, *upper, Operator::LessEqual)]
+        }))
+        .filter(|(a, b, _, _)| *a != 0.0 || *b != 0.0)
+        .collect::<Vec<_>>();
+
+    let is_feasible = |p: &[f32; 2]| {
+        half_planes.iter().all(|(a, b, c, op)| {
+            let value = a * p[0] + b * p[1];
+            match op {
+                Operator::Equal => (value - c).abs() <= EPSILON,
+                Operator::Less | Operator::LessEqual => value <= c + EPSILON,
+                Operator::Greater | Operator::GreaterEqual => value >= c - EPSILON,
+            }
+        })
+    };
+
+    // Intersect every pair of lines, and keep the feasible points
+    let mut vertices: Vec<[f32; 2]> = vec![];
+    for ((a1, b1, c1, _), (a2, b2, c2, _)) in half_planes.iter().tuple_combinations() {
+        let determinant = a1 * b2 - a2 * b1;
+        if determinant == 0.0 {
+            continue;
+        }
+        let point = [(c1 * b2 - c2 * b1) / determinant, (a1 * c2 - a2 * c1) / determinant];
+        let already_seen = vertices
+            .iter()
+            .any(|v| (v[0] - point[0]).abs() <= EPSILON && (v[1] - point[1]).abs() <= EPSILON);
+        if !already_seen && is_feasible(&point) {
+            vertices.push(point);
+        }
+    }
+
+    // Sort the vertices by angle around their center
+    let count = vertices.len().max(1) as f32;
+    let center = [
+        vertices.iter().map(|v| v[0]).sum::<f32>() / count,
+        vertices.iter().map(|v| v[1]).sum::<f32>() / count,
+    ];
+    vertices.sort_by(|p, q| {
+        let angle = |v: &[f32; 2]| (v[1] - center[1]).atan2(v[0] - center[0]);
+        angle(p).total_cmp(&angle(q))
+    });
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_function::var;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_clip_line() {
+        // Horizontal line
+        assert_eq!(
+            clip_line(0.0, 2.0, 1.0, [0.0, 0.0], [4.0, 4.0]),
+            Some(([0.0, 0.5], [4.0, 0.5]))
+        );
+        // Line going through a corner only
+        assert_eq!(clip_line(1.0, 1.0, 0.0, [0.0, 0.0], [1.0, 1.0]), None);
+        // Line outside of the box
+        assert_eq!(clip_line(1.0, 1.0, 5.0, [0.0, 0.0], [1.0, 1.0]), None);
+    }
+
+    #[test]
+    fn test_contour_line() {
+        let objective = LinearFunction::from_str("x + 2y").unwrap();
+        let (x, y) = (var("x"), var("y"));
+
+        let (p, q) = contour_line(&objective, &x, &y, 4.0, [0.0, 0.0], [10.0, 10.0]).unwrap();
+        assert_eq!((p, q), ([0.0, 2.0], [4.0, 0.0]));
+        assert_eq!(objective.apply(&[(x.clone(), p[0]), (y.clone(), p[1])].into()), 4.0);
+
+        let (p, q) = contour_line(&objective, &x, &y, 24.0, [0.0, 0.0], [10.0, 10.0]).unwrap();
+        assert_eq!((p, q), ([10.0, 7.0], [4.0, 10.0]));
+
+        assert_eq!(contour_line(&objective, &x, &y, 50.0, [0.0, 0.0], [10.0, 10.0]), None);
+    }
+
+    #[test]
+    fn test_constraint_line() {
+        let constraint = Constraint::from_str("2x + 3y <= 6").unwrap();
+        // The line passes beyond the unit box
+        assert_eq!(constraint_line(&constraint, "x", "y", [0.0, 0.0], [1.0, 1.0]), None);
+        assert_eq!(
+            constraint_line(&constraint, "x", "y", [0.0, 0.0], [3.0, 3.0]),
+            Some(([0.0, 2.0], [3.0, 0.0]))
+        );
+        assert_eq!(constraint_line(&constraint, "x", "z", [0.0, 0.0], [3.0, 3.0]), None);
+    }
+
+    #[test]
+    fn test_feasible_polygon() {
+        let constraints = Constraints::compile("x <= 2\n y <= 3\n x + y <= 4").unwrap();
+        let polygon = feasible_polygon(&constraints, &var("x"), &var("y"));
+
+        assert_eq!(
+            polygon,
+            vec![[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [1.0, 3.0], [0.0, 3.0]]
+        );
+
+        // Bounds are taken into account as well
+        let constraints = Constraints::compile("0 <= x <= 2\n 1 <= y <= 3\n x + y <= 4").unwrap();
+        let polygon = feasible_polygon(&constraints, &var("x"), &var("y"));
+        assert_eq!(
+            polygon,
+            vec![[0.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 3.0], [0.0, 3.0]]
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Goal programming: instead of optimizing an objective, get as close as possible to some target
+//! levels, which may conflict with each other
+//!
+//! Each goal `left [operator] right` becomes the equation `left + under - over = right`, where
+//! the deviation variables `under` and `over` measure how far below or above its target the goal
+//! ends up. The weighted sum of the deviations which miss the goal is then minimized: both of them
+//! for an equation, only `under` for a `>=` goal and only `over` for a `<=` one
+use crate::constraint::{Constraint, Constraints, Operator};
+use crate::linear_function::{Coefficient, LinearFunction, Variable, GAP_VARIABLE_IDENTIFIER};
+use crate::{PivotRule, SimplexError};
+
+/// A linear program made of hard constraints, which must hold, and of weighted goals
+/// ```rust
+/// use simplex::constraint::Constraints;
+/// use simplex::goal::GoalProgram;
+///
+/// // Produce at least 10 units, but use at most 6 hours of work at 1 hour per unit
+/// let program = GoalProgram::new(Constraints::compile("x <= 20").unwrap())
+///     .with_goal("x >= 10".parse().unwrap(), 1.0)
+///     .with_goal("x <= 6".parse().unwrap(), 2.0);
+/// let solution = program.solve().unwrap();
+/// assert_eq!(solution.values, vec![("x".parse().unwrap(), 6.0)]);
+/// assert_eq!(solution.deviations, vec![(4.0, 0.0), (0.0, 0.0)]);
+/// assert_eq!(solution.weighted_deviation, 4.0);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GoalProgram {
+    constraints: Constraints,
+    /// The goals along with their weight
+    goals: Vec<(Constraint, Coefficient)>,
+}
+
+/// The point of a goal program closest to its goals
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalSolution {
+    /// The value of each decision variable, without the deviation variables
+    pub values: Vec<(Variable, Coefficient)>,
+    /// How far below and above its target each goal ends up, in the order they were given
+    pub deviations: Vec<(Coefficient, Coefficient)>,
+    /// The minimized weighted sum of the deviations missing the goals
+    pub weighted_deviation: Coefficient,
+}
+
+/// Returns the names of the deviation variables below and above the target of the `i`th goal,
+/// reserved like the names of the gap variables so that they can't clash with the user's ones
+fn deviation_variables(i: usize) -> (Variable, Variable) {
+    (
+        Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}gu{i}")),
+        Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}go{i}")),
+    )
+}
+
+impl GoalProgram {
+    /// Creates a goal program whose points must satisfy `constraints`
+    pub fn new(constraints: Constraints) -> GoalProgram {
+        GoalProgram {
+            constraints,
+            goals: vec![],
+        }
+    }
+
+    /// Adds a goal, the deviations missing it being multiplied by `weight` in the minimized sum
+    pub fn with_goal(mut self, goal: Constraint, weight: Coefficient) -> GoalProgram {
+        self.goals.push((goal, weight));
+        self
+    }
+
+    /// Finds the point satisfying the constraints which minimizes the weighted deviation from the
+    /// goals, solving with Bland's rule since the goal equations make the tableau degenerate
+    pub fn solve(&self) -> Result<GoalSolution, SimplexError> {
+        let mut constraints = self.constraints.clone();
+        let mut objective = LinearFunction::zero();
+        for (i, (goal, weight)) in self.goals.iter().enumerate() {
+            let (under, over) = deviation_variables(i);
+            let left = goal.left.clone() + LinearFunction::single_variable(under.clone())
+                - LinearFunction::single_variable(over.clone());
+            constraints.add_constraint(Constraint {
+                left,
+                operator: Operator::Equal,
+                right: goal.right.clone(),
+                label: goal.label.clone(),
+            });
+
+            if !matches!(goal.operator, Operator::Less | Operator::LessEqual) {
+                objective += LinearFunction::term(*weight, under);
+            }
+            if !matches!(goal.operator, Operator::Greater | Operator::GreaterEqual) {
+                objective += LinearFunction::term(*weight, over);
+            }
+        }
+
+        let mut simplex = constraints.minimize(&objective)?;
+        simplex.solve(PivotRule::Bland)?;
+        let state = simplex.current_state();
+        let valuation = state.valuation();
+        let value = |var: &Variable| valuation.get(var).copied().unwrap_or(0.0);
+
+        Ok(GoalSolution {
+            values: state.values(),
+            deviations: (0..self.goals.len())
+                .map(|i| {
+                    let (under, over) = deviation_variables(i);
+                    (value(&under), value(&over))
+                })
+                .collect(),
+            weighted_deviation: state.objective_value(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conflicting_goals() {
+        // Both goals can't be met, missing the first one costs twice as much
+        let program = GoalProgram::new(Constraints::compile("x <= 8\n y <= 8").unwrap())
+            .with_goal("x + y = 10".parse().unwrap(), 2.0)
+            .with_goal("x + y = 4".parse().unwrap(), 1.0);
+        let solution = program.solve().unwrap();
+        let total: Coefficient = solution.values.iter().map(|(_, value)| value).sum();
+        assert_eq!(total, 10.0);
+        assert_eq!(solution.deviations, vec![(0.0, 0.0), (0.0, 6.0)]);
+        assert_eq!(solution.weighted_deviation, 6.0);
+
+        // The hard constraints always hold, whatever the goals
+        let program = GoalProgram::new(Constraints::compile("x + y <= 6").unwrap())
+            .with_goal("x + y = 10".parse().unwrap(), 2.0)
+            .with_goal("x + y = 4".parse().unwrap(), 1.0);
+        let solution = program.solve().unwrap();
+        assert_eq!(solution.deviations, vec![(4.0, 0.0), (0.0, 2.0)]);
+        assert_eq!(solution.weighted_deviation, 10.0);
+    }
+
+    #[test]
+    fn test_one_sided_goals() {
+        // Exceeding a `>=` goal, or staying below a `<=` one, costs nothing
+        let program = GoalProgram::new(Constraints::compile("x + y <= 10\n x <= 8").unwrap())
+            .with_goal("x >= 2".parse().unwrap(), 1.0)
+            .with_goal("y <= 5".parse().unwrap(), 1.0);
+        let solution = program.solve().unwrap();
+        assert_eq!(solution.weighted_deviation, 0.0);
+        assert_eq!(solution.deviations[0].0, 0.0);
+        assert_eq!(solution.deviations[1].1, 0.0);
+
+        let infeasible = GoalProgram::new(Constraints::compile("x <= 1\n x >= 2").unwrap());
+        assert_eq!(infeasible.solve(), Err(SimplexError::Infeasible));
+    }
+
+    #[test]
+    fn test_user_variables_named_like_deviations() {
+        let program = GoalProgram::new(Constraints::compile("goal0_under + goal0_over <= 4").unwrap())
+            .with_goal("goal0_under >= 3".parse().unwrap(), 1.0)
+            .with_goal("goal0_over >= 3".parse().unwrap(), 1.0);
+        let solution = program.solve().unwrap();
+        assert_eq!(solution.values.len(), 2);
+        assert!((solution.weighted_deviation - 2.0).abs() < 1e-5);
+        assert!((solution.deviations[0].0 + solution.deviations[1].0 - 2.0).abs() < 1e-5);
+    }
+}
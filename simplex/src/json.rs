@@ -0,0 +1,141 @@
+//! A JSON entry point to the solver, for the web build to be used from JavaScript without the
+//! visualizer
+//!
+//! The problem is described as
+//! `{"sense": "max", "objective": "x + 6y", "constraints": ["x <= 200", "x + y <= 400"]}`, where
+//! `sense` is optional and defaults to `max`. The result is either
+//! `{"objective": 2400.0, "values": {"x": 0.0, "y": 400.0}}` or `{"error": "..."}`
+use crate::constraint::Constraints;
+use crate::linear_function::LinearFunction;
+use crate::{ObjectiveSense, PivotRule, Simplex, SimplexError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Deserialize)]
+struct Problem {
+    #[serde(default = "default_sense")]
+    sense: String,
+    objective: String,
+    constraints: Vec<String>,
+}
+
+fn default_sense() -> String {
+    "max".to_string()
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Solution {
+    Optimum {
+        objective: f32,
+        values: BTreeMap<String, f32>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Solves the problem described by `input` with Bland's rule, returning the optimum or the error
+/// met as JSON
+///
+/// Errors in a constraint give its index in `constraints`, e.g. `constraint 0: ...`
+pub fn solve_json_impl(input: &str) -> String {
+    let solution = solve(input).unwrap_or_else(|error| Solution::Error { error });
+    serde_json::to_string(&solution).expect("a solution can always be serialized")
+}
+
+fn solve(input: &str) -> Result<Solution, String> {
+    let problem: Problem = serde_json::from_str(input).map_err(|error| error.to_string())?;
+    let sense = match problem.sense.as_str() {
+        "max" => ObjectiveSense::Max,
+        "min" => ObjectiveSense::Min,
+        _ => return Err(format!("unknown sense `{}`, expected `max` or `min`", problem.sense)),
+    };
+    let objective = problem
+        .objective
+        .parse::<LinearFunction>()
+        .map_err(|_| format!("invalid objective `{}`", problem.objective))?;
+
+    // Each constraint is parsed on its own, so that one can't spill over several rows
+    let mut constraints = Constraints::new();
+    for (i, constraint) in problem.constraints.iter().enumerate() {
+        if constraint.contains('\n') {
+            return Err(format!("constraint {i}: a constraint must fit on a single line"));
+        }
+        constraints
+            .add_line(constraint.trim(), true)
+            .map_err(|_| format!("constraint {i}: invalid constraint `{constraint}`"))?;
+    }
+    let program = constraints.to_program(&objective, sense);
+
+    if program.is_unbounded() {
+        return Err(SimplexError::Unbounded.to_string());
+    }
+    let mut simplex = Simplex::from(program);
+    simplex.solve(PivotRule::Bland).map_err(|error| error.to_string())?;
+
+    Ok(Solution::Optimum {
+        objective: simplex.current_state().objective_value(),
+        values: simplex.current_values().into_iter().map(|(var, value)| (var.into(), value)).collect(),
+    })
+}
+
+/// Solves the problem described by `input` as JSON, see [`solve_json_impl`]
+#[cfg(feature = "wasm")]
+#[wasm_bindgen::prelude::wasm_bindgen]
+pub fn solve_json(input: &str) -> String {
+    solve_json_impl(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_json() {
+        let input = r#"{"objective": "x + y", "constraints": ["x + y <= 4"]}"#;
+        assert_eq!(solve_json_impl(input), r#"{"objective":4.0,"values":{"x":4.0,"y":0.0}}"#);
+
+        let input = r#"{
+            "objective": "x + 6y + 13z",
+            "constraints": ["x <= 200", "y <= 300", "x + y + z <= 400", "y + 3z <= 600"]
+        }"#;
+        let result: serde_json::Value = serde_json::from_str(&solve_json_impl(input)).unwrap();
+        assert_eq!(result["objective"], 3100.0);
+        assert_eq!(result["values"]["y"], 300.0);
+        assert_eq!(result["values"]["z"], 100.0);
+
+        let input = r#"{"sense": "min", "objective": "x + 2y", "constraints": ["x + y >= 2", "x <= 3"]}"#;
+        assert_eq!(solve_json_impl(input), r#"{"objective":2.0,"values":{"x":2.0,"y":0.0}}"#);
+    }
+
+    #[test]
+    fn test_solve_json_errors() {
+        let error = |input: &str| {
+            let result: serde_json::Value = serde_json::from_str(&solve_json_impl(input)).unwrap();
+            result["error"].as_str().unwrap().to_string()
+        };
+        assert!(error("not json").starts_with("expected"));
+        assert_eq!(
+            error(r#"{"sense": "best", "objective": "x", "constraints": []}"#),
+            "unknown sense `best`, expected `max` or `min`"
+        );
+        assert_eq!(
+            error(r#"{"objective": "x", "constraints": ["x <= 1", "x + y 4"]}"#),
+            "constraint 1: invalid constraint `x + y 4`"
+        );
+        assert_eq!(
+            error(r#"{"objective": "x", "constraints": ["x <= 1\ny <= 2"]}"#),
+            "constraint 0: a constraint must fit on a single line"
+        );
+        assert_eq!(error(r#"{"objective": "x +", "constraints": []}"#), "invalid objective `x +`");
+        assert_eq!(
+            error(r#"{"objective": "x", "constraints": ["x - y <= 0"]}"#),
+            "the program is unbounded"
+        );
+        assert_eq!(
+            error(r#"{"objective": "x", "constraints": ["x <= 1", "x >= 2"]}"#),
+            "the program is infeasible"
+        );
+    }
+}
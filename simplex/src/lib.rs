@@ -1,67 +1,1159 @@
 //! Implementation of the Simplex algorithm
 //! definition of the simplex object
+//!
+//! The visualizer lives behind the default `gui` feature: disabling it leaves only the solver,
+//! without any dependency on `eframe`/`egui`
 
+#[cfg(feature = "gui")]
 pub mod app;
+#[cfg(feature = "cli")]
+pub mod cli;
 pub mod constraint;
+#[cfg(any(test, feature = "testing"))]
+pub mod generators;
+pub mod geometry;
+pub mod goal;
+#[cfg(any(test, feature = "wasm"))]
+pub mod json;
 pub mod linear_function;
+#[cfg(feature = "gui")]
 mod polyhedron;
+pub mod prelude;
+mod dense;
 mod error;
+mod export;
+mod format;
+mod scaling;
 
-use crate::linear_function::{Coefficient, Variable};
-use constraint::Constraints;
+pub use crate::linear_function::{Coefficient, Variable};
+use crate::linear_function::{GAP_VARIABLE_IDENTIFIER, ZERO_TOLERANCE};
+use constraint::{free_variable_parts, Constraint, Constraints, Operator, ValidityReport};
 use linear_function::LinearFunction;
-use std::collections::HashSet;
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
-use crate::error::SimplexError;
+pub use crate::dense::TableauBackend;
+pub use crate::error::{NumericalWarning, ParseProblemError, SimplexError, ValidationWarning};
+pub use crate::format::{FormatStyle, LinearProgramFormatter};
+pub use crate::scaling::ScalingFactors;
 
-#[derive(Debug, Clone)]
+/// The number of pivots per row after which the dual simplex gives up, so that a degenerate
+/// tableau on which it would cycle is left to phase one
+const DUAL_SIMPLEX_PIVOTS_PER_ROW: usize = 10;
+
+/// Whether the objective of a linear program is to be maximized or minimized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ObjectiveSense {
+    #[default]
+    Max,
+    Min,
+}
+
+impl ObjectiveSense {
+    /// Returns 1 for a maximization and -1 for a minimization, the factor turning the objective
+    /// into the one maximized by the simplex
+    pub fn sign(&self) -> Coefficient {
+        match self {
+            ObjectiveSense::Max => 1.0,
+            ObjectiveSense::Min => -1.0,
+        }
+    }
+
+    /// Turns an objective to optimize in this sense into the one maximized by the simplex, i.e.
+    /// negates it for a minimization. Applied to the maximized objective, this gives the objective
+    /// of the user back
+    /// ```rust
+    /// use simplex::ObjectiveSense;
+    ///
+    /// let cost = "2x + 3y".parse().unwrap();
+    /// let maximized = ObjectiveSense::Min.to_maximized(&cost);
+    /// assert_eq!(maximized, "-2x - 3y".parse().unwrap());
+    /// assert_eq!(ObjectiveSense::Min.to_maximized(&maximized), cost);
+    /// ```
+    pub fn to_maximized(&self, objective: &LinearFunction) -> LinearFunction {
+        objective.clone() * self.sign()
+    }
+}
+
+/// The order in which the decision variables of a program are listed, e.g. by `point`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VariableOrder {
+    /// `x10` comes before `x2`
+    #[default]
+    Alphabetical,
+    /// Numbers in the names are compared by value, so that `x2` comes before `x10`
+    Natural,
+    /// The order in which the variables first appear in the original constraints, then in the
+    /// objective. Variables first appearing in the same constraint are sorted alphabetically, as
+    /// a linear function doesn't remember the order of its terms
+    InsertionOrder,
+}
+
+/// Compares two names like `Ord` does, except that the numbers they contain are compared by value
+fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    // Splits a name into its runs of digits and of other characters
+    let chunks = |s: &str| -> Vec<String> {
+        let mut chunks: Vec<String> = vec![];
+        for c in s.chars() {
+            match chunks.last_mut() {
+                Some(last) if last.starts_with(|l: char| l.is_ascii_digit()) == c.is_ascii_digit() => last.push(c),
+                _ => chunks.push(c.to_string()),
+            }
+        }
+        chunks
+    };
+
+    for (x, y) in chunks(a).iter().zip(chunks(b).iter()) {
+        let ordering = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(m), Ok(n)) => m.cmp(&n).then_with(|| x.cmp(y)),
+            _ => x.cmp(y),
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+    chunks(a).len().cmp(&chunks(b).len())
+}
+
+/// A linear program, i.e. a linear function to optimize under some constraints
+///
+/// The simplex always maximizes `linear_function`: a minimization problem is stored with its
+/// objective negated, which `sense` keeps track of. The entering variables are chosen on
+/// `linear_function`, while [`LinearProgram::objective`] and `objective_value` give the objective
+/// in the sense of the user
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearProgram {
     pub linear_function: LinearFunction,
     pub constraints: Constraints,
+    pub sense: ObjectiveSense,
+    /// Lower and upper bounds of some variables, handled by the pivot rule rather than by
+    /// constraints. In the tableau, a bounded variable stands for its distance to its lower bound,
+    /// or to its upper bound if it is in `at_upper_bound`
+    pub bounds: HashMap<Variable, (Coefficient, Coefficient)>,
+    /// The bounded variables currently standing for their distance to their upper bound
+    pub at_upper_bound: HashSet<Variable>,
+    /// The thresholds used when comparing values while solving this program
+    pub tolerances: Tolerances,
+    /// The order of the decision variables in `non_gap_variables`, hence in `point` and `values`
+    pub variable_order: VariableOrder,
+}
+
+/// Thresholds used when comparing floating point values while solving, which can be loosened
+/// for ill-conditioned problems
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tolerances {
+    /// Values within this distance of 0 are considered to be 0
+    pub zero: Coefficient,
+    /// Ratios within this distance of each other are considered tied in the ratio test
+    pub ratio: Coefficient,
+    /// Pivoting on an element smaller than this in absolute value raises a `NumericalWarning`
+    pub pivot: Coefficient,
+    /// The conditions of [`LinearProgram::verify_optimal`] are checked up to this, looser than
+    /// `zero` as `f32` values in the thousands only have a few exact decimals
+    #[cfg_attr(feature = "serde", serde(default = "default_certificate_tolerance"))]
+    pub certificate: Coefficient,
+}
+
+#[cfg(feature = "serde")]
+fn default_certificate_tolerance() -> Coefficient {
+    Tolerances::default().certificate
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Tolerances {
+            zero: ZERO_TOLERANCE,
+            ratio: ZERO_TOLERANCE,
+            pivot: 1e-3,
+            certificate: 1e-3,
+        }
+    }
+}
+
+/// The way a bounded ratio test ends: either a basic variable reaches one of its bounds and
+/// leaves the base, or the entering variable reaches its upper bound first
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RatioTestOutcome {
+    /// The basic variable of this row goes down to its lower bound
+    Pivot(usize),
+    /// The basic variable of this row goes up to its upper bound
+    PivotToUpperBound(usize),
+    /// The entering variable goes from one of its bounds to the other, without any pivot
+    BoundFlip,
+}
+
+/// A step computed from the current one of a `Simplex`, not recorded in its historic yet
+struct ComputedStep {
+    state: LinearProgram,
+    degenerate: bool,
+    phase: Phase,
+    record: PivotRecord,
+}
+
+/// The rule choosing the variables entering and leaving the base at each step
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PivotRule {
+    /// The variable with the largest coefficient in the objective enters the base
+    #[default]
+    Dantzig,
+    /// The first variable with a positive coefficient, in alphabetical order, enters the base,
+    /// which prevents cycling
+    Bland,
+    /// The variable entering the base is chosen as with `Dantzig`, but ties between the leaving
+    /// variables are broken by comparing their rows lexicographically, which prevents cycling
+    Lexicographic,
+    /// The variable improving the objective the most per unit of distance travelled along its
+    /// edge enters the base, see [`LinearProgram::edge_improvements`]
+    ///
+    /// This usually takes fewer steps than `Dantzig`, but computing the length of every edge
+    /// costs a pass over the whole tableau at each step. The lengths are computed exactly from
+    /// the tableau rather than updated from one step to the next with reference weights as in
+    /// Goldfarb and Reid's method: the dictionary holds every row anyway, so the update would
+    /// cost the same pass
+    SteepestEdge,
+}
+
+/// The phase of the two-phase method a tableau belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Phase {
+    /// Looking for a feasible vertex, by bringing an artificial variable down to 0
+    Phase1,
+    /// Optimizing the objective from the feasible vertex found by phase one
+    Phase2,
+    /// Optimizing the objective of a program whose origin is feasible, which needs no phase one
+    Single,
+}
+
+/// Where the algorithm stands at some step, see [`Simplex::status`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimplexStatus {
+    /// Some variable can still improve the objective, or phase one isn't over
+    InProgress,
+    /// Phase one is over and no variable can improve the objective anymore
+    Optimal,
+    /// Some variable improves the objective without ever being restricted
+    Unbounded,
+    /// Phase one can't bring the artificial variable down to 0
+    Infeasible,
+}
+
+/// Returns the name of the artificial variable added to every constraint during phase one
+fn artificial_variable() -> Variable {
+    Variable::unchecked(format!("{GAP_VARIABLE_IDENTIFIER}a"))
+}
+
+/// A quick overview of the shape of a linear program
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProblemSummary {
+    pub decision_variables: usize,
+    pub constraints: usize,
+    /// Number of `<` and `<=` constraints
+    pub less_than: usize,
+    /// Number of `>` and `>=` constraints
+    pub greater_than: usize,
+    pub equal: usize,
+    pub gap_variables: usize,
+    /// Whether setting every decision variable to 0 satisfies all the constraints
+    pub origin_is_feasible: bool,
+}
+
+/// What changed between two steps of the simplex, see [`Simplex::difference`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StepDiff {
+    /// The variables in the base of the later step but not of the earlier one, sorted
+    pub entered: Vec<Variable>,
+    /// The variables in the base of the earlier step but not of the later one, sorted
+    pub left: Vec<Variable>,
+    /// How much the objective value, in the sense of the user, changed
+    pub objective_delta: Coefficient,
+    /// The variables whose value changed, with their value at both steps, sorted
+    pub value_changes: Vec<(Variable, Coefficient, Coefficient)>,
+    /// The variables whose coefficient in the objective row changed, with their coefficient at
+    /// both steps, sorted
+    pub objective_changes: Vec<(Variable, Coefficient, Coefficient)>,
+}
+
+/// What happened during a step of the simplex
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PivotRecord {
+    /// The variable that entered the base
+    pub entering: Variable,
+    /// The variable that left the base, or `None` if a bounded entering variable went to its
+    /// other bound without any pivot
+    pub leaving: Option<Variable>,
+    /// The coefficient of the entering variable in the row of the leaving one, before the pivot
+    pub pivot_element: Option<Coefficient>,
+    /// The value of the objective after the step, in the sense given by the user
+    pub objective_value: Coefficient,
 }
 
 /// Simplex object
 #[derive(Debug, Clone)]
 pub struct Simplex {
     index: usize,
-    historic: Vec<LinearProgram>,
+    /// The computed steps. The ones a solve on the dense backend went through are left empty,
+    /// and rebuilt from the previous step and the pivot log once looked at, see [`Simplex::state`]
+    historic: Vec<OnceCell<LinearProgram>>,
+    /// For each entry of the historic, whether it was reached through a degenerate pivot
+    degenerate: Vec<bool>,
+    /// The pivot that led to each entry of the historic but the first one
+    log: Vec<PivotRecord>,
+    /// The phase each entry of the historic belongs to
+    phases: Vec<Phase>,
+    /// The objective to optimize once phase one is over, if the program needs one
+    phase_two_objective: Option<LinearFunction>,
+    /// The numerical issues met while computing the steps
+    warnings: Vec<NumericalWarning>,
+    /// The maximum number of steps kept in the historic, if any, see [`Simplex::with_history_limit`]
+    history_limit: Option<usize>,
+    /// The representation the pivots of `solve` are computed on
+    backend: TableauBackend,
+}
+
+/// The current state of a simplex, without its history, from which a new simplex can be started
+///
+/// This is much lighter than cloning the whole simplex, e.g. to explore the branches of a branch
+/// and bound
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimplexSnapshot {
+    program: LinearProgram,
+    phase: Phase,
+    phase_two_objective: Option<LinearFunction>,
+}
+
+impl SimplexSnapshot {
+    /// Returns the tableau the snapshot was taken at
+    pub fn program(&self) -> &LinearProgram {
+        &self.program
+    }
+}
+
+impl PivotRule {
+    /// Returns the variable that should enter the base, or `None` if no variable can improve
+    /// the objective by more than the `zero` tolerance anymore
+    ///
+    /// Ties are broken in alphabetical order of the variables, so that solving a program twice
+    /// always takes the same steps. Without the tableau, every edge of `SteepestEdge` is
+    /// considered to have length 1, which makes it choose like `Dantzig`: see
+    /// [`PivotRule::entering_variable_in`]
+    pub fn entering_variable(&self, objective: &LinearFunction, tolerances: &Tolerances) -> Option<Variable> {
+        match self {
+            PivotRule::Bland => objective.first_positive_coefficient(true, tolerances.zero),
+            PivotRule::Dantzig | PivotRule::Lexicographic | PivotRule::SteepestEdge => objective
+                .max_coefficient()
+                .filter(|(_, coeff)| *coeff > tolerances.zero)
+                .map(|(var, _)| var),
+        }
+    }
+
+    /// Returns the variable that should enter the base of `program`, like
+    /// [`PivotRule::entering_variable`] but with the actual length of the edges for `SteepestEdge`
+    pub fn entering_variable_in(&self, program: &LinearProgram) -> Option<Variable> {
+        match self {
+            PivotRule::SteepestEdge => program
+                .edge_improvements()
+                .into_iter()
+                .filter(|(var, _)| program.linear_function[var] > program.tolerances.zero)
+                .fold(None, |best: Option<(Variable, Coefficient)>, (var, improvement)| match best {
+                    Some((_, best_improvement)) if best_improvement >= improvement => best,
+                    _ => Some((var, improvement)),
+                })
+                .map(|(var, _)| var),
+            _ => self.entering_variable(&program.linear_function, &program.tolerances),
+        }
+    }
 }
 
 impl LinearProgram {
-    pub fn pivot(&mut self, var: String) -> Result<(), SimplexError> {
-        let max_constraint_index = self.constraints.most_restrictive(&var).ok_or(SimplexError::Unbounded)?;
-        self.constraints.pivot(max_constraint_index, &var);
-        self.linear_function.replace(&var, &self.constraints[max_constraint_index].right);
-        Ok(())
+    /// Pivots `var` into the base, returning the index of the constraint it was pivoted with
+    ///
+    /// If `var` is bounded and reaches its other bound before any basic variable is blocked, it
+    /// stays out of the base and `None` is returned
+    pub fn pivot(&mut self, var: Variable) -> Result<Option<usize>, SimplexError> {
+        self.pivot_with_rule(var, PivotRule::Dantzig)
+    }
+
+    /// Pivots `var` into the base like [`LinearProgram::pivot`], choosing the leaving variable
+    /// according to `rule`
+    pub fn pivot_with_rule(&mut self, var: Variable, rule: PivotRule) -> Result<Option<usize>, SimplexError> {
+        match self.choose_leaving(&var, rule).ok_or(SimplexError::Unbounded)? {
+            RatioTestOutcome::Pivot(index) => {
+                self.pivot_on(index, &var);
+                Ok(Some(index))
+            }
+            RatioTestOutcome::PivotToUpperBound(index) => {
+                let leaving = self.constraints[index].left.name_single_variable();
+                self.pivot_on(index, &var);
+                if let Some(leaving) = leaving {
+                    self.flip_bound(&leaving);
+                }
+                Ok(Some(index))
+            }
+            RatioTestOutcome::BoundFlip => {
+                self.flip_bound(&var);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Pivots `var` into the base with the row at `index`, then updates the reduced costs in
+    /// place from that row alone: the objective gets `cost * row` added for the cost of `var`,
+    /// one pass over the pivot row rather than a substitution of every basic variable
+    fn pivot_on(&mut self, index: usize, var: &Variable) {
+        self.constraints.pivot(index, var);
+        self.linear_function.replace(var, &self.constraints[index].right);
+    }
+
+    /// Returns the distance between the bounds of `var`, if it is bounded
+    fn bound_range(&self, var: &str) -> Option<Coefficient> {
+        self.bounds.get(var).map(|(lower, upper)| upper - lower)
+    }
+
+    /// Finds how far `var` can increase before a basic variable, or itself, reaches a bound.
+    /// Without any bound, this is the usual choice of the most restrictive constraint
+    fn choose_leaving(&self, var: &Variable, rule: PivotRule) -> Option<RatioTestOutcome> {
+        let most_restrictive = match rule {
+            PivotRule::Lexicographic => self.constraints.lexicographic_most_restrictive(var, &self.tolerances),
+            PivotRule::Dantzig | PivotRule::Bland | PivotRule::SteepestEdge => {
+                self.constraints.most_restrictive(var, &self.tolerances)
+            }
+        };
+        let mut best = most_restrictive.map(|index| {
+            let row = &self.constraints[index].right;
+            (-row.constant / row[var], RatioTestOutcome::Pivot(index))
+        });
+
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            let coefficient = constraint.right[var];
+            let range = constraint
+                .left
+                .name_single_variable()
+                .and_then(|basic| self.bound_range(&basic));
+            if let (Some(range), true) = (range, coefficient > self.tolerances.zero) {
+                let ratio = (range - constraint.right.constant) / coefficient;
+                if best.is_none_or(|(best_ratio, _)| ratio < best_ratio) {
+                    best = Some((ratio, RatioTestOutcome::PivotToUpperBound(index)));
+                }
+            }
+        }
+
+        if let Some(range) = self.bound_range(var) {
+            if best.is_none_or(|(best_ratio, _)| range < best_ratio) {
+                best = Some((range, RatioTestOutcome::BoundFlip));
+            }
+        }
+        best.map(|(_, outcome)| outcome)
+    }
+
+    /// Returns the index of the row of `leaving` if the ratio test may choose it when `var` enters
+    /// the base, i.e. if its ratio is tied with the smallest one, on programs without bounds
+    fn tied_leaving_row(&self, var: &Variable, leaving: &Variable) -> Option<usize> {
+        if !self.bounds.is_empty() {
+            return None;
+        }
+        let smallest = self.constraints.most_restrictive(var, &self.tolerances)?;
+        let ratio = |index: usize| self.constraints[index].ratio(var, self.tolerances.zero);
+        let index = self
+            .constraints
+            .iter()
+            .position(|constraint| constraint.left.name_single_variable().as_ref() == Some(leaving))?;
+        (ratio(index)? <= ratio(smallest)? + self.tolerances.ratio).then_some(index)
+    }
+
+    /// Makes a bounded variable, out of the base, switch to standing for its distance to its
+    /// other bound
+    fn flip_bound(&mut self, var: &Variable) {
+        if let Some(range) = self.bound_range(var) {
+            let complement = LinearFunction::constant(range) - LinearFunction::single_variable(var.clone());
+            self.constraints.replace_variable_with(var, &complement);
+            self.linear_function.replace(var, &complement);
+            if !self.at_upper_bound.remove(var) {
+                self.at_upper_bound.insert(var.clone());
+            }
+        }
+    }
+
+    /// Returns true if the base of this program, with every variable out of the base at 0,
+    /// satisfies all the constraints
+    fn is_feasible(&self) -> bool {
+        self.constraints
+            .iter()
+            .all(|c| Operator::GreaterEqual.satisfied(c.right.constant, 0.0, self.tolerances.zero))
+    }
+
+    /// Turns the last tableau of phase one, with the artificial variable at 0, into the first
+    /// tableau of phase two, optimizing `objective`
+    fn end_phase_one(&mut self, objective: &LinearFunction) {
+        let artificial = artificial_variable();
+        let artificial_row = self.constraints.iter().position(|c| c.left.contains(&artificial));
+        if let Some(index) = artificial_row {
+            // The artificial variable is 0, so any variable of its row can take its place in the
+            // base. If there is none, the row is `εa = 0` and can't restrict anything anymore
+            let replacement = self.constraints[index].right.var_iter().next().cloned();
+            if let Some(var) = replacement {
+                self.pivot_on(index, &var);
+            }
+        }
+        self.constraints.replace_variable_with(&artificial, &LinearFunction::zero());
+
+        // Express the objective with the variables that are now out of the base
+        let mut objective = objective.clone();
+        for var in self.at_upper_bound.iter() {
+            if let Some(range) = self.bound_range(var) {
+                objective.replace(var, &(LinearFunction::constant(range) - LinearFunction::single_variable(var.clone())));
+            }
+        }
+        for constraint in self.constraints.iter() {
+            if let Some(basic) = constraint.left.name_single_variable() {
+                objective.replace(&basic, &constraint.right);
+            }
+        }
+        self.linear_function = objective;
+    }
+
+    /// Bounds `var` between `lower` and `upper`, which the pivot rule then takes care of instead
+    /// of two additional constraints
+    ///
+    /// This must be done once per variable, before any pivot
+    pub fn add_bound(&mut self, var: &Variable, lower: Coefficient, upper: Coefficient) {
+        if lower != 0.0 {
+            let shifted = LinearFunction::constant(lower) + LinearFunction::single_variable(var.clone());
+            self.constraints.replace_variable_with(var, &shifted);
+            self.linear_function.replace(var, &shifted);
+        }
+        self.bounds.insert(var.clone(), (lower, upper));
+    }
+
+    /// Returns the actual value of `var` as a function of the variables out of the base: the
+    /// difference of its parts for a free variable, the distance to one of its bounds for a
+    /// bounded one
+    fn actual_function(&self, var: &Variable) -> LinearFunction {
+        let single = LinearFunction::single_variable(var.clone());
+        let mut function = match self.bounds.get(var) {
+            _ if self.constraints.free_variables().contains(var) => {
+                let (positive, negative) = free_variable_parts(var);
+                LinearFunction::single_variable(positive) - LinearFunction::single_variable(negative)
+            }
+            Some((_, upper)) if self.at_upper_bound.contains(var) => LinearFunction::constant(*upper) - single,
+            Some((lower, _)) => LinearFunction::constant(*lower) + single,
+            None => single,
+        };
+        for constraint in self.constraints.iter() {
+            if let Some(basic) = constraint.left.name_single_variable() {
+                function.replace(&basic, &constraint.right);
+            }
+        }
+        function
+    }
+
+    /// Adds the constraint `var [operator] bound` to the tableau, its gap variable entering the
+    /// base with a row written with the variables out of the base, whatever the pivots done
+    fn add_cut(&mut self, var: &Variable, operator: Operator, bound: Coefficient) {
+        let function = self.actual_function(var);
+        let cut = Constraint::new(
+            LinearFunction::single_variable(var.clone()),
+            operator,
+            LinearFunction::constant(bound),
+        );
+        self.constraints.add_constraint(cut);
+        let row = self.constraints.len() - 1;
+        self.constraints[row].right = match operator {
+            Operator::Less | Operator::LessEqual => LinearFunction::constant(bound) - function,
+            _ => function - LinearFunction::constant(bound),
+        };
+    }
+
+    /// Pivots an optimal tableau with the dual simplex until every basic variable is non negative
+    /// again: the row with the most negative constant leaves the base, and the variable of that
+    /// row which lowers the objective the least enters it, so that the tableau stays optimal
+    ///
+    /// This stops when no variable can enter, which happens if the program is infeasible, or
+    /// after `DUAL_SIMPLEX_PIVOTS_PER_ROW` pivots per row, and doesn't let bounded variables enter
+    /// the base. Phase one then takes care of the tableau
+    fn dual_simplex(&mut self) {
+        for _ in 0..DUAL_SIMPLEX_PIVOTS_PER_ROW * self.constraints.len() {
+            let zero = self.tolerances.zero;
+            let Some(row) = (0..self.constraints.len())
+                .filter(|i| self.constraints[*i].right.constant < -zero)
+                .min_by(|i, j| self.constraints[*i].right.constant.total_cmp(&self.constraints[*j].right.constant))
+            else {
+                return;
+            };
+            let ratio = |var: &Variable, coeff: Coefficient| -self.linear_function[var] / coeff;
+            let entering = self.constraints[row]
+                .right
+                .terms()
+                .filter(|(var, coeff)| *coeff > zero && !self.bounds.contains_key(*var))
+                .min_by(|(a, a_coeff), (b, b_coeff)| ratio(a, *a_coeff).total_cmp(&ratio(b, *b_coeff)))
+                .map(|(var, _)| var.clone());
+            match entering {
+                Some(var) => self.pivot_on(row, &var),
+                None => return,
+            }
+        }
+    }
+
+    /// Turns the value taken by `var` in the tableau into the actual value of `var`, which only
+    /// differ for bounded variables
+    fn actual_value(&self, var: &str, value: Coefficient) -> Coefficient {
+        match self.bounds.get(var) {
+            Some((_, upper)) if self.at_upper_bound.contains(var) => upper - value,
+            Some((lower, _)) => lower + value,
+            None => value,
+        }
     }
 
+    /// Returns true if the tableau is a feasible basic solution, see [`LinearProgram::validity_report`]
     pub fn is_valid(&self) -> bool {
-        self.constraints.is_valid()
+        self.constraints.is_valid(&self.tolerances)
+    }
+
+    /// Lists the rows of the tableau preventing it from being read as a basic solution, and the
+    /// constraints violated by the current one, see [`Constraints::validity_report`]
+    pub fn validity_report(&self) -> ValidityReport {
+        self.constraints.validity_report(&self.tolerances)
     }
 
+    /// Returns true if some variable in the base has value 0, up to the zero tolerance (i.e. the
+    /// current vertex is degenerate)
+    pub fn is_degenerate(&self) -> bool {
+        self.constraints.iter().any(|c| c.right.constant.abs() <= self.tolerances.zero)
+    }
+
+    /// Returns true if some variable could improve the objective without ever being restricted
+    ///
+    /// Without any constraint, this is the case as soon as an unbounded variable improves the
+    /// objective, while a constant objective is optimal right away
     pub fn is_unbounded(&self) -> bool {
         self.linear_function.var_iter()
-            .any(|v| self.constraints.most_restrictive(v).is_none())
+            .filter(|v| self.linear_function[v] > self.tolerances.zero && !self.bounds.contains_key(*v))
+            .any(|v| self.constraints.most_restrictive(v, &self.tolerances).is_none())
+    }
+
+    /// Looks for a variable along which the objective obviously grows forever, without running
+    /// the algorithm: a variable improving the objective such that every constraint restricting
+    /// it can be relaxed by increasing another variable, which restricts nothing and doesn't
+    /// worsen the objective. Meant to be called before solving, to warn the user upfront
+    ///
+    /// Unlike `is_unbounded`, this only looks at the original constraints, hence may miss some
+    /// unbounded programs
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max x\n x - y <= 0".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(program.quick_unbounded_check(), Some("x".parse().unwrap()));
+    /// ```
+    pub fn quick_unbounded_check(&self) -> Option<Variable> {
+        // Every constraint as `function [=|<|<=] 0`
+        let constraints = self
+            .constraints
+            .original_constraints()
+            .iter()
+            .map(|constraint| constraint.normalized())
+            .collect::<Vec<_>>();
+        // Whether increasing `var` can break the given constraint
+        let restricts = |(function, operator): &(LinearFunction, Operator), var: &Variable| {
+            function[var] > 0.0 || (*operator == Operator::Equal && function[var] != 0.0)
+        };
+        let is_free = |var: &Variable| {
+            !self.bounds.contains_key(var) && !constraints.iter().any(|c| restricts(c, var))
+        };
+
+        self.linear_function
+            .var_iter()
+            .filter(|var| self.linear_function[var] > 0.0 && !self.bounds.contains_key(*var))
+            .sorted()
+            .find(|var| {
+                constraints.iter().filter(|c| restricts(c, var)).all(|(function, _)| {
+                    function.var_iter().any(|other| {
+                        function[other] < 0.0 && self.linear_function[other] >= 0.0 && is_free(other)
+                    })
+                })
+            })
+            .cloned()
+    }
+
+    /// Looks for likely mistakes in the original program: variables of the objective which no
+    /// constraint nor bound restricts, constraints without any variable, variables which a
+    /// constraint forces below 0 without being free, and names merged when compiling the
+    /// constraints
+    ///
+    /// Unlike `is_valid`, which checks the tableau, this is meant to be called before solving, to
+    /// warn the user
+    /// ```rust
+    /// use simplex::{LinearProgram, ValidationWarning};
+    ///
+    /// let program = "max x + y\n x <= 3".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(
+    ///     program.validate(),
+    ///     Err(vec![ValidationWarning::UnconstrainedObjectiveVariable("y".parse().unwrap())])
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), Vec<ValidationWarning>> {
+        let originals = self.constraints.original_constraints();
+        let constrained = originals
+            .iter()
+            .flat_map(|constraint| (constraint.left.clone() - constraint.right.clone()).simplified().non_gap_variables())
+            .chain(self.bounds.keys().cloned())
+            .flat_map(|var| {
+                // The objective refers to the parts of the free variables
+                let (positive, negative) = free_variable_parts(&var);
+                if self.constraints.free_variables().contains(&var) {
+                    vec![positive, negative]
+                } else {
+                    vec![var]
+                }
+            })
+            .collect::<HashSet<_>>();
+
+        let warnings = self
+            .linear_function
+            .simplified()
+            .non_gap_variables()
+            .into_iter()
+            .filter(|var| !constrained.contains(var))
+            .sorted()
+            .map(ValidationWarning::UnconstrainedObjectiveVariable)
+            .chain(originals.iter().enumerate().filter_map(|(i, constraint)| {
+                let is_empty = (constraint.left.clone() - constraint.right.clone()).is_constant(self.tolerances.zero);
+                is_empty.then_some(ValidationWarning::EmptyConstraint(i))
+            }))
+            .chain(self.constraints.negativity_conflict().map(ValidationWarning::ForcedNegative))
+            .chain(self.constraints.merged_variables().iter().map(|(var, names)| {
+                ValidationWarning::MergedVariables { names: names.clone(), into: var.clone() }
+            }))
+            .collect::<Vec<_>>();
+
+        if warnings.is_empty() {
+            Ok(())
+        } else {
+            Err(warnings)
+        }
+    }
+
+    /// Returns the program optimizing `objective` in the given sense under the same constraints,
+    /// starting over from the original constraints even if this program was pivoted
+    ///
+    /// Variables of the new objective which no constraint restricts are reported by `validate`
+    /// ```rust
+    /// use simplex::{LinearProgram, ObjectiveSense};
+    ///
+    /// let program = "max x\n x + y <= 4".parse::<LinearProgram>().unwrap();
+    /// let other = program.with_objective("y".parse().unwrap(), ObjectiveSense::Min);
+    /// assert_eq!(other.sense, ObjectiveSense::Min);
+    /// assert_eq!(other.objective_value(), 0.0);
+    /// ```
+    pub fn with_objective(&self, objective: LinearFunction, sense: ObjectiveSense) -> LinearProgram {
+        let mut constraints = self.constraints.clone();
+        constraints.rebuild_from(self.constraints.original_constraints().to_vec());
+        LinearProgram {
+            tolerances: self.tolerances,
+            variable_order: self.variable_order,
+            ..constraints.to_program(&objective, sense)
+        }
+    }
+
+    /// Returns a copy of this program comparing floating point values with `tolerances`, e.g. to
+    /// solve the same program with several precisions. Each program, and each simplex started
+    /// from it, keeps its own tolerances
+    /// ```rust
+    /// use simplex::{LinearProgram, Simplex, Tolerances};
+    ///
+    /// let program = "max 0.00001x + y\n x <= 1\n y <= 1".parse::<LinearProgram>().unwrap();
+    /// let loose = program.clone_with_tolerance(Tolerances { zero: 1e-4, ..Tolerances::default() });
+    /// assert_eq!(loose.tolerances.zero, 1e-4);
+    /// assert_eq!(program.tolerances, Tolerances::default());
+    /// ```
+    pub fn clone_with_tolerance(&self, tol: Tolerances) -> LinearProgram {
+        LinearProgram {
+            tolerances: tol,
+            ..self.clone()
+        }
+    }
+
+    /// Builds the dual of this program, with a variable `y{i}` for the `i`th row of the tableau
+    /// and a constraint for each decision variable
+    ///
+    /// The dual is built from the original constraints, with every row turned into a `<=` one (an
+    /// equation giving two rows, like in the tableau) and every finite bound into an additional
+    /// row, so the decision variables are supposed to be non negative. It uses the current
+    /// objective, hence should be computed before any pivot. The sense is flipped, and both
+    /// programs have the same optimal value
+    /// ```rust
+    /// use simplex::{LinearProgram, ObjectiveSense};
+    ///
+    /// let program = "max 3x + 2y\n x + y <= 4\n x <= 3".parse::<LinearProgram>().unwrap();
+    /// let dual = program.dual();
+    /// assert_eq!(dual.sense, ObjectiveSense::Min);
+    /// let constraints = dual.constraints.original_constraints().iter().map(|c| c.to_string());
+    /// assert_eq!(constraints.collect::<Vec<_>>(), vec!["y0 + y1 >= 3.0", "y0 >= 2.0"]);
+    /// ```
+    pub fn dual(&self) -> LinearProgram {
+        // Every row as `function <= bound`, the constant being moved to the bound
+        let mut rows: Vec<(LinearFunction, Coefficient)> = vec![];
+        let mut add_row = |mut function: LinearFunction| {
+            let bound = -function.constant;
+            function.constant = 0.0;
+            rows.push((function, bound));
+        };
+        for constraint in self.constraints.original_constraints() {
+            let function = constraint.left.clone() - constraint.right.clone();
+            match constraint.operator {
+                Operator::Less | Operator::LessEqual => add_row(function),
+                Operator::Greater | Operator::GreaterEqual => add_row(-function),
+                Operator::Equal => {
+                    add_row(function.clone());
+                    add_row(-function);
+                }
+            }
+        }
+        // Bounded variables are shifted by their lower bound in the objective
+        let mut constant = self.linear_function.constant;
+        for (var, (lower, upper)) in self.bounds.iter().sorted_by_key(|(var, _)| *var) {
+            constant -= self.linear_function[var] * lower;
+            if upper.is_finite() {
+                add_row(LinearFunction::single_variable(var.clone()) - LinearFunction::constant(*upper));
+            }
+            if *lower != 0.0 {
+                add_row(LinearFunction::constant(*lower) - LinearFunction::single_variable(var.clone()));
+            }
+        }
+
+        let mut constraints = Constraints::new();
+        for var in self.non_gap_variables() {
+            let left = rows
+                .iter()
+                .enumerate()
+                .fold(LinearFunction::zero(), |acc, (i, (function, _))| {
+                    acc + LinearFunction::term(function[&var], Variable::unchecked(format!("y{i}")))
+                })
+                .simplified();
+            let right = LinearFunction::constant(self.linear_function[&var]);
+            constraints.add_constraint(Constraint::new(left, Operator::GreaterEqual, right));
+        }
+
+        // The dual minimizes `Σ bound_i * y_i` for a maximization, hence maximizes its opposite
+        let objective = rows
+            .iter()
+            .enumerate()
+            .fold(LinearFunction::constant(-constant), |acc, (i, (_, bound))| {
+                acc - LinearFunction::term(*bound, Variable::unchecked(format!("y{i}")))
+            })
+            .simplified();
+        let sense = match self.sense {
+            ObjectiveSense::Max => ObjectiveSense::Min,
+            ObjectiveSense::Min => ObjectiveSense::Max,
+        };
+        LinearProgram {
+            tolerances: self.tolerances,
+            ..constraints.to_program(&sense.to_maximized(&objective), sense)
+        }
     }
 
-    /// only works on a proper linear program which is verif by is_valid function
+    /// Checks that `primal` and `dual` certify the optimality of this program, without solving
+    /// it, returning the list of violated conditions otherwise
+    ///
+    /// `dual` holds a value per original constraint, in the convention of `shadow_prices`: how
+    /// much the objective value changes for each unit the constraint is relaxed by. The certificate
+    /// is checked for primal feasibility (the constraints and the bounds hold at `primal`), dual
+    /// feasibility (relaxing an inequality can't worsen the objective, and no variable can improve
+    /// it in a direction it is free to move in), and complementary slackness (only binding
+    /// constraints and variables at one of their bounds have a non zero dual value, respectively
+    /// reduced cost). Like `dual`, this uses the current objective, hence should be called before
+    /// any pivot
+    ///
+    /// Since the coefficients are `f32`, every condition is checked up to the `certificate`
+    /// tolerance of the program
+    /// ```rust
+    /// use simplex::LinearProgram;
+    /// use std::collections::HashMap;
+    ///
+    /// let program = "max 3x + 2y\n x + y <= 4\n x <= 3".parse::<LinearProgram>().unwrap();
+    /// let primal = HashMap::from([("x".parse().unwrap(), 3.0), ("y".parse().unwrap(), 1.0)]);
+    /// assert_eq!(program.verify_optimal(&primal, &[2.0, 1.0]), Ok(()));
+    /// // Relaxing `x + y <= 4` improves the objective, so its dual value can't be 0
+    /// assert!(program.verify_optimal(&primal, &[0.0, 3.0]).is_err());
+    /// ```
+    pub fn verify_optimal(&self, primal: &HashMap<Variable, Coefficient>, dual: &[Coefficient]) -> Result<(), Vec<String>> {
+        let originals = self.constraints.original_constraints();
+        if dual.len() != originals.len() {
+            return Err(vec![format!("expected {} dual values, found {}", originals.len(), dual.len())]);
+        }
+        let mut violations = vec![];
+        let tolerance = self.tolerances.certificate;
+        let is_zero = |value: Coefficient| value.abs() <= tolerance;
+
+        // Every constraint as `residual >= 0`, with a multiplier in the maximized sense which must
+        // be non negative unless the constraint is an equation
+        for (i, (constraint, value)) in originals.iter().zip(dual).enumerate() {
+            let residual = constraint.residual(primal);
+            let multiplier = self.sense.sign() * value;
+            let is_equation = constraint.operator == Operator::Equal;
+            if residual < -tolerance || (is_equation && !is_zero(residual)) {
+                violations.push(format!("constraint #{i} ({constraint}) doesn't hold, its residual is {residual}"));
+            }
+            if !is_equation && multiplier < -tolerance {
+                violations.push(format!("the dual value {value} of constraint #{i} has the wrong sign"));
+            }
+            if !is_zero(residual) && !is_zero(multiplier) {
+                violations.push(format!("constraint #{i} has a slack of {residual} but a dual value of {value}"));
+            }
+        }
+
+        // The reduced cost of each variable, i.e. how much increasing it improves the maximized
+        // objective once the constraints are priced by the dual values
+        let free = self.constraints.free_variables();
+        let variables = originals
+            .iter()
+            .flat_map(Constraint::non_gap_variables)
+            .chain(self.non_gap_variables())
+            .chain(primal.keys().cloned())
+            .unique()
+            .sorted();
+        for var in variables {
+            let cost = if free.contains(&var) {
+                self.linear_function[&free_variable_parts(&var).0]
+            } else {
+                self.linear_function[&var]
+            };
+            let reduced_cost = originals.iter().zip(dual).fold(cost, |acc, (constraint, value)| {
+                // How much increasing the variable consumes the residual of the constraint
+                let usage = -constraint.residual(&HashMap::from([(var.clone(), 1.0)]))
+                    + constraint.residual(&HashMap::new());
+                acc - self.sense.sign() * value * usage
+            });
+            let (lower, upper) = match self.bounds.get(&var) {
+                Some(bounds) => *bounds,
+                None if free.contains(&var) => (Coefficient::NEG_INFINITY, Coefficient::INFINITY),
+                None => (0.0, Coefficient::INFINITY),
+            };
+            let value = primal.get(&var).copied().unwrap_or(0.0);
+            if value < lower - tolerance || value > upper + tolerance {
+                violations.push(format!("{var} = {value} is out of its bounds [{lower}, {upper}]"));
+            }
+            // A variable which can still increase mustn't improve the objective, and one which
+            // can still decrease mustn't worsen it
+            if (upper.is_infinite() && reduced_cost > tolerance)
+                || (lower.is_infinite() && reduced_cost < -tolerance)
+            {
+                violations.push(format!("the reduced cost {reduced_cost} of {var} has the wrong sign"));
+            } else if (reduced_cost > tolerance && !is_zero(value - upper))
+                || (reduced_cost < -tolerance && !is_zero(value - lower))
+            {
+                violations.push(format!("{var} = {value} isn't at a bound but has a reduced cost of {reduced_cost}"));
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// only works on a tableau whose rows all have a single basic variable, see
+    /// [`ValidityReport::is_basic`], the basic solution being possibly infeasible
+    ///
+    /// The coordinates follow the order given by `variable_order`. Without any constraint, the
+    /// tableau is trivially valid and every variable of the objective is out of the base
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max 5 - x".parse::<LinearProgram>().unwrap();
+    /// assert!(program.is_valid());
+    /// assert_eq!(program.point(), vec![0.0]);
+    /// ```
     pub fn point(&self) -> Vec<f32> {
-        if !self.is_valid() {
-            panic!("Linear program is not valid");
+        let report = self.validity_report();
+        if !report.is_basic() {
+            panic!("Linear program is not valid:\n{report}");
         }
-        let variables = self.non_gap_variables();
-        let mut point = vec![0.0; variables.len()];
+        let basic_values: HashMap<Variable, Coefficient> = self
+            .constraints
+            .iter()
+            .filter_map(|constraint| Some((constraint.left.name_single_variable()?, constraint.right.constant)))
+            .collect();
+        let value = |var: &Variable| basic_values.get(var).copied().unwrap_or(0.0);
 
-        for constraint in self.constraints.iter() {
-            if let Some(left_variable) = constraint.left.name_single_variable() {
-                if let Some(index) = variables.iter().position(|v| *v == left_variable) {
-                    point[index] = constraint.right.constant;
+        // A free variable is the difference of its two parts
+        self.non_gap_variables()
+            .iter()
+            .map(|var| {
+                if self.constraints.free_variables().contains(var) {
+                    let (positive, negative) = free_variable_parts(var);
+                    value(&positive) - value(&negative)
+                } else {
+                    self.actual_value(var, value(var))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the objective of the current tableau in the sense given by the user, i.e. not
+    /// negated for a minimization problem unlike `linear_function`
+    pub fn objective(&self) -> LinearFunction {
+        self.sense.to_maximized(&self.linear_function)
+    }
+
+    /// Returns the value of the objective at the current vertex, in the sense given by the user
+    /// (i.e. not negated for a minimization problem)
+    pub fn objective_value(&self) -> Coefficient {
+        self.sense.sign() * self.linear_function.constant
+    }
+
+    /// Returns the value of `expr` at the current vertex, the variables which aren't decision
+    /// variables of the program counting as 0
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max x + y\n x >= 1\n x + y <= 4".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(program.value_of(&"2x + 3z + 1".parse().unwrap()), 1.0);
+    /// ```
+    pub fn value_of(&self, expr: &LinearFunction) -> Coefficient {
+        expr.evaluate(&self.values())
+    }
+
+    /// Returns the gap variable measuring the slack of the original constraint at index `i`, or
+    /// `None` if there is no such constraint, see [`Constraints::gap_variable_of`]
+    ///
+    /// Gap variables are named after the row they were added for, `ε0` for the first one, so the
+    /// name stays the same after any pivot, whether the gap variable is in the base or not. As
+    /// the name is derived from the row instead of being stored, it is returned by value
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let mut program = "max x\n x + y = 4\n x <= 3".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(program.slack_for_constraint(1).unwrap(), "ε2");
+    /// program.pivot("x".parse().unwrap()).unwrap();
+    /// assert_eq!(program.slack_for_constraint(1).unwrap(), "ε2");
+    /// assert_eq!(program.slack_for_constraint(2), None);
+    /// ```
+    pub fn slack_for_constraint(&self, i: usize) -> Option<Variable> {
+        self.constraints.gap_variable_of(i)
+    }
+
+    /// Returns the shadow price of each original constraint, i.e. how much the objective value
+    /// (in the user's sense) changes for each unit the constraint is relaxed by
+    ///
+    /// An equation is relaxed by moving its right-hand side up, which loosens its first row and
+    /// tightens its second one. Only meaningful once the optimum has been reached
+    pub fn shadow_prices(&self) -> Vec<Coefficient> {
+        let reduced_cost = |row: usize| self.linear_function[&format!("{GAP_VARIABLE_IDENTIFIER}{row}")];
+        self.constraints
+            .original_constraints()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, constraint)| {
+                let row = self.constraints.gap_row_of(i)?;
+                let mut cost = reduced_cost(row);
+                if constraint.operator == Operator::Equal {
+                    cost -= reduced_cost(row + 1);
                 }
+                // Adding 0 turns a -0 into a 0
+                Some(-self.sense.sign() * cost + 0.0)
+            })
+            .collect()
+    }
+
+    /// Returns how much the objective changes per unit of distance travelled along the edge of
+    /// each variable out of the base, in alphabetical order of the variables
+    ///
+    /// Increasing such a variable by 1 changes each basic variable by its coefficient in the
+    /// row, so the edge has length `sqrt(1 + Σ coefficient²)`, and the objective changes by the
+    /// coefficient of the variable in it
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max 2x + y\n 3x + y <= 4".parse::<LinearProgram>().unwrap();
+    /// let improvements = program.edge_improvements();
+    /// assert_eq!(improvements[0].0, "x");
+    /// assert!((improvements[0].1 - 2.0 / 10f32.sqrt()).abs() < 1e-6);
+    /// assert!((improvements[1].1 - 1.0 / 2f32.sqrt()).abs() < 1e-6);
+    /// ```
+    pub fn edge_improvements(&self) -> Vec<(Variable, Coefficient)> {
+        self.out_of_base_variables()
+            .into_iter()
+            .chain(self.linear_function.variables())
+            .unique()
+            .sorted()
+            .map(|var| {
+                let squared_length = self
+                    .constraints
+                    .iter()
+                    .fold(1.0, |acc, constraint| acc + constraint.right[&var].powi(2));
+                let improvement = self.linear_function[&var] / squared_length.sqrt();
+                (var, improvement)
+            })
+            .collect()
+    }
+
+    /// Returns the slack of each original constraint at the current vertex, along with its index,
+    /// i.e. the value of its gap variable. The constraints with no slack are binding
+    pub fn slack_values(&self) -> Vec<(usize, Coefficient)> {
+        let valuation = self.valuation();
+        (0..self.constraints.original_constraints().len())
+            .filter_map(|i| {
+                let gap = self.constraints.gap_variable_of(i)?;
+                Some((i, valuation.get(&gap).copied().unwrap_or(0.0)))
+            })
+            .collect()
+    }
+
+    /// Formats the shadow prices with one `label: price` line per constraint, using the index of
+    /// the constraint for the unlabeled ones
+    pub fn shadow_prices_report(&self) -> String {
+        self.constraints
+            .original_constraints()
+            .iter()
+            .zip(self.shadow_prices())
+            .enumerate()
+            .fold(String::new(), |acc, (i, (constraint, price))| match &constraint.label {
+                Some(label) => format!("{acc}{label}: {price}\n"),
+                None => format!("{acc}#{i}: {price}\n"),
+            })
+    }
+
+    /// Gives the value of every variable of the program (gap variables included) at the current
+    /// vertex, a free variable being given as itself rather than as its two parts
+    pub fn valuation(&self) -> HashMap<Variable, Coefficient> {
+        let mut valuation = HashMap::new();
+        for var in self.linear_function.var_iter() {
+            valuation.insert(var.clone(), 0.0);
+        }
+        for constraint in self.constraints.iter() {
+            for var in constraint.right.var_iter() {
+                valuation.insert(var.clone(), 0.0);
             }
         }
-        point
+        for constraint in self.constraints.iter() {
+            if let Some(var) = constraint.left.name_single_variable() {
+                valuation.insert(var, constraint.right.constant);
+            }
+        }
+        for (var, value) in valuation.iter_mut() {
+            *value = self.actual_value(var, *value);
+        }
+        for var in self.constraints.free_variables() {
+            let (positive, negative) = free_variable_parts(var);
+            let (positive, negative) = (valuation.remove(&positive), valuation.remove(&negative));
+            if positive.is_some() || negative.is_some() {
+                valuation.insert(var.clone(), positive.unwrap_or(0.0) - negative.unwrap_or(0.0));
+            }
+        }
+        valuation
     }
 
+    /// Returns the value of each non gap variable at the current vertex, in the order given by
+    /// `variable_order`
+    ///
+    /// Those values do not depend on the objective sense
     pub fn values(&self) -> Vec<(Variable, Coefficient)> {
         let variables = self.non_gap_variables();
         let values = self.point();
@@ -69,13 +1161,85 @@ impl LinearProgram {
         variables.into_iter().zip(values).collect()
     }
 
-    /// Give every non gap variables of a linear program sorted by alphabetical order
-    pub fn non_gap_variables(&self) -> Vec<String> {
+    /// Gives an overview of the program: variables, constraints by operator and feasibility of
+    /// the origin
+    pub fn summary(&self) -> ProblemSummary {
+        let original = self.constraints.original_constraints();
+        let count = |ops: &[Operator]| original.iter().filter(|c| ops.contains(&c.operator)).count();
+
+        ProblemSummary {
+            decision_variables: self.non_gap_variables().len(),
+            constraints: original.len(),
+            less_than: count(&[Operator::Less, Operator::LessEqual]),
+            greater_than: count(&[Operator::Greater, Operator::GreaterEqual]),
+            equal: count(&[Operator::Equal]),
+            gap_variables: self.constraints.gap_variables_count(),
+            origin_is_feasible: original.iter().all(|c| c.is_satisfied_at(&HashMap::new())),
+        }
+    }
+
+    /// Give every non gap variables of a linear program, sorted according to `variable_order`
+    pub fn non_gap_variables(&self) -> Vec<Variable> {
         let mut var_set: HashSet<Variable> = HashSet::from_iter(self.linear_function.non_gap_variables());
         for v in self.constraints.non_gap_variables() {
             var_set.insert(v);
         }
-        var_set.into_iter().sorted().collect()
+        // The two parts of a free variable are given as the variable itself
+        for var in self.constraints.free_variables() {
+            let (positive, negative) = free_variable_parts(var);
+            if var_set.remove(&positive) | var_set.remove(&negative) {
+                var_set.insert(var.clone());
+            }
+        }
+        match self.variable_order {
+            VariableOrder::Alphabetical => var_set.into_iter().sorted().collect(),
+            VariableOrder::Natural => var_set.into_iter().sorted_by(|a, b| natural_cmp(a, b)).collect(),
+            VariableOrder::InsertionOrder => {
+                let appearances = self
+                    .constraints
+                    .original_constraints()
+                    .iter()
+                    .flat_map(|constraint| constraint.non_gap_variables().into_iter().sorted())
+                    .chain(self.linear_function.non_gap_variables().into_iter().sorted())
+                    .unique()
+                    .collect::<Vec<_>>();
+                // Variables which got in the tableau otherwise (e.g. through a substitution) come last
+                let position = |var: &Variable| appearances.iter().position(|v| v == var).unwrap_or(usize::MAX);
+                var_set.into_iter().sorted_by(|a, b| position(a).cmp(&position(b)).then_with(|| a.cmp(b))).collect()
+            }
+        }
+    }
+
+    /// Returns the ratio of each row of the tableau for `entering`, along with the index of the
+    /// row: how much `entering` can increase before the basic variable of the row reaches 0, or
+    /// `None` if the row doesn't bound it
+    ///
+    /// A row `basic = constant + coefficient * entering + ...` bounds `entering` when its
+    /// coefficient is negative, giving the ratio `constant / -coefficient`. The leaving row is the
+    /// one with the smallest ratio, see [`Constraints::most_restrictive`]. The bounds of the
+    /// variables aren't taken into account
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "max x + y\n x <= 4\n 2x + y <= 6\n y <= 5".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(program.ratio_test("x"), vec![(0, Some(4.0)), (1, Some(3.0)), (2, None)]);
+    /// ```
+    pub fn ratio_test(&self, entering: &str) -> Vec<(usize, Option<Coefficient>)> {
+        self.constraints
+            .iter()
+            .enumerate()
+            .map(|(i, constraint)| (i, constraint.ratio(entering, self.tolerances.zero)))
+            .collect()
+    }
+
+    /// Returns the basic variables of the tableau, i.e. the left side of each row, sorted
+    /// alphabetically
+    pub fn basic_variables(&self) -> Vec<Variable> {
+        self.constraints
+            .iter()
+            .filter_map(|constraint| constraint.left.name_single_variable())
+            .sorted()
+            .collect()
     }
 
     fn out_of_base_variables(&self) -> Vec<Variable> {
@@ -94,63 +1258,1036 @@ impl Simplex {
         self.index == 0
     }
 
-    pub fn next_step(&mut self, use_bland_rule: bool) -> Result<(), SimplexError> {
-        if let Some(var) = self
-            .current_state()
-            .linear_function
-            .first_positive_coefficient(use_bland_rule)
-        {
-            if self.index == self.historic.len() - 1 {
-                let mut new = self.current_state().clone();
-                new.pivot(var)?;
-                self.historic.push(new);
-            }
-            self.index += 1;
-            Ok(())
-        } else {
-            Err(SimplexError::AlreadyOptimal)
+    /// Moves to the next step, computing it with `rule` if it wasn't already
+    pub fn next_step(&mut self, rule: PivotRule) -> Result<(), SimplexError> {
+        if self.index == self.historic.len() - 1 {
+            self.compute_next_step(rule, None, None)?;
         }
+        self.index += 1;
+        self.evict_old_steps();
+        Ok(())
     }
 
-    pub fn previous_step(&mut self) {
-        if !self.is_first_step() {
-            self.index -= 1;
-        }
+    /// Moves to the next step like [`Simplex::next_step`] did before it took a `PivotRule`:
+    /// `use_bland_rule` gives Bland's rule if set and Dantzig's otherwise
+    #[deprecated(note = "pass a PivotRule")]
+    pub fn next_step_with_flag(&mut self, use_bland_rule: bool) -> Result<(), SimplexError> {
+        self.next_step(if use_bland_rule { PivotRule::Bland } else { PivotRule::Dantzig })
     }
 
-    /// Returns a reference to the current state of the algorithm
-    pub fn current_state(&self) -> &LinearProgram {
-        &self.historic[self.index]
-    }
+    /// Pivots on `var`, chosen by the user instead of a pivot rule, the leaving variable being
+    /// chosen by the ratio test. This gives `NotImproving` if increasing `var` wouldn't improve
+    /// the objective of the current step
+    ///
+    /// If some steps were already computed after the current one, they are dropped as the
+    /// history now branches off at the current step
+    /// ```rust
+    /// use simplex::{LinearProgram, Simplex, SimplexError};
+    ///
+    /// let program = "max x + 2y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.step_with_entering("x").unwrap();
+    /// assert_eq!(simplex.current_values(), vec![("x".parse().unwrap(), 2.0), ("y".parse().unwrap(), 0.0)]);
+    /// assert_eq!(simplex.step_with_entering("x"), Err(SimplexError::NotImproving("x".parse().unwrap())));
+    /// ```
+    pub fn step_with_entering(&mut self, var: &str) -> Result<(), SimplexError> {
+        let current = self.current_state();
+        if current.linear_function.coefficient_of(var) <= current.tolerances.zero {
+            return Err(SimplexError::NotImproving(Variable::unchecked(var)));
+        }
 
-    pub fn current_point(&self) -> Vec<f32> {
-        self.current_state().point()
+        // The later steps are only dropped once the new one is known to exist
+        let step = self.compute_step(PivotRule::Dantzig, Some(Variable::unchecked(var)), None)?;
+        let kept = self.index + 1;
+        self.historic.truncate(kept);
+        self.degenerate.truncate(kept);
+        self.phases.truncate(kept);
+        self.log.truncate(self.index);
+        self.warnings.retain(|NumericalWarning::SmallPivot { step, .. }| *step < kept);
+
+        self.record_step(step);
+        self.index += 1;
+        self.evict_old_steps();
+        Ok(())
     }
 
-    pub fn current_values(&self) -> Vec<(Variable, Coefficient)> {
-        self.current_state().values()
+    /// Computes the step following the last one of the historic, `entering` entering the base if
+    /// given instead of the variable chosen by `rule`, and `leaving` leaving it if given and tied
+    /// in the ratio test, see [`LinearProgram::tied_leaving_row`]
+    fn compute_next_step(
+        &mut self,
+        rule: PivotRule,
+        entering: Option<Variable>,
+        leaving: Option<&Variable>,
+    ) -> Result<(), SimplexError> {
+        let step = self.compute_step(rule, entering, leaving)?;
+        self.record_step(step);
+        Ok(())
     }
-}
 
-impl From<LinearProgram> for Simplex {
-    fn from(value: LinearProgram) -> Self {
-        Simplex {
-            index: 0,
-            historic: vec![value],
+    /// Computes the step following the current one like [`Simplex::compute_next_step`], without
+    /// changing the historic
+    fn compute_step(
+        &self,
+        rule: PivotRule,
+        entering: Option<Variable>,
+        leaving: Option<&Variable>,
+    ) -> Result<ComputedStep, SimplexError> {
+        let current = self.current_state();
+        let mut phase = self.current_phase();
+        let mut new = current.clone();
+
+        let (var, pivot_index) = if phase == Phase::Phase1 && !current.is_feasible() {
+            // The first pivot of phase one brings the artificial variable in the base, in place
+            // of the most negative variable, which makes the base feasible
+            let var = artificial_variable();
+            let index = (0..current.constraints.len())
+                .min_by(|i, j| {
+                    current.constraints[*i].right.constant.total_cmp(&current.constraints[*j].right.constant)
+                })
+                .ok_or(SimplexError::Infeasible)?;
+            new.pivot_on(index, &var);
+            (var, Some(index))
+        } else {
+            match entering.or_else(|| rule.entering_variable_in(current)) {
+                Some(var) => {
+                    let pivot_index = match leaving.and_then(|leaving| new.tied_leaving_row(&var, leaving)) {
+                        Some(index) => {
+                            new.pivot_on(index, &var);
+                            Some(index)
+                        }
+                        None => new.pivot_with_rule(var.clone(), rule)?,
+                    };
+                    (var, pivot_index)
+                }
+                None if phase == Phase::Phase1 => return Err(SimplexError::Infeasible),
+                None => return Err(SimplexError::AlreadyOptimal),
+            }
+        };
+        // The pivot is degenerate if it didn't move the vertex, hence didn't change the objective
+        let degenerate = (new.linear_function.constant - current.linear_function.constant).abs()
+            <= current.tolerances.zero;
+
+        // Phase one is over once the artificial variable can't be lowered anymore. If it
+        // reached 0, phase two starts right away, otherwise the program is infeasible
+        if phase == Phase::Phase1
+            && rule.entering_variable_in(&new).is_none()
+            && new.linear_function.constant >= -new.tolerances.zero
+        {
+            if let Some(objective) = &self.phase_two_objective {
+                new.end_phase_one(objective);
+                phase = Phase::Phase2;
+            }
         }
+
+        let record = PivotRecord {
+            leaving: pivot_index.and_then(|i| current.constraints[i].left.name_single_variable()),
+            pivot_element: pivot_index.map(|i| current.constraints[i].right[&var]),
+            entering: var,
+            objective_value: new.objective_value(),
+        };
+        Ok(ComputedStep {
+            state: new,
+            degenerate,
+            phase,
+            record,
+        })
     }
-}
+
+    /// Appends a computed step to the historic, warning about its pivot if it is too small
+    fn record_step(&mut self, step: ComputedStep) {
+        let ComputedStep { state, degenerate, phase, record } = step;
+        if let Some(pivot_element) = record.pivot_element.filter(|e| e.abs() < state.tolerances.pivot) {
+            self.warnings.push(NumericalWarning::SmallPivot {
+                step: self.historic.len(),
+                entering: record.entering.clone(),
+                pivot_element,
+            });
+        }
+        self.historic.push(OnceCell::from(state));
+        self.degenerate.push(degenerate);
+        self.log.push(record);
+        self.phases.push(phase);
+    }
+
+    /// Keeps at most `limit` steps (at least one) in the historic: once it is full, computing a
+    /// step drops the oldest one, which bounds the memory used by long solves
+    ///
+    /// The steps are then numbered from the oldest one kept, which becomes the initial state,
+    /// and `previous_step` stops there. The pivot log, the computed objective values and the
+    /// numerical warnings only cover the steps kept, the warning about the pivot reaching the
+    /// new initial state being kept as its rounding errors carry over to the following steps
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max x + y + z\n x <= 1\n y <= 1\n z <= 1".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program).with_history_limit(2);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.objective_history(), vec![2.0, 3.0]);
+    /// simplex.previous_step();
+    /// simplex.previous_step();
+    /// assert_eq!(simplex.current_state().objective_value(), 2.0);
+    /// ```
+    pub fn with_history_limit(mut self, limit: usize) -> Simplex {
+        self.history_limit = Some(limit.max(1));
+        self.evict_old_steps();
+        self
+    }
+
+    /// Drops the oldest steps beyond the history limit, keeping the current one
+    fn evict_old_steps(&mut self) {
+        let Some(limit) = self.history_limit else {
+            return;
+        };
+        let excess = self.historic.len().saturating_sub(limit).min(self.index);
+        if excess == 0 {
+            return;
+        }
+        // The oldest step kept becomes the initial state, which is always built
+        self.state(excess);
+        self.historic.drain(..excess);
+        self.degenerate.drain(..excess);
+        self.phases.drain(..excess);
+        self.log.drain(..excess.min(self.log.len()));
+        self.index -= excess;
+        self.warnings.retain(|NumericalWarning::SmallPivot { step, .. }| *step >= excess);
+        for NumericalWarning::SmallPivot { step, .. } in self.warnings.iter_mut() {
+            *step -= excess;
+        }
+    }
+
+    /// Computes the pivots of [`Simplex::solve`] and [`Simplex::solve_with`] on the given
+    /// representation of the tableau
+    ///
+    /// Both backends record the same steps and pivot log, so only the speed of the solve changes,
+    /// along with the calls of `solve_with` which the dense backend only makes for the last step
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex, TableauBackend};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program).with_backend(TableauBackend::Dense);
+    /// simplex.solve(PivotRule::Dantzig).unwrap();
+    /// assert_eq!(simplex.current_state().objective_value(), 5.0);
+    /// assert_eq!(simplex.pivot_log().len(), 2);
+    /// ```
+    pub fn with_backend(mut self, backend: TableauBackend) -> Simplex {
+        self.backend = backend;
+        self
+    }
+
+    /// Returns the dense copy of the current tableau if the dense backend is selected and can
+    /// compute the remaining pivots with `rule`
+    fn dense_tableau(&self, rule: PivotRule) -> Option<dense::DenseTableau> {
+        let current = self.current_state();
+        let supported = self.backend == TableauBackend::Dense
+            && matches!(rule, PivotRule::Dantzig | PivotRule::Bland)
+            && self.current_phase() != Phase::Phase1
+            && self.index + 1 == self.historic.len()
+            && current.bounds.is_empty();
+        if !supported {
+            return None;
+        }
+        dense::DenseTableau::from_program(current)
+    }
+
+    /// Uses the given tolerances in every step of the historic: the steps still to be computed,
+    /// and the checks such as `is_optimal` on the steps already computed, which aren't recomputed
+    pub fn with_tolerances(mut self, tolerances: Tolerances) -> Simplex {
+        // The steps left to rebuild get them from the previous ones
+        for state in self.historic.iter_mut().filter_map(OnceCell::get_mut) {
+            state.tolerances = tolerances;
+        }
+        self
+    }
+
+    /// Lists the decision variables in the given order in every step, e.g. in `current_point`
+    pub fn with_variable_order(mut self, order: VariableOrder) -> Simplex {
+        for state in self.historic.iter_mut().filter_map(OnceCell::get_mut) {
+            state.variable_order = order;
+        }
+        self
+    }
+
+    pub fn tolerances(&self) -> Tolerances {
+        self.current_state().tolerances
+    }
+
+    /// Returns the numerical issues met while computing the steps so far, which may explain
+    /// inaccurate results on ill-conditioned problems
+    pub fn warnings(&self) -> &[NumericalWarning] {
+        &self.warnings
+    }
+
+    /// Returns the phase of the two-phase method the current step belongs to
+    pub fn current_phase(&self) -> Phase {
+        self.phases[self.index]
+    }
+
+    /// Returns the index of the current step among the computed ones
+    pub fn current_step_index(&self) -> usize {
+        self.index
+    }
+
+    /// Steps forward with `rule` until the optimum is reached
+    ///
+    /// Dantzig's rule may cycle forever on a degenerate program, which the other rules prevent
+    pub fn solve(&mut self, rule: PivotRule) -> Result<(), SimplexError> {
+        self.solve_with(rule, |_, _| {})
+    }
+
+    /// Solves like [`Simplex::solve`], calling `on_step` after each step with the new state and
+    /// its index, e.g. to animate or log the progress of a long solve
+    pub fn solve_with(
+        &mut self,
+        rule: PivotRule,
+        mut on_step: impl FnMut(&LinearProgram, usize),
+    ) -> Result<(), SimplexError> {
+        loop {
+            if let Some(tableau) = self.dense_tableau(rule) {
+                return self.solve_dense(tableau, rule, &mut on_step);
+            }
+            match self.next_step(rule) {
+                Ok(()) => on_step(self.current_state(), self.index),
+                Err(SimplexError::AlreadyOptimal) => return Ok(()),
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Solves like [`Simplex::solve`], but gives up with `IterationLimit` after `max_steps` steps,
+    /// or with `TimedOut` once `max_duration` has elapsed, e.g. to keep the GUI responsive on
+    /// untrusted input. The steps computed before giving up are kept, so solving can go on later
+    ///
+    /// The duration is checked between steps, and isn't checked at all on WebAssembly where the
+    /// clock of the standard library isn't available
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex, SimplexError};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// assert_eq!(simplex.solve_with_budget(PivotRule::Bland, 1, None), Err(SimplexError::IterationLimit));
+    /// assert_eq!(simplex.solve_with_budget(PivotRule::Bland, 1, None), Ok(()));
+    /// ```
+    pub fn solve_with_budget(
+        &mut self,
+        rule: PivotRule,
+        max_steps: usize,
+        max_duration: Option<std::time::Duration>,
+    ) -> Result<(), SimplexError> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let deadline = max_duration.map(|duration| std::time::Instant::now() + duration);
+        #[cfg(target_arch = "wasm32")]
+        let _ = max_duration;
+
+        for _ in 0..max_steps {
+            #[cfg(not(target_arch = "wasm32"))]
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                return Err(SimplexError::TimedOut);
+            }
+            match self.next_step(rule) {
+                Ok(()) => {}
+                Err(SimplexError::AlreadyOptimal) => return Ok(()),
+                Err(error) => return Err(error),
+            }
+        }
+        if self.is_optimal() {
+            Ok(())
+        } else {
+            Err(SimplexError::IterationLimit)
+        }
+    }
+
+    /// Turns the simplex into an iterator over the states following the current one, computed
+    /// lazily with `rule`. The iterator ends after the optimum, or after yielding the error
+    /// which stopped the algorithm
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let objectives: Vec<f32> = Simplex::from(program)
+    ///     .into_steps(PivotRule::Bland)
+    ///     .map(|state| state.unwrap().objective_value())
+    ///     .collect();
+    /// assert_eq!(objectives, vec![2.0, 5.0]);
+    /// ```
+    pub fn into_steps(mut self, rule: PivotRule) -> impl Iterator<Item = Result<LinearProgram, SimplexError>> {
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            match self.next_step(rule) {
+                Ok(()) => Some(Ok(self.current_state().clone())),
+                Err(SimplexError::AlreadyOptimal) => {
+                    done = true;
+                    None
+                }
+                Err(error) => {
+                    done = true;
+                    Some(Err(error))
+                }
+            }
+        })
+    }
+
+    /// Optimizes each objective in turn, in the sense of this program, while keeping the previous
+    /// ones at their optimal value: the second objective only breaks the ties of the first one,
+    /// and so on
+    ///
+    /// The simplex is started over from the original constraints, with one more constraint per
+    /// optimized objective fixing its level, so the final tableau holds all of them. Since fixing
+    /// levels makes the tableau degenerate, every objective is solved with Bland's rule
+    pub fn solve_lexicographic(&mut self, objectives: &[LinearFunction]) -> Result<(), SimplexError> {
+        let state = self.current_state();
+        let (sense, tolerances, order) = (state.sense, state.tolerances, state.variable_order);
+        let mut constraints = state.constraints.clone();
+        constraints.rebuild_from(state.constraints.original_constraints().to_vec());
+
+        for (i, objective) in objectives.iter().enumerate() {
+            let mut simplex = match sense {
+                ObjectiveSense::Max => constraints.maximize(objective)?,
+                ObjectiveSense::Min => constraints.minimize(objective)?,
+            }
+            .with_tolerances(tolerances)
+            .with_variable_order(order);
+            simplex.solve(PivotRule::Bland)?;
+
+            // Slightly loosen the level, so that rounding errors can't make the next program infeasible
+            let value = simplex.current_state().objective_value();
+            let level = match sense {
+                ObjectiveSense::Max => Constraint::new(
+                    objective.clone(),
+                    Operator::GreaterEqual,
+                    LinearFunction::constant(value - tolerances.zero),
+                ),
+                ObjectiveSense::Min => Constraint::new(
+                    objective.clone(),
+                    Operator::LessEqual,
+                    LinearFunction::constant(value + tolerances.zero),
+                ),
+            };
+            if i + 1 < objectives.len() {
+                constraints.add_constraint(level.with_label(format!("objective #{i}")));
+            } else {
+                *self = simplex;
+            }
+        }
+        Ok(())
+    }
+
+    /// Panics if the objective got worse between two steps of the same phase of the computed
+    /// history, which the pivot rules should never allow
+    ///
+    /// Meant to be used in tests. The objective of phase one is compared separately, as it is
+    /// not the one given by the user. Rounding errors up to the `zero` tolerance, relative to the
+    /// objective value, are accepted
+    pub fn assert_monotonic_objective(&self) {
+        for (i, (before, after)) in self.states().tuple_windows().enumerate() {
+            let same_phase = self.phases[i] == self.phases[i + 1];
+            let (before, after) = (before.linear_function.constant, after.linear_function.constant);
+            let tolerance = self.tolerances().zero * before.abs().max(1.0);
+            assert!(
+                !same_phase || after >= before - tolerance,
+                "the objective got worse from step {} to step {}: {before} then {after}",
+                i,
+                i + 1
+            );
+        }
+    }
+
+    /// Captures the current step, see [`Simplex::restore`]
+    pub fn snapshot(&self) -> SimplexSnapshot {
+        SimplexSnapshot {
+            program: self.current_state().clone(),
+            phase: self.current_phase(),
+            phase_two_objective: self.phase_two_objective.clone(),
+        }
+    }
+
+    /// Splits the program around the fractional value `v` taken by `var` at the optimum, as branch
+    /// and bound does: the first child adds the constraint `var <= floor(v)`, the second one
+    /// `var >= ceil(v)`, so that their feasible regions are the one of this program without the
+    /// points where `floor(v) < var < ceil(v)`
+    ///
+    /// Both children start from the current optimal tableau along with their new constraint,
+    /// which the dual simplex makes feasible again while keeping it optimal, so that solving them
+    /// takes few pivots. A child the dual simplex can't make feasible, e.g. because it is
+    /// infeasible, starts with phase one instead
+    ///
+    /// Gives `NotOptimal` if the current step isn't an optimum, `UnknownVariable` if `var` isn't
+    /// a decision variable of the program, and `AlreadyIntegral` if `var` is integral there, up
+    /// to the `zero` tolerance
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max x + y\n 2x + 3y <= 12\n x <= 3.5".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// let (mut lower, mut upper) = simplex.fork_branch("x").unwrap();
+    /// lower.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(lower.current_point(), vec![3.0, 2.0]);
+    /// assert!(upper.solve(PivotRule::Bland).is_err());
+    /// ```
+    pub fn fork_branch(&self, var: &str) -> Result<(Simplex, Simplex), SimplexError> {
+        if !self.is_optimal() {
+            return Err(SimplexError::NotOptimal);
+        }
+        let var = Variable::new(var)?;
+        let current = self.current_state();
+        let Some((_, value)) = current.values().into_iter().find(|(other, _)| *other == var) else {
+            return Err(SimplexError::UnknownVariable(var));
+        };
+        if (value - value.round()).abs() <= current.tolerances.zero {
+            return Err(SimplexError::AlreadyIntegral(var));
+        }
+
+        let child = |operator: Operator, bound: Coefficient| {
+            let mut program = current.clone();
+            program.add_cut(&var, operator, bound);
+            program.dual_simplex();
+            let mut child = Simplex::from(program).with_backend(self.backend);
+            child.history_limit = self.history_limit;
+            child
+        };
+        Ok((
+            child(Operator::LessEqual, value.floor()),
+            child(Operator::GreaterEqual, value.ceil()),
+        ))
+    }
+
+    /// Starts a new simplex, without any history, from the step captured by `snapshot`
+    pub fn restore(snapshot: SimplexSnapshot) -> Simplex {
+        Simplex {
+            index: 0,
+            historic: vec![OnceCell::from(snapshot.program)],
+            degenerate: vec![false],
+            log: vec![],
+            phases: vec![snapshot.phase],
+            phase_two_objective: snapshot.phase_two_objective,
+            warnings: vec![],
+            history_limit: None,
+            backend: TableauBackend::Map,
+        }
+    }
+
+    /// Rebuilds the history of solving `lp` by replaying the pivots of `log`, e.g. one saved from
+    /// [`Simplex::pivot_log`], so that a given walkthrough can be scripted even if it doesn't
+    /// follow any pivot rule
+    ///
+    /// Each pivot must be valid for the state it is applied to: its entering variable must
+    /// improve the objective, and its leaving variable must be chosen by the ratio test, possibly
+    /// among tied ones. Otherwise, this gives `InvalidPivot` with the index of the pivot in `log`
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max x + 2y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program.clone());
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// let replayed = Simplex::replay(program, simplex.pivot_log()).unwrap();
+    /// assert_eq!(replayed.current_values(), simplex.current_values());
+    /// assert_eq!(replayed.pivot_log(), simplex.pivot_log());
+    /// ```
+    pub fn replay(lp: LinearProgram, log: &[PivotRecord]) -> Result<Simplex, SimplexError> {
+        let mut simplex = Simplex::from(lp);
+        for (i, record) in log.iter().enumerate() {
+            let current = simplex.current_state();
+            let is_improving = current.linear_function.coefficient_of(&record.entering) > current.tolerances.zero;
+            if !is_improving && record.entering != artificial_variable() {
+                return Err(SimplexError::InvalidPivot(i));
+            }
+            simplex.compute_next_step(PivotRule::Dantzig, Some(record.entering.clone()), record.leaving.as_ref())?;
+            simplex.index += 1;
+            let replayed = &simplex.log[simplex.log.len() - 1];
+            if replayed.entering != record.entering || replayed.leaving != record.leaving {
+                return Err(SimplexError::InvalidPivot(i));
+            }
+        }
+        Ok(simplex)
+    }
+
+    /// Returns true if the current step is an optimum: phase one is over and no variable can
+    /// improve the objective anymore
+    pub fn is_optimal(&self) -> bool {
+        let current = self.current_state();
+        self.current_phase() != Phase::Phase1
+            && PivotRule::Bland
+                .entering_variable(&current.linear_function, &current.tolerances)
+                .is_none()
+    }
+
+    /// Returns the basic variables of the optimal tableau, sorted alphabetically, or `NotOptimal`
+    /// if the current step isn't an optimum
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex, SimplexError};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// assert_eq!(simplex.optimal_basis(), Err(SimplexError::NotOptimal));
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.optimal_basis().unwrap(), vec!["x", "y"]);
+    /// assert_eq!(simplex.nonbasic_at_optimum().unwrap(), vec!["ε0", "ε1"]);
+    /// ```
+    pub fn optimal_basis(&self) -> Result<Vec<Variable>, SimplexError> {
+        if !self.is_optimal() {
+            return Err(SimplexError::NotOptimal);
+        }
+        Ok(self.current_state().basic_variables())
+    }
+
+    /// Returns the variables out of the base of the optimal tableau, whose tableau columns are at
+    /// their bound, sorted alphabetically, or `NotOptimal` if the current step isn't an optimum
+    ///
+    /// A bounded variable listed in `at_upper_bound` is out of the base at its upper bound
+    pub fn nonbasic_at_optimum(&self) -> Result<Vec<Variable>, SimplexError> {
+        if !self.is_optimal() {
+            return Err(SimplexError::NotOptimal);
+        }
+        Ok(self.current_state().out_of_base_variables().into_iter().sorted().collect())
+    }
+
+    /// Returns the status of the current step, without computing the next one
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex, SimplexStatus};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// assert_eq!(simplex.status(), SimplexStatus::InProgress);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.status(), SimplexStatus::Optimal);
+    /// ```
+    pub fn status(&self) -> SimplexStatus {
+        let current = self.current_state();
+        let stuck = PivotRule::Bland
+            .entering_variable(&current.linear_function, &current.tolerances)
+            .is_none();
+        match self.current_phase() {
+            // The artificial variable is in the base once the tableau is feasible, and phase one
+            // is stuck if it can't be brought down to 0
+            Phase::Phase1 if current.is_feasible() && stuck && current.linear_function.constant < -current.tolerances.zero => {
+                SimplexStatus::Infeasible
+            }
+            Phase::Phase1 => SimplexStatus::InProgress,
+            _ if stuck => SimplexStatus::Optimal,
+            _ if current.is_unbounded() => SimplexStatus::Unbounded,
+            _ => SimplexStatus::InProgress,
+        }
+    }
+
+    /// Returns true if [`Simplex::previous_step`] would move back, i.e. unless this is the first step
+    pub fn can_step_backward(&self) -> bool {
+        !self.is_first_step()
+    }
+
+    /// Returns true if there is a step after the current one, either already computed or because
+    /// some variable can still improve the objective, without computing it
+    ///
+    /// A step may still fail when the objective is found to be unbounded by the ratio test
+    pub fn can_step_forward(&self) -> bool {
+        let current = self.current_state();
+        self.index < self.historic.len() - 1
+            || (self.current_phase() == Phase::Phase1 && !current.is_feasible())
+            || PivotRule::Bland
+                .entering_variable(&current.linear_function, &current.tolerances)
+                .is_some()
+    }
+
+    pub fn previous_step(&mut self) {
+        if !self.is_first_step() {
+            self.index -= 1;
+        }
+    }
+
+    /// Goes back to the first step, keeping the computed steps
+    pub fn reset(&mut self) {
+        self.index = 0;
+    }
+
+    /// Returns a reference to the program the algorithm started from
+    pub fn initial_state(&self) -> &LinearProgram {
+        self.state(0)
+    }
+
+    /// Returns a reference to the current state of the algorithm
+    pub fn current_state(&self) -> &LinearProgram {
+        self.state(self.index)
+    }
+
+    /// Returns the state at `step` of the historic, rebuilding it first if it was skipped by the
+    /// dense backend, along with the skipped steps before it, by replaying their pivots
+    fn state(&self, step: usize) -> &LinearProgram {
+        if let Some(state) = self.historic[step].get() {
+            return state;
+        }
+        let built = (0..step)
+            .rev()
+            .find(|i| self.historic[*i].get().is_some())
+            .expect("the first step is always built");
+        for i in built + 1..=step {
+            let mut state = self.historic[i - 1].get().expect("the previous step was just built").clone();
+            let record = &self.log[i - 1];
+            let leaving = record.leaving.as_ref().expect("the dense backend always pivots");
+            let row = state
+                .constraints
+                .iter()
+                .position(|constraint| constraint.left.name_single_variable().as_ref() == Some(leaving))
+                .expect("the leaving variable is basic in the previous step");
+            state.pivot_on(row, &record.entering);
+            let _ = self.historic[i].set(state);
+        }
+        self.historic[step].get().expect("the step was just built")
+    }
+
+    /// Returns every state of the historic, from the oldest one kept
+    pub(crate) fn states(&self) -> impl Iterator<Item = &LinearProgram> {
+        (0..self.historic.len()).map(|step| self.state(step))
+    }
+
+    pub fn current_point(&self) -> Vec<f32> {
+        self.current_state().point()
+    }
+
+    pub fn current_values(&self) -> Vec<(Variable, Coefficient)> {
+        self.current_state().values()
+    }
+
+    /// Returns the value of the objective given by the user, in its sense, at each computed step
+    /// whatever the current step is
+    ///
+    /// During phase one, this is the value of the objective at the vertex of the auxiliary
+    /// program, which needn't satisfy the constraints yet
+    pub fn objective_history(&self) -> Vec<Coefficient> {
+        self.states()
+            .zip(self.phases.iter())
+            .map(|(state, phase)| match (phase, &self.phase_two_objective) {
+                (Phase::Phase1, Some(objective)) => {
+                    let mut objective = objective.clone();
+                    for constraint in state.constraints.iter() {
+                        if let Some(basic) = constraint.left.name_single_variable() {
+                            objective.replace(&basic, &constraint.right);
+                        }
+                    }
+                    state.sense.sign() * objective.constant
+                }
+                _ => state.objective_value(),
+            })
+            .collect()
+    }
+
+    /// Returns a record of every pivot computed so far, whatever the current step is
+    pub fn pivot_log(&self) -> &[PivotRecord] {
+        &self.log
+    }
+
+    /// Compares the computed steps `from` and `to`: for adjacent steps, this is what the pivot
+    /// did, otherwise the net change between them. Steps that weren't computed give `NoSuchStep`
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max x + y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// let diff = simplex.difference(0, 2).unwrap();
+    /// assert_eq!(diff.entered, vec!["x", "y"]);
+    /// assert_eq!(diff.left, vec!["ε0", "ε1"]);
+    /// assert_eq!(diff.objective_delta, 5.0);
+    /// ```
+    pub fn difference(&self, from: usize, to: usize) -> Result<StepDiff, SimplexError> {
+        let state = |step: usize| (step < self.historic.len()).then(|| self.state(step)).ok_or(SimplexError::NoSuchStep(step));
+        let (before, after) = (state(from)?, state(to)?);
+        let (basis_before, basis_after) = (before.basic_variables(), after.basic_variables());
+        let (values_before, values_after) = (before.valuation(), after.valuation());
+        let (objective_before, objective_after) = (before.objective(), after.objective());
+
+        // Lists the variables whose value differs by more than the `zero` tolerance
+        let zero = after.tolerances.zero;
+        let changes = |variables: Vec<&Variable>,
+                       old: &dyn Fn(&Variable) -> Coefficient,
+                       new: &dyn Fn(&Variable) -> Coefficient| {
+            variables
+                .into_iter()
+                .unique()
+                .sorted()
+                .map(|var| (var.clone(), old(var), new(var)))
+                .filter(|(_, old, new)| (old - new).abs() > zero)
+                .collect()
+        };
+        let value =
+            |valuation: &HashMap<Variable, Coefficient>, var: &Variable| valuation.get(var).copied().unwrap_or(0.0);
+        Ok(StepDiff {
+            entered: basis_after.iter().filter(|var| !basis_before.contains(var)).cloned().collect(),
+            left: basis_before.iter().filter(|var| !basis_after.contains(var)).cloned().collect(),
+            objective_delta: after.objective_value() - before.objective_value(),
+            value_changes: changes(
+                values_before.keys().chain(values_after.keys()).collect(),
+                &|var| value(&values_before, var),
+                &|var| value(&values_after, var),
+            ),
+            objective_changes: changes(
+                objective_before.var_iter().chain(objective_after.var_iter()).collect(),
+                &|var| objective_before[var],
+                &|var| objective_after[var],
+            ),
+        })
+    }
+
+    /// Describes in words the pivot going from the computed step `index` to the next one: why the
+    /// entering variable was chosen, and which row the ratio test made leave. When `index` is
+    /// the last step and an optimum, tells that the optimum is reached instead
+    ///
+    /// Gives `NoSuchStep` if the step after `index` wasn't computed
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "max 3x + 2y\n x + y <= 4\n x <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(
+    ///     simplex.explain_step(0).unwrap(),
+    ///     "x enters the basis because its reduced cost 3 is the largest positive one; ε1 leaves \
+    ///      the basis as constraint #1 gives the minimum ratio 3 in the ratio test. The objective \
+    ///      becomes 9."
+    /// );
+    /// assert_eq!(
+    ///     simplex.explain_step(2).unwrap(),
+    ///     "The optimum is reached: no variable has a positive reduced cost anymore, and the \
+    ///      objective is 11."
+    /// );
+    /// ```
+    pub fn explain_step(&self, index: usize) -> Result<String, SimplexError> {
+        let before = (index < self.historic.len()).then(|| self.state(index)).ok_or(SimplexError::NoSuchStep(index))?;
+        let Some(record) = self.log.get(index) else {
+            let is_optimal = self.phases[index] != Phase::Phase1
+                && PivotRule::Bland
+                    .entering_variable(&before.linear_function, &before.tolerances)
+                    .is_none();
+            if !is_optimal {
+                return Err(SimplexError::NoSuchStep(index + 1));
+            }
+            return Ok(format!(
+                "The optimum is reached: no variable has a positive reduced cost anymore, and the objective is {}.",
+                before.objective_value()
+            ));
+        };
+
+        let entering = &record.entering;
+        let is_artificial = *entering == artificial_variable();
+        let mut explanation = if is_artificial {
+            format!("The artificial variable {entering} enters the basis to make it feasible")
+        } else {
+            let cost = before.linear_function.coefficient_of(entering);
+            let is_largest = before.linear_function.var_iter().all(|var| before.linear_function[var] <= cost);
+            let rank = if is_largest { "the largest positive one" } else { "positive" };
+            format!("{entering} enters the basis because its reduced cost {cost} is {rank}")
+        };
+
+        let row = record.leaving.as_ref().and_then(|leaving| {
+            before
+                .constraints
+                .iter()
+                .position(|constraint| constraint.left.name_single_variable().as_ref() == Some(leaving))
+        });
+        match (&record.leaving, row) {
+            (Some(leaving), Some(row)) => {
+                let constraint = &before.constraints[row];
+                let name = match &constraint.label {
+                    Some(label) => format!("constraint {label}"),
+                    None => format!("constraint #{row}"),
+                };
+                if is_artificial {
+                    explanation += &format!(
+                        "; {leaving} leaves the basis as {name} has the most negative constant {}",
+                        constraint.right.constant
+                    );
+                } else {
+                    match constraint.ratio(entering, before.tolerances.zero) {
+                        Some(ratio) => {
+                            explanation += &format!(
+                                "; {leaving} leaves the basis as {name} gives the minimum ratio {ratio} in the ratio test"
+                            )
+                        }
+                        None => explanation += &format!("; {leaving} leaves the basis"),
+                    }
+                }
+            }
+            (Some(leaving), None) => explanation += &format!("; {leaving} leaves the basis"),
+            (None, _) => explanation += ", and goes to its other bound without any pivot",
+        }
+        Ok(format!("{explanation}. The objective becomes {}.", record.objective_value))
+    }
+
+    /// Returns the indices of the computed steps that were reached through a degenerate pivot,
+    /// i.e. a pivot that did not move the current vertex. Those can lead to cycling when Bland's
+    /// rule is not used
+    pub fn degenerate_step_indices(&self) -> Vec<usize> {
+        self.degenerate
+            .iter()
+            .enumerate()
+            .filter_map(|(i, degenerate)| if *degenerate { Some(i) } else { None })
+            .collect()
+    }
+}
+
+impl From<LinearProgram> for Simplex {
+    /// Starts the simplex from the given program. If its origin is not feasible, the simplex
+    /// starts with phase one, looking for a feasible vertex by lowering an artificial variable
+    /// added to every constraint
+    fn from(value: LinearProgram) -> Self {
+        let (program, phase, phase_two_objective) = if value.is_feasible() {
+            (value, Phase::Single, None)
+        } else {
+            let mut program = value;
+            let objective = program.linear_function.clone();
+            let artificial = LinearFunction::single_variable(artificial_variable());
+            for i in 0..program.constraints.len() {
+                program.constraints[i].right += artificial.clone();
+            }
+            program.linear_function = -artificial;
+            (program, Phase::Phase1, Some(objective))
+        };
+
+        Simplex {
+            index: 0,
+            historic: vec![OnceCell::from(program)],
+            degenerate: vec![false],
+            log: vec![],
+            phases: vec![phase],
+            phase_two_objective,
+            warnings: vec![],
+            history_limit: None,
+            backend: TableauBackend::Map,
+        }
+    }
+}
+
+impl std::fmt::Display for SimplexStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimplexStatus::InProgress => write!(f, "in progress"),
+            SimplexStatus::Optimal => write!(f, "optimal"),
+            SimplexStatus::Unbounded => write!(f, "unbounded"),
+            SimplexStatus::Infeasible => write!(f, "infeasible"),
+        }
+    }
+}
+
+impl std::fmt::Display for Simplex {
+    /// Shows the index of the current step among the computed ones, its status and its tableau
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let program = "min x - y\n y <= 3".parse::<LinearProgram>().unwrap();
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.to_string(), "Step 1/1 [optimal]\nmin -3.0 + x + ε0\ny = 3.0 - ε0\n");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Step {}/{} [{}]", self.index, self.historic.len() - 1, self.status())?;
+        write!(f, "{}", self.current_state())
+    }
+}
+
+impl std::fmt::Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Phase1 => write!(f, "phase 1"),
+            Phase::Phase2 => write!(f, "phase 2"),
+            Phase::Single => write!(f, "single phase"),
+        }
+    }
+}
+
+impl std::fmt::Display for ProblemSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} decision variables and {} constraints ({} <, {} >, {} =) using {} gap variables; the origin is {}feasible",
+            self.decision_variables,
+            self.constraints,
+            self.less_than,
+            self.greater_than,
+            self.equal,
+            self.gap_variables,
+            if self.origin_is_feasible { "" } else { "not " },
+        )
+    }
+}
 
 impl std::fmt::Display for LinearProgram {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "max {}", self.linear_function)?;
-        write!(f, "{}", self.constraints)
+        write!(f, "{}", LinearProgramFormatter::default().format(self))
+    }
+}
+
+impl std::str::FromStr for LinearProgram {
+    type Err = ParseProblemError;
+
+    /// Parses a whole linear program: a first `max` or `min` line giving the objective, followed
+    /// by one constraint per line. Blank lines and `#` comments are ignored
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let program = "
+    ///     max x + 6y + 13z # the default problem
+    ///     x <= 200
+    ///     y <= 300 # a comment can end a line
+    ///     x + y + z <= 400
+    ///     y + 3z <= 600
+    /// ".parse::<LinearProgram>().unwrap();
+    /// assert_eq!(program.constraints.original_constraints().len(), 4);
+    ///
+    /// let error = "max x + y\n x + y 4".parse::<LinearProgram>().unwrap_err();
+    /// assert_eq!(error.line, 2);
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let (objective_line, objective) = lines.next().ok_or(ParseProblemError {
+            line: 1,
+            message: "missing objective".to_string(),
+        })?;
+        let error = |line: usize, message: &str| ParseProblemError {
+            line,
+            message: message.to_string(),
+        };
+        let (sense, function) = match objective.split_once(char::is_whitespace) {
+            Some((keyword, function)) => match keyword.to_lowercase().as_str() {
+                "max" => (ObjectiveSense::Max, function),
+                "min" => (ObjectiveSense::Min, function),
+                _ => return Err(error(objective_line, "expected `max` or `min` before the objective")),
+            },
+            None => return Err(error(objective_line, "expected `max` or `min` followed by the objective")),
+        };
+        let function = function
+            .parse::<LinearFunction>()
+            .map_err(|_| error(objective_line, "invalid objective"))?;
+
+        let mut constraints = Constraints::new();
+        for (line, constraint) in lines {
+            constraints
+                .add_line(constraint, true)
+                .map_err(|_| error(line, &format!("invalid constraint `{constraint}`")))?;
+        }
+
+        Ok(constraints.to_program(&function, sense))
+    }
+}
+
+impl TryFrom<&str> for LinearProgram {
+    type Error = ParseProblemError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::linear_function::var;
 
     #[test]
     fn test_non_gap_variables() {
@@ -158,10 +2295,11 @@ mod tests {
         let lp = LinearProgram {
             linear_function: LinearFunction::from_str("x + 2y").unwrap(),
             constraints: Constraints::compile("x + y <= 2\n x + 2y <= 3").unwrap(),
+            ..Default::default()
         };
         assert_eq!(
             lp.non_gap_variables(),
-            HashSet::from(["x".to_string(), "y".to_string()])
+            vec![var("x"), var("y")]
         );
     }
 
@@ -171,6 +2309,7 @@ mod tests {
         let lp = LinearProgram {
             linear_function: LinearFunction::from_str("x + 2y").unwrap(),
             constraints: Constraints::compile("x + y <= 2\n x + 2y <= 3").unwrap(),
+            ..Default::default()
         };
         assert_eq!(lp.point(), vec![0.0, 0.0]);
     }
@@ -182,9 +2321,1224 @@ mod tests {
         let lp = LinearProgram {
             linear_function: LinearFunction::from_str("x + 2y").unwrap(),
             constraints: Constraints::compile("x <= 200\n 300 - x + 2y >= 0").unwrap(),
+            ..Default::default()
         };
         let mut simplex = Simplex::from(lp);
-        simplex.next_step(true).unwrap();
+        simplex.next_step(PivotRule::Bland).unwrap();
         assert_eq!(simplex.current_point(), vec![200.0, 0.0]);
+        simplex.assert_monotonic_objective();
+    }
+
+    #[test]
+    fn test_degenerate_steps() {
+        use std::str::FromStr;
+        let lp = LinearProgram {
+            linear_function: LinearFunction::from_str("x + y").unwrap(),
+            constraints: Constraints::compile("x - y <= 0\n x <= 1").unwrap(),
+            ..Default::default()
+        };
+        assert!(lp.is_degenerate());
+
+        let mut simplex = Simplex::from(lp);
+        while simplex.next_step(PivotRule::Bland).is_ok() {}
+        assert!(!simplex.degenerate_step_indices().is_empty());
+        assert_eq!(simplex.degenerate_step_indices()[0], 1);
+        assert_eq!(simplex.current_point(), vec![1.0, 1.0]);
+
+        // Values within the zero tolerance count as 0
+        let mut lp = LinearProgram {
+            linear_function: LinearFunction::from_str("x + y").unwrap(),
+            constraints: Constraints::compile("x - y <= 0\n x <= 1").unwrap(),
+            ..Default::default()
+        };
+        lp.constraints[0].right.constant = 1e-12;
+        assert!(lp.is_degenerate());
+        let mut simplex = Simplex::from(lp);
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.degenerate_step_indices(), vec![1]);
+    }
+
+    #[test]
+    fn test_can_step() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        assert!(!simplex.can_step_backward());
+        assert!(simplex.can_step_forward());
+
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert!(simplex.can_step_backward());
+        assert!(simplex.can_step_forward());
+        // Asking doesn't compute the next step
+        assert_eq!(simplex.historic.len(), 2);
+
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert!(simplex.can_step_backward());
+        assert!(!simplex.can_step_forward());
+        assert_eq!(simplex.next_step(PivotRule::Bland), Err(SimplexError::AlreadyOptimal));
+
+        // Going back, the computed steps are still ahead
+        simplex.reset();
+        assert!(simplex.can_step_forward());
+
+        // Phase one starts from an infeasible base, which can't be optimal
+        let simplex = Constraints::compile("x + y >= 2\n x <= 3").unwrap().minimize(&"x + y".parse().unwrap()).unwrap();
+        assert!(simplex.can_step_forward());
+    }
+
+    #[test]
+    fn test_into_steps() {
+        let simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        let mut solved = simplex.clone();
+        solved.solve(PivotRule::Dantzig).unwrap();
+
+        let steps = simplex.into_steps(PivotRule::Dantzig).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(steps.len(), solved.pivot_log().len());
+        let last = steps.last().unwrap();
+        assert_eq!(last.values(), solved.current_values());
+        assert_eq!(last.objective_value(), solved.current_state().objective_value());
+
+        // The error ends the iteration
+        let simplex = Constraints::compile("x - y <= 1").unwrap().maximize(&"x".parse().unwrap()).unwrap();
+        let mut steps = simplex.into_steps(PivotRule::Bland);
+        assert!(steps.next().unwrap().is_ok());
+        assert_eq!(steps.next().unwrap().err(), Some(SimplexError::Unbounded));
+        assert!(steps.next().is_none());
+    }
+
+    #[test]
+    fn test_replay() {
+        // Klee-Minty's cube, on which Dantzig's rule visits every vertex
+        let program = "max 4x + 2y + z\n x <= 5\n 4x + y <= 25\n 8x + 4y + z <= 125".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        let replayed = Simplex::replay(program.clone(), simplex.pivot_log()).unwrap();
+        let states = |simplex: &Simplex| simplex.states().map(|state| state.to_string()).collect::<Vec<_>>();
+        assert_eq!(states(&replayed), states(&simplex));
+        assert_eq!(replayed.pivot_log(), simplex.pivot_log());
+        assert_eq!(replayed.index, 7);
+
+        // A walkthrough off the path of any rule, stopped before the optimum
+        let log = &simplex.pivot_log()[..0];
+        assert_eq!(Simplex::replay(program.clone(), log).unwrap().historic.len(), 1);
+        let mut walkthrough = Simplex::from(program.clone());
+        walkthrough.step_with_entering("z").unwrap();
+        let replayed = Simplex::replay(program.clone(), walkthrough.pivot_log()).unwrap();
+        assert_eq!(states(&replayed), states(&walkthrough));
+
+        // Through phase one, with the leaving variable chosen among tied ones
+        let program = "max x + y\n x + y >= 2\n x <= 2\n y <= 2".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Lexicographic).unwrap();
+        let replayed = Simplex::replay(program, simplex.pivot_log()).unwrap();
+        assert_eq!(states(&replayed), states(&simplex));
+        assert_eq!(replayed.phases, simplex.phases);
+
+        // Invalid pivots
+        let program = "max x + 2y\n x <= 2\n y <= 3".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        let mut log = simplex.pivot_log().to_vec();
+        log[1].leaving = Some(var("ε1"));
+        assert_eq!(Simplex::replay(program.clone(), &log).unwrap_err(), SimplexError::InvalidPivot(1));
+        log.swap(0, 1);
+        log.push(log[0].clone());
+        assert_eq!(Simplex::replay(program, &log[1..]).unwrap_err(), SimplexError::InvalidPivot(1));
+    }
+
+    #[test]
+    fn test_step_with_entering() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        assert_eq!(simplex.step_with_entering("w"), Err(SimplexError::NotImproving(var("w"))));
+        assert_eq!(simplex.step_with_entering("ε0"), Err(SimplexError::NotImproving(var("ε0"))));
+
+        // Dantzig's rule would have chosen z
+        simplex.step_with_entering("x").unwrap();
+        assert_eq!(simplex.pivot_log()[0].entering, "x");
+        assert_eq!(simplex.pivot_log()[0].leaving, Some(var("ε0")));
+        assert_eq!(simplex.current_state().objective_value(), 200.0);
+        // The history goes on from there as with `next_step`
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 3100.0);
+        assert!(simplex.step_with_entering("x").is_err());
+
+        // Going back and choosing another variable drops the steps that followed
+        simplex.reset();
+        simplex.step_with_entering("y").unwrap();
+        assert_eq!(simplex.historic.len(), 2);
+        assert_eq!(simplex.pivot_log().len(), 1);
+        assert_eq!(simplex.pivot_log()[0].entering, "y");
+        assert_eq!(simplex.index, 1);
+
+        // A failing pivot keeps the steps that followed
+        let mut simplex = Simplex::from("max x + y\n x <= 2".parse::<LinearProgram>().unwrap());
+        simplex.step_with_entering("x").unwrap();
+        simplex.reset();
+        assert_eq!(simplex.step_with_entering("y"), Err(SimplexError::Unbounded));
+        assert_eq!((simplex.historic.len(), simplex.pivot_log().len(), simplex.index), (2, 1, 0));
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 2.0);
+    }
+
+    #[test]
+    fn test_value_of() {
+        let mut simplex = Simplex::from(
+            "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+                .parse::<LinearProgram>()
+                .unwrap(),
+        );
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        let optimum = simplex.current_state();
+        assert_eq!(optimum.value_of(&"x + y".parse().unwrap()), 300.0);
+        assert_eq!(optimum.value_of(&"x + 6y + 13z".parse().unwrap()), optimum.objective_value());
+        assert_eq!(optimum.value_of(&"w + 2".parse().unwrap()), 2.0);
+    }
+
+    #[test]
+    fn test_slack_for_constraint() {
+        let mut program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let slacks: Vec<_> = (0..4).map(|i| program.slack_for_constraint(i).unwrap()).collect();
+        assert_eq!(slacks, vec!["ε0", "ε1", "ε2", "ε3"]);
+
+        // The slack of the second constraint leaves the base, and keeps its name
+        program.pivot(var("y")).unwrap();
+        assert!(program.basic_variables().contains(&var("y")));
+        assert!(!program.basic_variables().contains(&slacks[1]));
+        assert_eq!((0..4).map(|i| program.slack_for_constraint(i).unwrap()).collect::<Vec<_>>(), slacks);
+        assert_eq!(program.valuation()[&slacks[1]], 0.0);
+        assert_eq!(program.valuation()[&slacks[3]], 300.0);
+    }
+
+    #[test]
+    fn test_history_limit() {
+        // Dantzig's rule visits every vertex of this Klee-Minty cube
+        let program = "max 4x + 2y + z\n x <= 5\n 4x + y <= 25\n 8x + 4y + z <= 125"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut unlimited = Simplex::from(program.clone());
+        unlimited.solve(PivotRule::Dantzig).unwrap();
+        assert_eq!(unlimited.historic.len(), 8);
+
+        let mut simplex = Simplex::from(program.clone()).with_history_limit(3);
+        let mut indices = vec![];
+        simplex
+            .solve_with(PivotRule::Dantzig, |_, index| indices.push(index))
+            .unwrap();
+        // The current step is always the last one kept
+        assert!(indices.iter().all(|index| *index < 3));
+        assert_eq!(simplex.historic.len(), 3);
+        assert_eq!(simplex.pivot_log().len(), 2);
+        assert_eq!(simplex.current_values(), unlimited.current_values());
+        assert_eq!(simplex.objective_history(), unlimited.objective_history()[5..]);
+
+        // Going back stops at the oldest step kept
+        for _ in 0..5 {
+            simplex.previous_step();
+        }
+        assert!(!simplex.can_step_backward());
+        assert_eq!(simplex.current_state().objective_value(), unlimited.objective_history()[5]);
+        simplex.next_step(PivotRule::Dantzig).unwrap();
+        assert_eq!(simplex.historic.len(), 3);
+
+        // The warnings about the dropped steps go away, the others follow the numbering
+        let tolerances = Tolerances { pivot: 2.0, ..Default::default() };
+        let mut unlimited = Simplex::from(program.clone()).with_tolerances(tolerances);
+        unlimited.solve(PivotRule::Dantzig).unwrap();
+        let mut simplex = Simplex::from(program).with_tolerances(tolerances).with_history_limit(3);
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        let kept = unlimited
+            .warnings()
+            .iter()
+            .filter(|NumericalWarning::SmallPivot { step, .. }| *step >= 5)
+            .map(|NumericalWarning::SmallPivot { step, entering, pivot_element }| NumericalWarning::SmallPivot {
+                step: step - 5,
+                entering: entering.clone(),
+                pivot_element: *pivot_element,
+            })
+            .collect::<Vec<_>>();
+        assert!(!kept.is_empty() && kept.len() < unlimited.warnings().len());
+        assert_eq!(simplex.warnings(), kept);
+    }
+
+    #[test]
+    fn test_solve_with_budget() {
+        // Dantzig's rule takes 7 steps on this Klee-Minty cube
+        let program = "max 4x + 2y + z\n x <= 5\n 4x + y <= 25\n 8x + 4y + z <= 125"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        assert_eq!(simplex.solve_with_budget(PivotRule::Dantzig, 3, None), Err(SimplexError::IterationLimit));
+        assert_eq!(simplex.pivot_log().len(), 3);
+        assert_eq!(simplex.status(), SimplexStatus::InProgress);
+        // The progress is kept
+        assert_eq!(simplex.solve_with_budget(PivotRule::Dantzig, 4, None), Ok(()));
+        assert_eq!(simplex.current_state().objective_value(), 125.0);
+
+        // Exactly enough steps
+        let mut simplex = Simplex::from(program.clone());
+        assert_eq!(simplex.solve_with_budget(PivotRule::Dantzig, 7, None), Ok(()));
+
+        let mut simplex = Simplex::from(program);
+        let timeout = Some(std::time::Duration::ZERO);
+        assert_eq!(simplex.solve_with_budget(PivotRule::Dantzig, 100, timeout), Err(SimplexError::TimedOut));
+        assert_eq!(simplex.pivot_log().len(), 0);
+    }
+
+    #[test]
+    fn test_display_simplex() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        let shown = simplex.to_string();
+        assert!(shown.starts_with("Step 0/0 [in progress]\nmax x + 6.0y + 13.0z\n"), "{shown}");
+
+        simplex.solve(PivotRule::Bland).unwrap();
+        simplex.previous_step();
+        let last = simplex.historic.len() - 1;
+        assert!(simplex.to_string().starts_with(&format!("Step {}/{last} [in progress]", last - 1)));
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert!(simplex.to_string().starts_with(&format!("Step {last}/{last} [optimal]")));
+        assert!(simplex.to_string().ends_with(&simplex.current_state().to_string()));
+    }
+
+    #[test]
+    fn test_status() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        assert_eq!(simplex.status(), SimplexStatus::InProgress);
+        assert!(!simplex.is_optimal());
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.status(), SimplexStatus::Optimal);
+        assert!(simplex.is_optimal());
+        simplex.reset();
+        assert_eq!(simplex.status(), SimplexStatus::InProgress);
+
+        // y grows forever once x is in the base
+        let mut simplex = Constraints::compile("x - y <= 1").unwrap().maximize(&"x".parse().unwrap()).unwrap();
+        assert_eq!(simplex.status(), SimplexStatus::InProgress);
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.status(), SimplexStatus::Unbounded);
+
+        let mut simplex = Constraints::compile("x <= 1\n x >= 2").unwrap().maximize(&"x".parse().unwrap()).unwrap();
+        assert_eq!(simplex.status(), SimplexStatus::InProgress);
+        assert_eq!(simplex.solve(PivotRule::Bland), Err(SimplexError::Infeasible));
+        assert_eq!(simplex.status(), SimplexStatus::Infeasible);
+    }
+
+    #[test]
+    fn test_minimize_objective_value() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x <= 4\n y <= 3").unwrap();
+        let mut simplex = constraints
+            .minimize(&LinearFunction::from_str("x - y").unwrap())
+            .unwrap();
+        while simplex.next_step(PivotRule::Bland).is_ok() {}
+
+        assert_eq!(simplex.current_state().sense, ObjectiveSense::Min);
+        assert_eq!(simplex.current_state().objective_value(), -3.0);
+        assert_eq!(simplex.current_point(), vec![0.0, 3.0]);
+    }
+
+    #[test]
+    fn test_summary() {
+        use std::str::FromStr;
+        let lp = LinearProgram {
+            linear_function: LinearFunction::from_str("x + 6y + 13z").unwrap(),
+            constraints: Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap(),
+            ..Default::default()
+        };
+        let summary = lp.summary();
+
+        assert_eq!(summary.decision_variables, 3);
+        assert_eq!(summary.constraints, 4);
+        assert_eq!(summary.less_than, 4);
+        assert_eq!(summary.gap_variables, 4);
+        assert!(summary.origin_is_feasible);
+        assert_eq!(
+            summary.to_string(),
+            "3 decision variables and 4 constraints (4 <, 0 >, 0 =) using 4 gap variables; the origin is feasible"
+        );
+
+        let lp = LinearProgram {
+            constraints: Constraints::compile("x + y >= 2").unwrap(),
+            ..lp
+        };
+        assert!(!lp.summary().origin_is_feasible);
+    }
+
+    #[test]
+    fn test_slack_values() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600\n x + z >= 50"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut simplex = Simplex::from(program);
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        // The optimum x = 0, y = 300, z = 100 is on the constraints 1 to 3
+        assert_eq!(
+            simplex.current_state().slack_values(),
+            vec![(0, 200.0), (1, 0.0), (2, 0.0), (3, 0.0), (4, 50.0)]
+        );
+    }
+
+    #[test]
+    fn test_free_variable_values() {
+        let program = "max 2x + y\n x + y <= 4\n x <= -1\n x free".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program);
+        simplex.solve(PivotRule::Bland).unwrap();
+        let state = simplex.current_state();
+        assert_eq!(state.objective_value(), 3.0);
+
+        // The parts standing for x in the tableau never show up
+        let valuation = state.valuation();
+        assert_eq!((valuation[&var("x")], valuation[&var("y")]), (-1.0, 5.0));
+        assert!(!valuation.keys().any(|var| var.contains(['⁺', '⁻'])));
+        assert_eq!(state.values(), vec![(var("x"), -1.0), (var("y"), 5.0)]);
+        // Relaxing either constraint lets y grow by 1, the second one at the cost of 1 for x
+        assert_eq!(state.shadow_prices(), vec![1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_labeled_shadow_prices() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("budget: x + y <= 4\n capacity: x <= 3\n 2y <= 10").unwrap();
+        let mut simplex = constraints
+            .maximize(&LinearFunction::from_str("x + y").unwrap())
+            .unwrap();
+        while simplex.next_step(PivotRule::Bland).is_ok() {}
+
+        assert_eq!(simplex.current_state().objective_value(), 4.0);
+        assert_eq!(simplex.current_state().shadow_prices(), vec![1.0, 0.0, 0.0]);
+        assert_eq!(
+            simplex.current_state().shadow_prices_report(),
+            "budget: 1\ncapacity: 0\n#2: 0\n"
+        );
+
+        // An equation has a single shadow price, although it is split into two rows
+        let mut simplex = Simplex::from("max x + 2y\n demand: x + y = 4\n y <= 3".parse::<LinearProgram>().unwrap());
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().shadow_prices(), vec![1.0, 1.0]);
+        assert_eq!(simplex.current_state().shadow_prices_report(), "demand: 1\n#1: 1\n");
+        assert!(Constraint::from_str("a: b: x <= 1").is_err());
+    }
+
+    #[test]
+    fn test_bounded_variables() {
+        use std::str::FromStr;
+        let solve = |constraints: &str, objective: &str| {
+            let mut simplex = Constraints::compile(constraints)
+                .unwrap()
+                .maximize(&LinearFunction::from_str(objective).unwrap())
+                .unwrap();
+            while simplex.next_step(PivotRule::Bland).is_ok() {}
+            simplex
+        };
+
+        for (constraints, bounds, expanded, objective) in [
+            ("x + y <= 6", "0 <= x <= 4\n 0 <= y <= 5", "x <= 4\n y <= 5", "3x + 2y"),
+            (
+                "x + y + z <= 10\n y - x <= 2\n z - y <= 1",
+                "0 <= x <= 4\n 0 <= y <= 5\n 0 <= z <= 3",
+                "x <= 4\n y <= 5\n z <= 3",
+                "x + 2y + 3z",
+            ),
+        ] {
+            let bounded = solve(&format!("{constraints}\n {bounds}"), objective);
+            let expanded = solve(&format!("{constraints}\n {expanded}"), objective);
+
+            let (bounded_state, expanded_state) = (bounded.current_state(), expanded.current_state());
+            assert!(bounded_state.constraints.gap_variables_count() < expanded_state.constraints.gap_variables_count());
+            assert_eq!(bounded_state.objective_value(), expanded_state.objective_value());
+            assert_eq!(bounded.current_point(), expanded.current_point());
+            assert!(bounded.historic.len() <= expanded.historic.len());
+        }
+
+        // Non zero lower bounds, both variables ending at their upper bound
+        let simplex = solve("x + y <= 10\n 2 <= x <= 3\n 1 <= y <= 4", "x + y");
+        assert_eq!(simplex.initial_state().point(), vec![2.0, 1.0]);
+        assert_eq!(simplex.current_point(), vec![3.0, 4.0]);
+        assert_eq!(simplex.current_state().objective_value(), 7.0);
+        assert_eq!(simplex.current_state().at_upper_bound.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_program() {
+        use crate::constraint::Constraint;
+        use std::str::FromStr;
+        let program = LinearProgram::try_from(
+            "# Minimize the cost\n\nmin 2x + 3y\n x + y >= 0\n\n x <= 4 # at most 4\n y <= 3",
+        )
+        .unwrap();
+        assert_eq!(program.sense, ObjectiveSense::Min);
+        assert_eq!(program.linear_function, -LinearFunction::from_str("2x + 3y").unwrap());
+        assert_eq!(program.constraints.original_constraints().len(), 3);
+        assert_eq!(
+            program.constraints.original_constraints()[1],
+            Constraint::from_str("x <= 4").unwrap()
+        );
+
+        let error = LinearProgram::from_str("max x + y\n x <= 4\n x + y 3").unwrap_err();
+        assert_eq!(error.line, 3);
+        assert_eq!(error.to_string(), "line 3: invalid constraint `x + y 3`");
+
+        let error = LinearProgram::from_str("\n x + y\n x <= 4").unwrap_err();
+        assert_eq!(error.line, 2);
+    }
+
+    #[test]
+    fn test_constant_objective() {
+        use std::str::FromStr;
+        assert_eq!(LinearFunction::constant(5.0).first_positive_coefficient(true, 0.0), None);
+
+        let program = LinearProgram::from_str("max 5\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let mut simplex = Simplex::from(program);
+        assert!(!simplex.can_step_forward());
+        assert_eq!(simplex.next_step(PivotRule::Dantzig), Err(SimplexError::AlreadyOptimal));
+        assert_eq!(simplex.current_state().objective_value(), 5.0);
+        assert_eq!(simplex.current_point(), vec![0.0, 0.0, 0.0]);
+
+        let program = LinearProgram::from_str("min 5\n x + y >= 2\n x <= 3").unwrap();
+        let mut simplex = Simplex::from(program);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 5.0);
+        assert!(simplex.current_state().constraints.original_constraints()[0]
+            .is_satisfied_at(&simplex.current_state().valuation()));
+    }
+
+    #[test]
+    fn test_both_senses() {
+        let region = "x + y <= 4\n x <= 3\n y <= 3\n x + y >= 1";
+        let solve = |sense: &str| {
+            let program = format!("{sense} x - 2y\n{region}").parse::<LinearProgram>().unwrap();
+            assert_eq!(program.objective(), "x - 2y".parse().unwrap());
+            let mut simplex = Simplex::from(program);
+            simplex.solve(PivotRule::Bland).unwrap();
+            (simplex.current_state().objective_value(), simplex.current_values())
+        };
+
+        let (max, values) = solve("max");
+        assert_eq!(max, 3.0);
+        assert_eq!(values, vec![(var("x"), 3.0), (var("y"), 0.0)]);
+        let (min, values) = solve("min");
+        assert_eq!(min, -6.0);
+        assert_eq!(values, vec![(var("x"), 0.0), (var("y"), 3.0)]);
+
+        // Both senses give the same optimum through the constraints
+        let constraints = Constraints::compile(region).unwrap();
+        let objective: LinearFunction = "x - 2y".parse().unwrap();
+        assert_eq!(constraints.max_value(&objective), Ok(3.0));
+        assert_eq!(constraints.min_value(&objective), Ok(-6.0));
+    }
+
+    #[test]
+    fn test_ratio_test() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let ratios = program.ratio_test("z");
+        assert_eq!(ratios, vec![(0, None), (1, None), (2, Some(400.0)), (3, Some(200.0))]);
+
+        // The leaving row has the smallest ratio
+        let zero = program.tolerances;
+        assert_eq!(program.constraints.most_restrictive(&var("z"), &zero), Some(3));
+        let mut pivoted = program.clone();
+        assert_eq!(pivoted.pivot(var("z")), Ok(Some(3)));
+        let expected = [None, Some(300.0), Some(300.0), Some(600.0)];
+        for ((_, ratio), expected) in pivoted.ratio_test("y").into_iter().zip(expected) {
+            assert_eq!(ratio.is_some(), expected.is_some());
+            assert!(ratio.zip(expected).is_none_or(|(ratio, expected)| (ratio - expected).abs() < 1e-3));
+        }
+    }
+
+    #[test]
+    fn test_optimal_basis() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        assert_eq!(simplex.optimal_basis(), Err(SimplexError::NotOptimal));
+        simplex.solve(PivotRule::Bland).unwrap();
+        // The optimal vertex is degenerate, x being basic at 0
+        assert_eq!(simplex.optimal_basis().unwrap(), vec!["x", "y", "z", "ε0"]);
+        assert_eq!(simplex.nonbasic_at_optimum().unwrap(), vec!["ε1", "ε2", "ε3"]);
+        assert_eq!(simplex.current_state().valuation()["ε0"], 200.0);
+
+        simplex.previous_step();
+        assert_eq!(simplex.nonbasic_at_optimum(), Err(SimplexError::NotOptimal));
+    }
+
+    #[test]
+    fn test_lexicographic_rule() {
+        use std::str::FromStr;
+        // Beale's example, on which Dantzig's rule cycles when ties are broken badly
+        let constraints = Constraints::compile(
+            "0.25a - 60b - 0.04c + 9d <= 0\n 0.5a - 90b - 0.02c + 3d <= 0\n c <= 1",
+        )
+        .unwrap();
+        let objective = LinearFunction::from_str("0.75a - 150b + 0.02c - 6d").unwrap();
+
+        for rule in [PivotRule::Lexicographic, PivotRule::Bland] {
+            let mut simplex = constraints.maximize(&objective).unwrap();
+            for _ in 0..50 {
+                if simplex.next_step(rule).is_err() {
+                    break;
+                }
+            }
+            assert!(matches!(simplex.next_step(rule), Err(SimplexError::AlreadyOptimal)));
+            assert!((simplex.current_state().objective_value() - 0.05).abs() < 1e-5);
+            assert!(simplex.current_state().is_valid());
+        }
+
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        simplex.solve(PivotRule::Lexicographic).unwrap();
+        assert!((simplex.current_state().objective_value() - 0.05).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_steepest_edge() {
+        let program = crate::generators::random_feasible_lp(20, 10, 17);
+        let mut dantzig = Simplex::from(program.clone());
+        dantzig.solve(PivotRule::Dantzig).unwrap();
+        let mut steepest = Simplex::from(program);
+        steepest.solve(PivotRule::SteepestEdge).unwrap();
+        steepest.assert_monotonic_objective();
+
+        assert!(steepest.is_optimal());
+        let (a, b) = (dantzig.current_state().objective_value(), steepest.current_state().objective_value());
+        assert!((a - b).abs() <= 1e-5 * a, "{a} != {b}");
+        assert!(steepest.pivot_log().len() <= dantzig.pivot_log().len());
+    }
+
+    #[test]
+    fn test_incremental_objective() {
+        let program = crate::generators::random_feasible_lp(8, 6, 3);
+        let initial = program.linear_function.clone();
+        let mut simplex = Simplex::from(program);
+        while simplex.next_step(PivotRule::Dantzig).is_ok() {
+            // Substitutes each basic variable of the initial objective by its row, from scratch
+            let state = simplex.current_state();
+            let from_scratch = state.constraints.iter().fold(initial.clone(), |acc, row| {
+                let basic = row.left.name_single_variable().unwrap();
+                let coeff = acc[&basic];
+                acc - LinearFunction::term(coeff, basic) + row.right.clone() * coeff
+            });
+            let epsilon = 1e-4 * from_scratch.constant.abs().max(1.0);
+            assert!(state.linear_function.approx_eq(&from_scratch, epsilon), "step {}", simplex.index);
+        }
+        assert!(simplex.pivot_log().len() > 2);
+    }
+
+    #[test]
+    fn test_pivot_log() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let mut simplex = constraints
+            .maximize(&LinearFunction::from_str("x + 6y + 13z").unwrap())
+            .unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        let log = simplex.pivot_log();
+        assert_eq!(log.len(), simplex.historic.len() - 1);
+        assert_eq!(log[0].entering, "x");
+        assert_eq!(log[0].leaving, Some(var("ε0")));
+        assert_eq!(log[0].pivot_element, Some(-1.0));
+        assert_eq!(log[0].objective_value, 200.0);
+        assert_eq!(log.last().unwrap().objective_value, 3100.0);
+
+        // Going back and forth doesn't add any record
+        let before = log.to_vec();
+        simplex.previous_step();
+        simplex.previous_step();
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.pivot_log(), before);
+    }
+
+    #[test]
+    fn test_deterministic_dantzig() {
+        // Every variable ties for Dantzig's rule at the first step
+        let program = "max x + y + z + w\n x + y <= 4\n z + w <= 6\n x + z <= 5\n y + w <= 5"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let solve = || {
+            let mut simplex = Simplex::from(program.clone());
+            simplex.solve(PivotRule::Dantzig).unwrap();
+            simplex.pivot_log().to_vec()
+        };
+        let log = solve();
+        assert_eq!(log[0].entering, "w");
+        for _ in 0..10 {
+            assert_eq!(solve(), log);
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_next_step_with_flag() {
+        let program = "max x + y + z + w\n x + y <= 4\n z + w <= 6\n x + z <= 5\n y + w <= 5"
+            .parse::<LinearProgram>()
+            .unwrap();
+        for (use_bland_rule, rule) in [(true, PivotRule::Bland), (false, PivotRule::Dantzig)] {
+            let mut with_flag = Simplex::from(program.clone());
+            let mut with_rule = Simplex::from(program.clone());
+            with_flag.next_step_with_flag(use_bland_rule).unwrap();
+            with_rule.next_step(rule).unwrap();
+            assert_eq!(with_flag.pivot_log(), with_rule.pivot_log());
+        }
+    }
+
+    #[test]
+    fn test_fork_branch() {
+        let text = "max x + y\n 2x + 3y <= 12\n x <= 3.5";
+        let mut simplex = Simplex::from(text.parse::<LinearProgram>().unwrap());
+        assert_eq!(simplex.fork_branch("y").unwrap_err(), SimplexError::NotOptimal);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert!((simplex.current_values()[1].1 - 5.0 / 3.0).abs() < 1e-5);
+
+        let mut integral = Simplex::from("max x\n x <= 2".parse::<LinearProgram>().unwrap());
+        integral.solve(PivotRule::Bland).unwrap();
+        assert_eq!(integral.fork_branch("x").unwrap_err(), SimplexError::AlreadyIntegral(var("x")));
+        assert_eq!(integral.fork_branch("w").unwrap_err(), SimplexError::UnknownVariable(var("w")));
+
+        // Each child is the parent with one side of `1 < y < 2` cut off, and solves to the same
+        // optimum as the program written with its constraint from the start
+        let (lower, upper) = simplex.fork_branch("y").unwrap();
+        for (mut child, cut) in [(lower, "y <= 1"), (upper, "y >= 2")] {
+            let originals = child.current_state().constraints.original_constraints().to_vec();
+            assert_eq!(originals.len(), 3);
+            assert_eq!(originals[2], cut.parse().unwrap());
+
+            // The dual simplex already reached the optimum
+            child.solve(PivotRule::Bland).unwrap();
+            assert!(child.pivot_log().is_empty());
+
+            let mut cold = Simplex::from(format!("{text}\n {cut}").parse::<LinearProgram>().unwrap());
+            cold.solve(PivotRule::Bland).unwrap();
+            assert_eq!(child.current_values(), cold.current_values());
+            assert!(child.current_state().objective_value() <= simplex.current_state().objective_value());
+        }
+
+        // Free variables are branched on through their parts
+        let mut simplex = Simplex::from("min x\n 2x >= -3\n x free".parse::<LinearProgram>().unwrap());
+        simplex.solve(PivotRule::Bland).unwrap();
+        let (mut lower, mut upper) = simplex.fork_branch("x").unwrap();
+        assert_eq!(lower.solve(PivotRule::Bland), Err(SimplexError::Infeasible));
+        upper.solve(PivotRule::Bland).unwrap();
+        assert_eq!(upper.current_values(), vec![(var("x"), -1.0)]);
+    }
+
+    #[test]
+    fn test_explain_step() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        assert_eq!(simplex.explain_step(0), Err(SimplexError::NoSuchStep(1)));
+        simplex.solve(PivotRule::Dantzig).unwrap();
+
+        let explanation = simplex.explain_step(0).unwrap();
+        let entering = &simplex.pivot_log()[0].entering;
+        assert_eq!(entering, "z");
+        assert!(explanation.starts_with("z enters the basis because its reduced cost 13 is the largest"));
+        assert!(explanation.contains("ratio 200"));
+
+        let last = simplex.pivot_log().len();
+        assert!(simplex.explain_step(last).unwrap().starts_with("The optimum is reached"));
+        assert_eq!(simplex.explain_step(last + 1), Err(SimplexError::NoSuchStep(last + 1)));
+
+        // Phase one starts with the artificial variable entering the basis
+        let mut simplex = Simplex::from("max x\n x <= 4\n x >= 1".parse::<LinearProgram>().unwrap());
+        simplex.solve(PivotRule::Bland).unwrap();
+        let explanation = simplex.explain_step(0).unwrap();
+        assert!(explanation.starts_with("The artificial variable εa enters the basis"));
+        assert!(explanation.contains("most negative constant -1"));
+    }
+
+    #[test]
+    fn test_difference() {
+        let mut simplex = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600")
+            .unwrap()
+            .maximize(&"x + 6y + 13z".parse().unwrap())
+            .unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        let diff = simplex.difference(0, 1).unwrap();
+        assert_eq!(diff.entered, vec![simplex.pivot_log()[0].entering.clone()]);
+        assert_eq!(diff.entered, vec!["x"]);
+        assert_eq!(diff.left, vec!["ε0"]);
+        assert_eq!(diff.objective_delta, 200.0);
+        assert_eq!(
+            diff.value_changes,
+            vec![
+                (var("x"), 0.0, 200.0),
+                (var("ε0"), 200.0, 0.0),
+                (var("ε2"), 400.0, 200.0)
+            ]
+        );
+        assert_eq!(diff.objective_changes, vec![(var("x"), 1.0, 0.0), (var("ε0"), 0.0, -1.0)]);
+
+        // Between the first and the last step, only the net change is kept
+        let last = simplex.pivot_log().len();
+        let diff = simplex.difference(0, last).unwrap();
+        assert_eq!(diff.objective_delta, 3100.0);
+        assert_eq!(diff.entered, vec!["x", "y", "z"]);
+        assert_eq!(simplex.difference(last, 0).unwrap().left, vec!["x", "y", "z"]);
+        assert_eq!(simplex.difference(0, last + 1), Err(SimplexError::NoSuchStep(last + 1)));
+    }
+
+    #[test]
+    fn test_solve_with() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let mut simplex = constraints
+            .maximize(&LinearFunction::from_str("x + 6y + 13z").unwrap())
+            .unwrap();
+
+        let mut steps = vec![];
+        simplex
+            .solve_with(PivotRule::Bland, |state, step| steps.push((step, state.objective_value())))
+            .unwrap();
+        assert_eq!(steps.len(), simplex.pivot_log().len());
+        assert_eq!(steps.first(), Some(&(1, 200.0)));
+        assert_eq!(steps.last(), Some(&(steps.len(), 3100.0)));
+
+        // Errors stop the solve
+        let mut simplex = Constraints::compile("x - y <= 1")
+            .unwrap()
+            .maximize(&LinearFunction::from_str("x").unwrap())
+            .unwrap();
+        let mut calls = 0;
+        assert!(matches!(
+            simplex.solve_with(PivotRule::Bland, |_, _| calls += 1),
+            Err(SimplexError::Unbounded)
+        ));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_quick_unbounded_check() {
+        let program = LinearProgram::try_from("max x\n x - y <= 0").unwrap();
+        assert_eq!(program.quick_unbounded_check(), Some(var("x")));
+
+        // A variable restricting nothing
+        let program = LinearProgram::try_from("max x + y\n x <= 4").unwrap();
+        assert_eq!(program.quick_unbounded_check(), Some(var("y")));
+
+        let program = LinearProgram::try_from("max x + y\n x <= 4\n y <= 3").unwrap();
+        assert_eq!(program.quick_unbounded_check(), None);
+
+        // Relaxing the constraint with y would lower the objective
+        let program = LinearProgram::try_from("max x - y\n x - y <= 0").unwrap();
+        assert_eq!(program.quick_unbounded_check(), None);
+
+        // Bounded variables can't grow forever
+        let program = LinearProgram::try_from("max x\n x - y <= 0\n 0 <= y <= 5").unwrap();
+        assert_eq!(program.quick_unbounded_check(), None);
+    }
+
+    #[test]
+    fn test_two_phase() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x + y >= 2\n x <= 3\n y <= 3").unwrap();
+        let mut simplex = constraints
+            .minimize(&LinearFunction::from_str("x + 2y").unwrap())
+            .unwrap();
+        assert_eq!(simplex.current_phase(), Phase::Phase1);
+
+        // The artificial variable enters the base first, then leaves it when phase one ends
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_phase(), Phase::Phase1);
+        assert_eq!(simplex.pivot_log()[0].entering, artificial_variable());
+        simplex.next_step(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_phase(), Phase::Phase2);
+        assert_eq!(simplex.pivot_log()[1].leaving, Some(artificial_variable()));
+
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_phase(), Phase::Phase2);
+        assert_eq!(simplex.current_point(), vec![2.0, 0.0]);
+        assert_eq!(simplex.current_state().objective_value(), 2.0);
+
+        simplex.reset();
+        assert_eq!(simplex.current_phase(), Phase::Phase1);
+
+        // A program whose origin is feasible needs a single phase
+        let simplex = Constraints::compile("x <= 3")
+            .unwrap()
+            .maximize(&LinearFunction::from_str("x").unwrap())
+            .unwrap();
+        assert_eq!(simplex.current_phase(), Phase::Single);
+
+        let mut simplex = Constraints::compile("x + y >= 5\n x <= 1\n y <= 1")
+            .unwrap()
+            .maximize(&LinearFunction::from_str("x + y").unwrap())
+            .unwrap();
+        assert!(matches!(simplex.solve(PivotRule::Bland), Err(SimplexError::Infeasible)));
+    }
+
+    #[test]
+    fn test_tolerances() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("a <= 1\n x <= 1").unwrap();
+        let objective = LinearFunction::from_str("0.0000001a + x").unwrap();
+
+        // The coefficient of a is considered to be 0 by default, so it never enters the base
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        assert_eq!(simplex.tolerances(), Tolerances::default());
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_point(), vec![0.0, 1.0]);
+
+        let tight = Tolerances {
+            zero: 1e-9,
+            ratio: 1e-9,
+            ..Tolerances::default()
+        };
+        let mut simplex = constraints.maximize(&objective).unwrap().with_tolerances(tight);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_point(), vec![1.0, 1.0]);
+        assert_eq!(simplex.tolerances(), tight);
+    }
+
+    #[test]
+    fn test_no_constraints() {
+        let constraints = Constraints::compile("").unwrap();
+        assert!(constraints.is_empty());
+        assert!(matches!(constraints.maximize(&"x".parse().unwrap()), Err(SimplexError::Unbounded)));
+        assert_eq!(constraints.max_value(&"5".parse().unwrap()), Ok(5.0));
+        assert_eq!(constraints.max_value(&"5 - x".parse().unwrap()), Ok(5.0));
+        assert_eq!(constraints.min_value(&"x + 1".parse().unwrap()), Ok(1.0));
+        assert_eq!(constraints.find_feasible_point(), Ok(HashMap::new()));
+
+        // As the visualizer builds it when the constraints are cleared
+        let program = "max x\n".parse::<LinearProgram>().unwrap();
+        assert!(program.is_unbounded());
+        assert!(program.is_valid());
+        assert_eq!(program.point(), vec![0.0]);
+        for backend in [TableauBackend::Map, TableauBackend::Dense] {
+            let mut simplex = Simplex::from(program.clone()).with_backend(backend);
+            assert_eq!(simplex.solve(PivotRule::Dantzig), Err(SimplexError::Unbounded));
+            assert_eq!(simplex.status(), SimplexStatus::Unbounded);
+
+            let mut simplex = Simplex::from("max 5".parse::<LinearProgram>().unwrap()).with_backend(backend);
+            assert_eq!(simplex.solve(PivotRule::Dantzig), Ok(()));
+            assert_eq!(simplex.status(), SimplexStatus::Optimal);
+            assert_eq!(simplex.current_state().objective_value(), 5.0);
+            assert!(simplex.current_point().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_clone_with_tolerance() {
+        let program = "max 0.00001x + y\n x <= 1\n y <= 1".parse::<LinearProgram>().unwrap();
+        let loose = program.clone_with_tolerance(Tolerances {
+            zero: 1e-4,
+            ..Tolerances::default()
+        });
+
+        // Once y is in the base, the coefficient of x is only considered to be 0 by the loose one
+        let mut simplex = Simplex::from(program);
+        let mut loose = Simplex::from(loose);
+        simplex.step_with_entering("y").unwrap();
+        loose.step_with_entering("y").unwrap();
+        assert!(!simplex.is_optimal());
+        assert!(loose.is_optimal());
+        assert_eq!(loose.tolerances().zero, 1e-4);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_point(), vec![1.0, 1.0]);
+        assert_eq!(loose.current_point(), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_numerical_warnings() {
+        let mut simplex = Simplex::from("max x + y\n 0.0001x <= 1\n y <= 2".parse::<LinearProgram>().unwrap());
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(
+            simplex.warnings(),
+            &[NumericalWarning::SmallPivot {
+                step: 1,
+                entering: var("x"),
+                pivot_element: -0.0001
+            }]
+        );
+        // The solve still goes on to the optimum
+        assert_eq!(simplex.current_point(), vec![10000.0, 2.0]);
+
+        let mut simplex = Simplex::from("max x + y\n x <= 1\n y <= 2".parse::<LinearProgram>().unwrap());
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert!(simplex.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_dual() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let dual = program.dual();
+        assert_eq!(dual.sense, ObjectiveSense::Min);
+        assert_eq!(dual.non_gap_variables(), vec!["y0", "y1", "y2", "y3"]);
+        assert_eq!(dual.constraints.original_constraints().len(), 3);
+
+        // Strong duality: both programs have the same optimal value
+        let mut primal = Simplex::from(program);
+        primal.solve(PivotRule::Bland).unwrap();
+        let mut dual = Simplex::from(dual);
+        dual.solve(PivotRule::Bland).unwrap();
+        assert_eq!(primal.current_state().objective_value(), 3100.0);
+        assert!((dual.current_state().objective_value() - 3100.0).abs() < 1e-3);
+
+        // The dual of a minimization is a maximization
+        let program = "min x + 2y\n x + y >= 2\n x <= 3\n y <= 3".parse::<LinearProgram>().unwrap();
+        let dual = program.dual();
+        assert_eq!(dual.sense, ObjectiveSense::Max);
+        let mut dual = Simplex::from(dual);
+        dual.solve(PivotRule::Bland).unwrap();
+        assert!((dual.current_state().objective_value() - 2.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_verify_optimal() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Bland).unwrap();
+        let optimum = simplex.current_state();
+        let primal: HashMap<Variable, Coefficient> = optimum.values().into_iter().collect();
+        let dual = optimum.shadow_prices();
+        assert_eq!(program.verify_optimal(&primal, &dual), Ok(()));
+
+        // `x <= 200` isn't binding, so its dual value must be 0
+        let mut wrong_dual = dual.clone();
+        wrong_dual[0] = 1.0;
+        let violations = program.verify_optimal(&primal, &wrong_dual).unwrap_err();
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].contains("constraint #0 has a slack of 200"), "{violations:?}");
+
+        // A feasible point which isn't optimal has no certificate
+        let origin = HashMap::new();
+        assert!(program.verify_optimal(&origin, &[0.0; 4]).is_err());
+        let outside = HashMap::from([(var("x"), 300.0)]);
+        let violations = program.verify_optimal(&outside, &dual).unwrap_err();
+        assert!(violations[0].contains("doesn't hold"), "{violations:?}");
+        assert_eq!(program.verify_optimal(&primal, &dual[..2]), Err(vec!["expected 4 dual values, found 2".to_string()]));
+
+        // The conditions are checked up to the certificate tolerance of the program
+        let mut rounded_dual = dual.clone();
+        rounded_dual[3] += 0.01;
+        assert!(program.verify_optimal(&primal, &rounded_dual).is_err());
+        let loose = program.clone_with_tolerance(Tolerances { certificate: 0.1, ..Tolerances::default() });
+        assert_eq!(loose.verify_optimal(&primal, &rounded_dual), Ok(()));
+
+        // Minimizations, equations and free variables
+        let program = "min x + 2y\n x + y >= 2\n x <= 3\n y <= 3".parse::<LinearProgram>().unwrap();
+        let primal = HashMap::from([(var("x"), 2.0), (var("y"), 0.0)]);
+        assert_eq!(program.verify_optimal(&primal, &[-1.0, 0.0, 0.0]), Ok(()));
+        assert!(program.verify_optimal(&primal, &[1.0, 0.0, 0.0]).is_err());
+        let program = "max x\n x + y = 1\n y free".parse::<LinearProgram>().unwrap();
+        assert!(program.verify_optimal(&HashMap::from([(var("x"), 1.0)]), &[1.0]).is_err());
+        let program = "max x - y\n x - y = 1\n y <= 3\n y free".parse::<LinearProgram>().unwrap();
+        let primal = HashMap::from([(var("x"), 0.0), (var("y"), -1.0)]);
+        assert_eq!(program.verify_optimal(&primal, &[1.0, 0.0]), Ok(()));
+    }
+
+    #[test]
+    fn test_variable_order() {
+        let mut program = "max x10 + x2 + x1\n x2 + x10 <= 4\n x1 <= 1".parse::<LinearProgram>().unwrap();
+        assert_eq!(program.non_gap_variables(), vec!["x1", "x10", "x2"]);
+
+        program.variable_order = VariableOrder::Natural;
+        assert_eq!(program.non_gap_variables(), vec!["x1", "x2", "x10"]);
+
+        program.variable_order = VariableOrder::InsertionOrder;
+        assert_eq!(program.non_gap_variables(), vec!["x10", "x2", "x1"]);
+
+        // The coordinates of the points follow the same order
+        let mut simplex = Simplex::from(program).with_variable_order(VariableOrder::Natural);
+        simplex.solve(PivotRule::Bland).unwrap();
+        let values = simplex.current_values();
+        assert_eq!(values.iter().map(|(var, _)| var.as_str()).collect::<Vec<_>>(), vec!["x1", "x2", "x10"]);
+        assert_eq!(simplex.current_point()[0], 1.0);
+
+        assert_eq!(natural_cmp("x2", "x10"), std::cmp::Ordering::Less);
+        assert_eq!(natural_cmp("x02", "x2"), std::cmp::Ordering::Less);
+        assert_eq!(natural_cmp("y", "x10"), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn test_validate() {
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        assert_eq!(program.validate(), Ok(()));
+
+        let program = "max x + y + z\n x <= 3\n 0 <= z <= 1".parse::<LinearProgram>().unwrap();
+        assert_eq!(
+            program.validate(),
+            Err(vec![ValidationWarning::UnconstrainedObjectiveVariable(var("y"))])
+        );
+
+        let program = "max x\n x <= 3\n x - x <= 1".parse::<LinearProgram>().unwrap();
+        assert_eq!(program.validate(), Err(vec![ValidationWarning::EmptyConstraint(1)]));
+
+        let program = "max x + y\n x + y <= 4\n x <= -5".parse::<LinearProgram>().unwrap();
+        assert_eq!(program.validate(), Err(vec![ValidationWarning::ForcedNegative(var("x"))]));
+        let program = "max x + y\n x + y <= 4\n x <= -5\n x free".parse::<LinearProgram>().unwrap();
+        assert_eq!(program.validate(), Ok(()));
+
+        let options = constraint::CompileOptions::default().with_case_folding(true);
+        let constraints = Constraints::compile_with_options("x + Y <= 4\n y <= 3", &options).unwrap();
+        let program = constraints.to_program(&"x + y".parse().unwrap(), ObjectiveSense::Max);
+        assert_eq!(
+            program.validate(),
+            Err(vec![ValidationWarning::MergedVariables {
+                names: vec![var("Y"), var("y")],
+                into: var("y")
+            }])
+        );
+    }
+
+    #[test]
+    fn test_solve_lexicographic() {
+        use std::str::FromStr;
+        // Every point of the segment from (2, 0) to (2, 3) maximizes x
+        let constraints = Constraints::compile("x <= 2\n x + y <= 5\n y <= 4").unwrap();
+        let x = LinearFunction::from_str("x").unwrap();
+        let y = LinearFunction::from_str("y").unwrap();
+
+        let mut simplex = constraints.maximize(&x).unwrap();
+        simplex.solve_lexicographic(&[x.clone(), y.clone()]).unwrap();
+        let point = simplex.current_point();
+        assert!((point[0] - 2.0).abs() < 1e-3 && (point[1] - 3.0).abs() < 1e-3);
+        // The level of the first objective is part of the final tableau
+        assert_eq!(simplex.current_state().constraints.original_constraints().len(), 4);
+        assert_eq!(
+            simplex.current_state().constraints.original_constraints()[3].label,
+            Some("objective #0".to_string())
+        );
+
+        // The objectives are minimized for a minimization problem
+        let mut simplex = constraints.minimize(&x).unwrap();
+        simplex.solve_lexicographic(&[-y.clone(), -x.clone()]).unwrap();
+        let point = simplex.current_point();
+        assert!((point[0] - 1.0).abs() < 1e-3 && (point[1] - 4.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_snapshot() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x + y >= 2\n x <= 3\n y <= 3").unwrap();
+        let mut simplex = constraints.maximize(&LinearFunction::from_str("x + 2y").unwrap()).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        let restored = Simplex::restore(simplex.snapshot());
+        assert!(restored.is_first_step());
+        assert_eq!(restored.current_values(), simplex.current_values());
+        assert_eq!(restored.current_phase(), Phase::Phase2);
+
+        // A snapshot taken during phase one can still reach the optimum
+        let optimum = simplex.current_values();
+        simplex.reset();
+        let mut restored = Simplex::restore(simplex.snapshot());
+        assert_eq!(restored.current_phase(), Phase::Phase1);
+        restored.solve(PivotRule::Bland).unwrap();
+        assert_eq!(restored.current_values(), optimum);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serialize_snapshot() {
+        // Gap, artificial and free variables all get into the snapshot
+        let program = "max x + 2y - z\n x + y >= 2\n x - z <= 3\n y <= 3\n z free".parse::<LinearProgram>().unwrap();
+        let mut simplex = Simplex::from(program);
+        let round_trip = |simplex: &Simplex| {
+            let json = serde_json::to_string(&simplex.snapshot()).unwrap();
+            Simplex::restore(serde_json::from_str(&json).unwrap())
+        };
+
+        simplex.next_step(PivotRule::Bland).unwrap();
+        let mut restored = round_trip(&simplex);
+        assert_eq!(restored.current_values(), simplex.current_values());
+        assert_eq!(restored.current_phase(), simplex.current_phase());
+
+        simplex.solve(PivotRule::Bland).unwrap();
+        restored.solve(PivotRule::Bland).unwrap();
+        assert_eq!(restored.current_values(), simplex.current_values());
+        assert_eq!(round_trip(&simplex).current_values(), simplex.current_values());
+    }
+
+    #[test]
+    #[should_panic(expected = "the objective got worse from step 0 to step 1")]
+    fn test_assert_monotonic_objective() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x <= 2\n y <= 3").unwrap();
+        let mut simplex = constraints.maximize(&LinearFunction::from_str("x + y").unwrap()).unwrap();
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        simplex.assert_monotonic_objective();
+
+        // Pivoting a variable back out of the base by hand makes the objective worse
+        let mut state = simplex.current_state().clone();
+        state.pivot(var("ε0")).unwrap();
+        let mut worse = Simplex::restore(simplex.snapshot());
+        worse.historic.push(OnceCell::from(state));
+        worse.phases.push(worse.phases[0]);
+        worse.assert_monotonic_objective();
+    }
+
+    #[test]
+    fn test_with_objective() {
+        use std::str::FromStr;
+        let program = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let mut simplex = Simplex::from(program.clone());
+        simplex.solve(PivotRule::Bland).unwrap();
+
+        // The solved program gives the original constraints back
+        let other = simplex
+            .current_state()
+            .with_objective(LinearFunction::from_str("x + y").unwrap(), ObjectiveSense::Max);
+        assert_eq!(other.constraints, program.constraints);
+        let mut simplex = Simplex::from(other);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), 400.0);
+
+        let other = program.with_objective(LinearFunction::from_str("x - z").unwrap(), ObjectiveSense::Min);
+        let mut simplex = Simplex::from(other);
+        simplex.solve(PivotRule::Bland).unwrap();
+        assert_eq!(simplex.current_state().objective_value(), -200.0);
+        assert_eq!(simplex.current_point(), vec![0.0, 0.0, 200.0]);
+    }
+
+    #[test]
+    fn test_objective_history() {
+        use std::str::FromStr;
+        let constraints = Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+        let mut simplex = constraints.maximize(&LinearFunction::from_str("x + 6y + 13z").unwrap()).unwrap();
+        simplex.solve(PivotRule::Dantzig).unwrap();
+        simplex.reset();
+
+        let history = simplex.objective_history();
+        assert_eq!(history.len(), simplex.pivot_log().len() + 1);
+        assert!(history.iter().tuple_windows().all(|(a, b)| a <= b));
+        assert_eq!(history.first(), Some(&0.0));
+        assert_eq!(history.last(), Some(&3100.0));
+
+        // The objective of a minimization is given in its own sense, even during phase one
+        let constraints = Constraints::compile("x + y >= 2\n x <= 3\n y <= 3").unwrap();
+        let mut simplex = constraints.minimize(&LinearFunction::from_str("x + 2y").unwrap()).unwrap();
+        simplex.solve(PivotRule::Bland).unwrap();
+        let history = simplex.objective_history();
+        assert_eq!(history.first(), Some(&0.0));
+        assert_eq!(history.last(), Some(&2.0));
     }
 }
@@ -1,19 +1,205 @@
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::{alpha1, alphanumeric0, multispace0};
-use std::collections::{HashMap, HashSet};
+use nom::bytes::complete::{take_while, take_while1};
+use nom::character::complete::{char, digit0, digit1, multispace0, one_of};
+use nom::combinator::{map_res, opt, recognize};
+use std::collections::HashMap;
+use itertools::Itertools;
 
 use nom::multi::many0;
-use nom::number::complete::float;
-use nom::sequence::preceded;
+use nom::sequence::{pair, preceded, tuple};
 use nom::IResult;
 
-pub type Variable = String;
+use crate::SimplexError;
+
 pub type Coefficient = f32;
 
+/// The name of a variable: an ASCII letter or `_`, followed by ASCII letters, digits and `_`
+/// ```rust
+/// use simplex::linear_function::Variable;
+///
+/// let var = Variable::new("x_1").unwrap();
+/// assert_eq!(var, "x_1");
+/// assert_eq!(var.to_string(), "x_1");
+/// assert!(Variable::new("2x").is_err());
+/// assert_eq!(Variable::try_from("y"), "y".parse());
+/// ```
+///
+/// The gap variables and the parts of the free variables are named by the solver itself, and may
+/// not follow those rules
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SerializedName", into = "String")
+)]
+pub struct Variable(String);
+
+/// Returns true if `name` is an identifier the parser reads back, i.e. an ASCII letter or `_`
+/// followed by ASCII letters, digits and `_`
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Variable {
+    /// Returns the variable named `name`, or `InvalidVariable` if it isn't a valid identifier and
+    /// `ReservedVariable` if it is the name of a gap variable
+    pub fn new(name: impl Into<String>) -> Result<Variable, SimplexError> {
+        let name = name.into();
+        if name.starts_with(GAP_VARIABLE_IDENTIFIER) {
+            Err(SimplexError::ReservedVariable(Variable(name)))
+        } else if is_identifier(&name) {
+            Ok(Variable(name))
+        } else {
+            Err(SimplexError::InvalidVariable(name))
+        }
+    }
+
+    /// Returns the variable named `name` without checking it, for the names made up by the solver
+    pub(crate) fn unchecked(name: impl Into<String>) -> Variable {
+        Variable(name.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A variable name as serialized, which may also be one made up by the solver, since the
+/// snapshots of a solve hold the gap variables and the parts of the free variables
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+#[serde(transparent)]
+struct SerializedName(String);
+
+#[cfg(feature = "serde")]
+impl TryFrom<SerializedName> for Variable {
+    type Error = SimplexError;
+
+    fn try_from(SerializedName(name): SerializedName) -> Result<Variable, SimplexError> {
+        let made_by_solver = name.starts_with(GAP_VARIABLE_IDENTIFIER)
+            || name.strip_suffix(['⁺', '⁻']).is_some_and(is_identifier);
+        if made_by_solver {
+            Ok(Variable(name))
+        } else {
+            Variable::new(name)
+        }
+    }
+}
+
+impl std::ops::Deref for Variable {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::borrow::Borrow<str> for Variable {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Variable {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for Variable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::fmt::Debug for Variable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl std::str::FromStr for Variable {
+    type Err = SimplexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variable::new(s)
+    }
+}
+
+impl TryFrom<&str> for Variable {
+    type Error = SimplexError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Variable::new(value)
+    }
+}
+
+impl TryFrom<String> for Variable {
+    type Error = SimplexError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Variable::new(value)
+    }
+}
+
+impl From<Variable> for String {
+    fn from(value: Variable) -> Self {
+        value.0
+    }
+}
+
+impl PartialEq<str> for Variable {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Variable {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Variable {
+    fn eq(&self, other: &String) -> bool {
+        &self.0 == other
+    }
+}
+
+impl PartialEq<Variable> for &str {
+    fn eq(&self, other: &Variable) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<Variable> for String {
+    fn eq(&self, other: &Variable) -> bool {
+        *self == other.0
+    }
+}
+
+/// Returns the variable named `name`, which must be valid or the name of a gap variable, to
+/// write tests more concisely
+#[cfg(test)]
+pub(crate) fn var(name: &str) -> Variable {
+    if name.starts_with(GAP_VARIABLE_IDENTIFIER) {
+        Variable::unchecked(name)
+    } else {
+        Variable::new(name).unwrap()
+    }
+}
+
 pub const GAP_VARIABLE_IDENTIFIER: char = 'ε';
 
-#[derive(Default, PartialEq, Debug, Clone)]
+/// Coefficients smaller than this in absolute value are considered to be rounding errors, and
+/// dropped by `LinearFunction::simplify`
+pub const ZERO_TOLERANCE: Coefficient = 1e-6;
+
+#[derive(Default, Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LinearFunction {
     pub constant: Coefficient,
     coefficients: HashMap<Variable, Coefficient>,
@@ -32,6 +218,26 @@ impl LinearFunction {
         LinearFunction::default()
     }
 
+    /// Creates a new linear function with value `c`
+    pub fn constant(c: Coefficient) -> LinearFunction {
+        LinearFunction {
+            constant: c,
+            coefficients: HashMap::new(),
+        }
+    }
+
+    /// Creates a new linear function made of a single term `coeff * var`
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let f = LinearFunction::term(2.0, "x".parse().unwrap()) + LinearFunction::constant(3.0);
+    /// assert_eq!(f, "2x + 3".parse().unwrap());
+    /// assert_eq!(f.to_string(), "3.0 + 2.0x");
+    /// ```
+    pub fn term(coeff: Coefficient, var: Variable) -> LinearFunction {
+        LinearFunction::single_variable_with_coeff(var, coeff)
+    }
+
     /// Creates a new linear function containing a single variable with coefficient 1
     pub fn single_variable(var: Variable) -> LinearFunction {
         LinearFunction {
@@ -57,14 +263,34 @@ impl LinearFunction {
         }
     }
 
+    /// Returns true if both functions have the same constant and coefficients, up to `epsilon`
+    pub fn approx_eq(&self, other: &LinearFunction, epsilon: f32) -> bool {
+        (self.constant - other.constant).abs() <= epsilon
+            && self
+                .coefficients
+                .keys()
+                .chain(other.coefficients.keys())
+                .all(|var| (self[var] - other[var]).abs() <= epsilon)
+    }
+
+    /// Returns the coefficient of `var` in this function, 0 if it doesn't appear in it
+    pub fn coefficient_of(&self, var: &str) -> Coefficient {
+        self.coefficients.get(var).copied().unwrap_or(0.0)
+    }
+
+    /// Returns the constant term of this function
+    pub fn constant_term(&self) -> Coefficient {
+        self.constant
+    }
+
     /// Applies the linear function to a given valuation, returning the value
     /// ```rust
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
-    /// let linear_func = LinearFunction::new(10f32, HashMap::from([("x".to_string(), 20f32), ("z".to_string(), -2f32)]));
+    /// let linear_func = LinearFunction::new(10f32, HashMap::from([("x".parse().unwrap(), 20f32), ("z".parse().unwrap(), -2f32)]));
     /// let valuation = HashMap::from([
-    ///     (String::from("x"), 2f32),
-    ///     (String::from("y"), -432f32)
+    ///     ("x".parse().unwrap(), 2f32),
+    ///     ("y".parse().unwrap(), -432f32)
     /// ]);
     /// assert_eq!(linear_func.apply(&valuation), 50f32)
     /// ```
@@ -76,21 +302,40 @@ impl LinearFunction {
             })
     }
 
+    /// Evaluates the linear function at a point given as a list of variable values, such as the
+    /// one returned by [`crate::LinearProgram::values`]
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    /// let linear_func: LinearFunction = "x + 2y + 1".parse().unwrap();
+    /// assert_eq!(linear_func.evaluate(&[("x".parse().unwrap(), 3.0), ("y".parse().unwrap(), 2.0)]), 8.0);
+    /// ```
+    pub fn evaluate(&self, point: &[(Variable, Coefficient)]) -> Coefficient {
+        point
+            .iter()
+            .fold(self.constant, |acc, (var, value)| acc + self[var] * value)
+    }
+
     /// Returns true if the function only has negative coefficients
     pub fn no_positive_coefficient(&self) -> bool {
         !self.coefficients.values().any(|c| *c > 0.0)
     }
 
-    /// Returns the variable with the maximal coefficient
+    /// Returns the variable with the maximal coefficient. Ties go to the first variable in
+    /// alphabetical order, so that the choice doesn't depend on the order of the hash map
     pub fn max_coefficient(&self) -> Option<(Variable, Coefficient)> {
         self.coefficients
-            .clone()
-            .into_iter()
-            .max_by(|(_, coeff_x), (_, coeff_y)| coeff_x.total_cmp(coeff_y))
+            .iter()
+            .sorted_by(|(x, _), (y, _)| x.cmp(y))
+            .fold(None, |best: Option<(&Variable, &Coefficient)>, (var, coeff)| match best {
+                Some((_, best_coeff)) if best_coeff >= coeff => best,
+                _ => Some((var, coeff)),
+            })
+            .map(|(var, coeff)| (var.clone(), *coeff))
     }
 
-    /// Returns the first variable with a positive coefficient
-    pub fn first_positive_coefficient(&self, ordered: bool) -> Option<Variable> {
+    /// Returns the first variable with a coefficient greater than `tolerance`, in alphabetical
+    /// order if `ordered` is set, otherwise in the arbitrary order of the hash map
+    pub fn first_positive_coefficient(&self, ordered: bool, tolerance: Coefficient) -> Option<Variable> {
         let mut coeffs = self.coefficients.clone().into_iter().collect::<Vec<_>>();
         if ordered {
             coeffs.sort_by_key(|(v, _)| v.clone())
@@ -98,7 +343,7 @@ impl LinearFunction {
 
         coeffs
             .into_iter()
-            .find_map(|(v, c)| if c > 0.0 { Some(v) } else { None })
+            .find_map(|(v, c)| if c > tolerance { Some(v) } else { None })
     }
 
     /// Normalizes this linear function with respect to a given variable
@@ -109,16 +354,160 @@ impl LinearFunction {
     }
 
     /// Replaces a variable with a given linear function
+    ///
+    /// The function is updated in place, term by term, rather than rebuilt: this is done for
+    /// the objective and every row at each pivot
     pub fn replace(&mut self, var: &Variable, func: &LinearFunction) {
         if let Some(coeff) = self.coefficients.remove(var) {
-            *self += func.clone() * coeff
+            self.add_scaled(func, coeff);
+            self.simplify();
         }
     }
 
+    /// Adds `factor * other` to this function in place, without cloning `other`
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let mut f: LinearFunction = "x + 2y + 1".parse().unwrap();
+    /// f.add_scaled(&"y - z + 2".parse().unwrap(), 3.0);
+    /// assert_eq!(f, "x + 5y - 3z + 7".parse().unwrap());
+    /// ```
+    pub fn add_scaled(&mut self, other: &LinearFunction, factor: Coefficient) {
+        self.constant += other.constant * factor;
+        for (var, coeff) in &other.coefficients {
+            match self.coefficients.get_mut(var) {
+                Some(existing) => *existing += coeff * factor,
+                None => {
+                    self.coefficients.insert(var.clone(), coeff * factor);
+                }
+            }
+        }
+    }
+
+    /// Renames `from` to `to`, adding its coefficient to the one of `to` if it already appears
+    ///
+    /// Gap variables can neither be renamed nor be the new name, as the tableau relies on them
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let mut f: LinearFunction = "2x + 3y + 1".parse().unwrap();
+    /// f.rename_variable("x", "y").unwrap();
+    /// assert_eq!(f, "5y + 1".parse().unwrap());
+    /// assert!(f.rename_variable("y", "ε0").is_err());
+    /// ```
+    pub fn rename_variable(&mut self, from: &str, to: &str) -> Result<(), SimplexError> {
+        for name in [from, to] {
+            if name.starts_with(GAP_VARIABLE_IDENTIFIER) {
+                return Err(SimplexError::ReservedVariable(Variable::unchecked(name)));
+            }
+        }
+        let to = Variable::new(to)?;
+        if from != to {
+            if let Some(coeff) = self.coefficients.remove(from) {
+                *self.coefficients.entry(to).or_insert(0.0) += coeff;
+                self.simplify();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if every coefficient of the function is zero, up to `tolerance`
+    /// ```rust
+    /// use simplex::linear_function::{LinearFunction, ZERO_TOLERANCE};
+    ///
+    /// assert!("5".parse::<LinearFunction>().unwrap().is_constant(ZERO_TOLERANCE));
+    /// assert!(!"x + 5".parse::<LinearFunction>().unwrap().is_constant(ZERO_TOLERANCE));
+    /// assert!("0.01x + 5".parse::<LinearFunction>().unwrap().is_constant(0.1));
+    /// ```
+    pub fn is_constant(&self, tolerance: Coefficient) -> bool {
+        self.coefficients.values().all(|coeff| coeff.abs() <= tolerance)
+    }
+
+    /// Returns true if the function is constant and its constant is zero, up to `tolerance`
+    pub fn is_zero(&self, tolerance: Coefficient) -> bool {
+        self.is_constant(tolerance) && self.constant.abs() <= tolerance
+    }
+
+    /// Removes the terms whose coefficient is zero, up to `ZERO_TOLERANCE`
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let mut f = LinearFunction::term(1.0, "x".parse().unwrap()) - LinearFunction::term(1.0, "x".parse().unwrap()) + LinearFunction::term(2.0, "y".parse().unwrap());
+    /// assert_eq!(f.non_gap_variables().len(), 2);
+    /// f.simplify();
+    /// assert_eq!(f.non_gap_variables(), vec!["y"]);
+    /// ```
+    pub fn simplify(&mut self) {
+        self.coefficients.retain(|_, coeff| coeff.abs() > ZERO_TOLERANCE);
+    }
+
+    /// Returns a copy of this function without its zero terms, see `simplify`
+    pub fn simplified(&self) -> LinearFunction {
+        let mut simplified = self.clone();
+        simplified.simplify();
+        simplified
+    }
+
     /// Returns an iterator over the variables that have a coefficient different of 0
     pub fn var_iter(&self) -> impl Iterator<Item = &Variable> {
         self.coefficients.keys().filter(|var| self[var] != 0.0)
     }
+    /// Returns every variable with a coefficient different of 0, gap variables included, sorted in
+    /// alphabetical order (hence gap variables come last)
+    /// ```rust
+    /// use simplex::LinearProgram;
+    ///
+    /// let mut program = "max 3x + y + 0z\n 2x + y <= 4".parse::<LinearProgram>().unwrap();
+    /// program.pivot("x".parse().unwrap()).unwrap();
+    /// assert_eq!(program.linear_function.variables(), vec!["y", "ε0"]);
+    /// ```
+    pub fn variables(&self) -> Vec<Variable> {
+        let mut variables = self.var_iter().cloned().collect::<Vec<_>>();
+        variables.sort();
+        variables
+    }
+
+    /// Returns an iterator over the variables that have a coefficient different of 0 along with
+    /// their coefficient, sorted in alphabetical order, without the constant
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let f = "3y + 2x - 4".parse::<LinearFunction>().unwrap();
+    /// let terms: Vec<_> = f.terms().map(|(var, coeff)| (var.as_str(), coeff)).collect();
+    /// assert_eq!(terms, vec![("x", 2.0), ("y", 3.0)]);
+    /// ```
+    pub fn terms(&self) -> impl Iterator<Item = (&Variable, Coefficient)> {
+        self.coefficients
+            .iter()
+            .filter(|(_, coeff)| **coeff != 0.0)
+            .map(|(var, coeff)| (var, *coeff))
+            .sorted_by(|(a, _), (b, _)| a.cmp(b))
+    }
+
+    /// Splits the function into its terms with a positive coefficient and the opposite of its
+    /// terms with a negative one, so that it is the difference of the two returned functions,
+    /// whose coefficients are all positive. The constant goes to the first one if it is positive,
+    /// and to the second one otherwise
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let f = "2x - 3y + 4".parse::<LinearFunction>().unwrap();
+    /// let (positive, negative) = f.split_positive_negative();
+    /// assert_eq!(positive, "2x + 4".parse().unwrap());
+    /// assert_eq!(negative, "3y".parse().unwrap());
+    /// assert_eq!(positive - negative, f);
+    /// ```
+    pub fn split_positive_negative(&self) -> (LinearFunction, LinearFunction) {
+        let (positive, negative): (HashMap<_, _>, HashMap<_, _>) = self
+            .terms()
+            .map(|(var, coeff)| (var.clone(), coeff))
+            .partition(|(_, coeff)| *coeff > 0.0);
+        (
+            LinearFunction::new(self.constant.max(0.0), positive),
+            LinearFunction::new((-self.constant).max(0.0), negative.into_iter().map(|(var, coeff)| (var, -coeff)).collect()),
+        )
+    }
+
     pub fn into_var_iter(self) -> impl Iterator<Item = Variable> {
         self.coefficients
             .into_iter()
@@ -142,8 +531,8 @@ impl LinearFunction {
 
     pub fn non_gap_variables(&self) -> Vec<Variable> {
         self.coefficients
-            .iter()
-            .filter_map(|(var, _)| if !var.starts_with(GAP_VARIABLE_IDENTIFIER) { Some(var.to_string()) } else { None })
+            .keys()
+            .filter_map(|var| if !var.starts_with(GAP_VARIABLE_IDENTIFIER) { Some(var.clone()) } else { None })
             .collect()
     }
 
@@ -153,7 +542,7 @@ impl LinearFunction {
         }
         self.coefficients.iter().find_map(|(var, coeff)| {
             if *coeff == 1.0 {
-                Some(var.to_string())
+                Some(var.clone())
             } else {
                 None
             }
@@ -161,16 +550,29 @@ impl LinearFunction {
     }
 }
 
-impl std::ops::Index<&Variable> for LinearFunction {
+impl PartialEq for LinearFunction {
+    /// Two functions are equal if they have the same constant and coefficients, terms with a zero
+    /// coefficient being ignored
+    fn eq(&self, other: &Self) -> bool {
+        self.constant == other.constant
+            && self
+                .coefficients
+                .keys()
+                .chain(other.coefficients.keys())
+                .all(|var| self[var] == other[var])
+    }
+}
+
+impl<Q: AsRef<str> + ?Sized> std::ops::Index<&Q> for LinearFunction {
     type Output = Coefficient;
 
-    fn index(&self, index: &Variable) -> &Self::Output {
-        self.coefficients.get(index).unwrap_or(&0f32)
+    fn index(&self, index: &Q) -> &Self::Output {
+        self.coefficients.get(index.as_ref()).unwrap_or(&0f32)
     }
 }
 impl std::ops::IndexMut<&Variable> for LinearFunction {
     fn index_mut(&mut self, index: &Variable) -> &mut Self::Output {
-        self.coefficients.entry(index.to_string()).or_insert(0f32)
+        self.coefficients.entry(index.clone()).or_insert(0f32)
     }
 }
 
@@ -184,9 +586,9 @@ impl std::ops::Add<LinearFunction> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let a = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let b = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(25f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), 12f32), (String::from("z"), 0f32)]));
+    /// let a = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let b = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 0f32)]));
     /// assert_eq!(a + b, expected)
     /// ```
     fn add(self, rhs: LinearFunction) -> Self::Output {
@@ -207,9 +609,9 @@ impl std::ops::AddAssign<LinearFunction> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let mut c = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let l_f = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(25f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), 12f32), (String::from("z"), 0f32)]));
+    /// let mut c = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let l_f = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(25f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 0f32)]));
     /// c += l_f.clone();
     /// assert_eq!(c, expected)
     /// ```
@@ -228,9 +630,9 @@ impl std::ops::Sub<LinearFunction> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let a = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let b = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(35f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), -12f32), (String::from("z"), -10f32)]));
+    /// let a = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let b = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(35f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), -12f32), ("z".parse().unwrap(), -10f32)]));
     /// assert_eq!(a - b, expected)
     /// ```
     fn sub(self, rhs: LinearFunction) -> Self::Output {
@@ -250,9 +652,9 @@ impl std::ops::SubAssign<LinearFunction> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let mut c = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let l_f = LinearFunction::new(-5f32, HashMap::from([(String::from("y"), 12f32), (String::from("z"), 5f32)]));
-    /// let expected = LinearFunction::new(35f32, HashMap::from([(String::from("x"), 32f32), (String::from("y"), -12f32), (String::from("z"), -10f32)]));
+    /// let mut c = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let l_f = LinearFunction::new(-5f32, HashMap::from([("y".parse().unwrap(), 12f32), ("z".parse().unwrap(), 5f32)]));
+    /// let expected = LinearFunction::new(35f32, HashMap::from([("x".parse().unwrap(), 32f32), ("y".parse().unwrap(), -12f32), ("z".parse().unwrap(), -10f32)]));
     /// c -= l_f;
     /// assert_eq!(c, expected)
     /// ```
@@ -271,8 +673,8 @@ impl std::ops::Mul<f32> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let a = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let expected = LinearFunction::new(60f32, HashMap::from([(String::from("x"), 64f32), (String::from("z"), -10f32)]));
+    /// let a = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let expected = LinearFunction::new(60f32, HashMap::from([("x".parse().unwrap(), 64f32), ("z".parse().unwrap(), -10f32)]));
     /// assert_eq!(a * 2f32, expected)
     /// ```
     fn mul(self, rhs: f32) -> Self::Output {
@@ -281,7 +683,7 @@ impl std::ops::Mul<f32> for LinearFunction {
             coefficients: self
                 .coefficients
                 .iter()
-                .map(|(var, coeff)| (var.to_string(), coeff * rhs))
+                .map(|(var, coeff)| (var.clone(), coeff * rhs))
                 .collect(),
         }
     }
@@ -302,8 +704,8 @@ impl std::ops::Div<f32> for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let a = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let expected = LinearFunction::new(15f32, HashMap::from([(String::from("x"), 16f32), (String::from("z"), -2.5)]));
+    /// let a = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let expected = LinearFunction::new(15f32, HashMap::from([("x".parse().unwrap(), 16f32), ("z".parse().unwrap(), -2.5)]));
     /// assert_eq!(a / 2f32, expected)
     /// ```
     fn div(self, rhs: f32) -> Self::Output {
@@ -312,7 +714,7 @@ impl std::ops::Div<f32> for LinearFunction {
             coefficients: self
                 .coefficients
                 .iter()
-                .map(|(var, coeff)| (var.to_string(), coeff / rhs))
+                .map(|(var, coeff)| (var.clone(), coeff / rhs))
                 .collect(),
         }
     }
@@ -333,8 +735,8 @@ impl std::ops::Neg for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let a = LinearFunction::new(30f32, HashMap::from([(String::from("x"), 32f32), (String::from("z"), -5f32)]));
-    /// let expected = LinearFunction::new(-30f32, HashMap::from([(String::from("x"), -32f32), (String::from("z"), 5f32)]));
+    /// let a = LinearFunction::new(30f32, HashMap::from([("x".parse().unwrap(), 32f32), ("z".parse().unwrap(), -5f32)]));
+    /// let expected = LinearFunction::new(-30f32, HashMap::from([("x".parse().unwrap(), -32f32), ("z".parse().unwrap(), 5f32)]));
     /// assert_eq!(-a, expected)
     /// ```
     fn neg(self) -> Self::Output {
@@ -343,7 +745,7 @@ impl std::ops::Neg for LinearFunction {
             coefficients: self
                 .coefficients
                 .iter()
-                .map(|(var, coeff)| (var.to_string(), -coeff))
+                .map(|(var, coeff)| (var.clone(), -coeff))
                 .collect(),
         }
     }
@@ -359,36 +761,87 @@ impl std::str::FromStr for LinearFunction {
     /// use std::collections::HashMap;
     /// use simplex::linear_function::LinearFunction;
     ///
-    /// let expected = LinearFunction::new(3f32, HashMap::from([(String::from("x"), -2f32)]));
-    /// assert_eq!("3 - 2x".parse::<LinearFunction>().unwrap(), expected)
+    /// let expected = LinearFunction::new(3f32, HashMap::from([("x".parse().unwrap(), -2f32)]));
+    /// assert_eq!("3 - 2x".parse::<LinearFunction>().unwrap(), expected);
+    ///
+    /// // Coefficients can be fractions
+    /// let f = "1/2 x - 3/4y".parse::<LinearFunction>().unwrap();
+    /// assert_eq!(f, "0.5x - 0.75y".parse().unwrap());
+    /// assert!("2/0 x".parse::<LinearFunction>().is_err());
+    ///
+    /// // Consecutive signs combine, and a sign must be followed by a term
+    /// assert_eq!("x - -y".parse::<LinearFunction>(), "x + y".parse());
+    /// assert_eq!("x + -2y".parse::<LinearFunction>(), "x - 2y".parse());
+    /// assert!("x -".parse::<LinearFunction>().is_err());
+    ///
+    /// // Numbers may have an exponent, while `2e` is twice `e`
+    /// assert_eq!("1.5e2x + 2e".parse::<LinearFunction>(), "150x + 2 e".parse());
+    ///
+    /// // Parsing the exact string of a function gives back the same function
+    /// let f = LinearFunction::term(-0.125, "x_1".parse().unwrap()) + LinearFunction::term(2.0, "e1".parse().unwrap());
+    /// assert_eq!(f.to_exact_string(), "2.0*e1 - 0.125x_1");
+    /// assert_eq!(f.to_exact_string().parse::<LinearFunction>().unwrap(), f);
     /// ```
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        fn parse_variable(input: &str) -> IResult<&str, (Variable, Coefficient)> {
-            let (rest, positive) = if let Ok((rest, sign)) =
-                preceded(multispace0::<&str, ()>, alt((tag("-"), tag("+"))))(input)
-            {
-                (rest, sign == "+")
-            } else {
-                (input, true)
-            };
+        // An exponent needs digits, so that `2e` is read as twice `e`. Infinities and NaN aren't
+        // numbers, so that variables like `inf` keep their meaning
+        fn parse_exponent(input: &str) -> IResult<&str, &str, ()> {
+            recognize(tuple((one_of("eE"), opt(one_of("+-")), digit1)))(input)
+        }
+        fn parse_number(input: &str) -> IResult<&str, Coefficient, ()> {
+            let number = alt((
+                recognize(tuple((digit1, opt(pair(char('.'), digit0)), opt(parse_exponent)))),
+                recognize(tuple((char('.'), digit1, opt(parse_exponent)))),
+            ));
+            map_res(number, str::parse)(input)
+        }
+
+        // A number, or a fraction of two numbers such as `3/4`, whose denominator can't be 0
+        fn parse_coefficient(input: &str) -> IResult<&str, Coefficient, ()> {
+            let (rest, numerator) = parse_number(input)?;
+            match preceded(multispace0::<&str, ()>, char('/'))(rest) {
+                Ok((rest, _)) => match preceded(multispace0, parse_number)(rest) {
+                    Ok((rest, denominator)) if denominator != 0.0 => Ok((rest, numerator / denominator)),
+                    _ => Err(nom::Err::Failure(())),
+                },
+                Err(_) => Ok((rest, numerator)),
+            }
+        }
+
+        // Consecutive signs combine, so that `x - -y` is `x + y` and `x + -2y` is `x - 2y`
+        fn parse_sign(input: &str) -> IResult<&str, bool, ()> {
+            let (rest, signs) = many0(preceded(multispace0, alt((char('-'), char('+')))))(input)?;
+            Ok((rest, signs.iter().filter(|sign| **sign == '-').count() % 2 == 0))
+        }
+
+        fn parse_variable(input: &str) -> IResult<&str, (String, Coefficient)> {
+            let (rest, positive) = parse_sign(input).unwrap_or((input, true));
 
             let mut found_coeff = false;
-            let (rest, coeff) =
-                if let Ok((rest, coeff)) = preceded(multispace0::<&str, ()>, float)(rest) {
+            let (rest, coeff) = match preceded(multispace0, parse_coefficient)(rest) {
+                Ok((rest, coeff)) => {
                     found_coeff = true;
                     (rest, coeff)
-                } else {
-                    (rest, 1.0)
-                };
+                }
+                Err(nom::Err::Failure(())) => {
+                    return Err(nom::Err::Failure(nom::error::Error {
+                        input: rest,
+                        code: nom::error::ErrorKind::Fail,
+                    }))
+                }
+                Err(_) => (rest, 1.0),
+            };
 
             let rest = match preceded(multispace0::<&str, ()>, tag("*"))(rest) {
                 Ok((rest_mult, _)) => rest_mult,
                 _ => rest,
             };
 
-            let (rest, variable) = match preceded(multispace0::<&str, ()>, alpha1)(rest) {
+            let start_of_var = take_while1(|c: char| c.is_ascii_alphabetic() || c == '_');
+            let (rest, variable) = match preceded(multispace0::<&str, ()>, start_of_var)(rest) {
                 Ok((rest, variable)) => {
-                    let (rest, variable) = match alphanumeric0::<&str, ()>(rest) {
+                    let end_of_var = take_while::<_, &str, ()>(|c: char| c.is_ascii_alphanumeric() || c == '_');
+                    let (rest, variable) = match end_of_var(rest) {
                         Ok((rest, end_of_var)) => {
                             let mut var = variable.to_owned();
                             var += end_of_var;
@@ -419,56 +872,112 @@ impl std::str::FromStr for LinearFunction {
         }
 
         let mut linear_func = LinearFunction::zero();
-        let (_, variables) = many0(parse_variable)(s).unwrap();
+        let (rest, variables) = many0(parse_variable)(s).map_err(|_| ())?;
+        // Whatever couldn't be read, such as a sign without any term after it, is an error
+        // rather than being dropped
+        if !rest.trim().is_empty() {
+            return Err(());
+        }
         for (var, coeff) in variables {
             if var.is_empty() {
                 linear_func.constant += coeff;
             } else {
-                linear_func[&var] += coeff;
+                linear_func[&Variable::unchecked(var)] += coeff;
             }
         }
         Ok(linear_func)
     }
 }
 
-impl std::fmt::Display for LinearFunction {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl LinearFunction {
+    /// Formats this function like `Display` does, but with as many decimals as each number needs,
+    /// so that parsing the string gives back the same function
+    /// ```rust
+    /// use simplex::linear_function::LinearFunction;
+    ///
+    /// let f = LinearFunction::term(1.0 / 3.0, "x".parse().unwrap());
+    /// assert_eq!(f.to_string(), "0.3x");
+    /// assert_eq!(f.to_exact_string(), "0.33333334x");
+    /// ```
+    pub fn to_exact_string(&self) -> String {
+        self.format_with(None, |_| true)
+    }
+
+    /// Formats this function like `Display` does, with `precision` decimals and only the terms
+    /// whose variable is accepted by `keep`
+    ///
+    /// Without a precision, each number gets as many decimals as needed to be parsed back
+    /// exactly, and at least one, and a coefficient is separated by `*` from a variable which
+    /// would be read as its exponent
+    pub(crate) fn format_with(&self, precision: Option<usize>, keep: impl Fn(&Variable) -> bool) -> String {
+        let number = |value: Coefficient| match precision {
+            Some(precision) => format!("{value:.precision$}"),
+            None => {
+                let short = format!("{value:.1}");
+                if short.parse() == Ok(value) {
+                    short
+                } else {
+                    value.to_string()
+                }
+            }
+        };
+
+        let separator = |var: &Variable| {
+            let mut chars = var.chars();
+            let is_exponent = matches!(chars.next(), Some('e' | 'E')) && chars.next().is_some_and(|c| c.is_ascii_digit());
+            if precision.is_none() && is_exponent {
+                "*"
+            } else {
+                ""
+            }
+        };
+
         // sort the hashmap by variable name
         // filtre for the non-zero coefficients
         // then iterate over the coefficients
         let mut h_map: Vec<_> = self.coefficients.clone().into_iter().collect();
         h_map.sort_by_key(|(var, _)| var.clone());
-        h_map.retain(|(_, coeff)| *coeff != 0.0);
+        h_map.retain(|(var, coeff)| *coeff != 0.0 && keep(var));
         let mut coeff_iter = h_map.iter();
 
-        if self.constant != 0.0 {
-            write!(f, "{:.1}", self.constant)
+        let mut s = if self.constant != 0.0 {
+            number(self.constant)
         } else if let Some((var, coeff)) = coeff_iter.next() {
             match *coeff {
-                x if x == 1.0 => write!(f, "{var}"),
-                x if x == -1.0 => write!(f, "-{var}"),
-                _ => write!(f, "{coeff:.1}{var}"),
+                1.0 => var.to_string(),
+                -1.0 => format!("-{var}"),
+                _ => format!("{}{}{var}", number(*coeff), separator(var)),
             }
         } else {
-            write!(f, "0")
-        }?;
+            "0".to_string()
+        };
         for (var, coeff) in coeff_iter {
             match *coeff {
-                x if x == 1.0 => write!(f, " + {var}"),
-                x if x == -1.0 => write!(f, " - {var}"),
-                _ => write!(
-                    f,
-                    "{}{:.1}{var}",
-                    if coeff.is_sign_positive() {
-                        " + "
-                    } else {
-                        " - "
-                    },
-                    coeff.abs(),
-                ),
-            }?;
+                1.0 => s += &format!(" + {var}"),
+                -1.0 => s += &format!(" - {var}"),
+                _ => {
+                    s += &format!(
+                        "{}{}{}{var}",
+                        if coeff.is_sign_positive() {
+                            " + "
+                        } else {
+                            " - "
+                        },
+                        number(coeff.abs()),
+                        separator(var),
+                    )
+                }
+            }
         }
-        Ok(())
+        s
+    }
+}
+
+impl std::fmt::Display for LinearFunction {
+    /// Rounds every number to one decimal, see [`LinearFunction::to_exact_string`] for a string
+    /// which parses back to the same function
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format_with(Some(1), |_| true))
     }
 }
 
@@ -478,20 +987,145 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_variable_names() {
+        for name in ["x", "x1", "_t"] {
+            assert_eq!(Variable::new(name).map(String::from), Ok(name.to_string()));
+            assert_eq!(name.parse::<Variable>(), Variable::try_from(name));
+        }
+        for name in ["2x", "x y", "", "é", "xé"] {
+            assert_eq!(Variable::new(name), Err(SimplexError::InvalidVariable(name.to_string())));
+        }
+        assert_eq!(Variable::new("ε0"), Err(SimplexError::ReservedVariable(Variable::unchecked("ε0"))));
+
+        // The parser accepts the same names
+        let lf = LinearFunction::from_str("2_t + x1").unwrap();
+        assert_eq!(lf.variables(), vec!["_t", "x1"]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_variable() {
+        let deserialize = |json: &str| serde_json::from_str::<Variable>(json).ok();
+        assert_eq!(deserialize(r#""x1""#), Some(var("x1")));
+        assert_eq!(serde_json::to_string(&var("x1")).unwrap(), r#""x1""#);
+        // The names made up by the solver are found in snapshots
+        assert_eq!(deserialize(r#""ε0""#), Some(var("ε0")));
+        assert_eq!(deserialize(r#""x⁺""#), Some(Variable::unchecked("x⁺")));
+        for json in [r#""2x""#, r#""é""#, r#""x y""#, r#""2x⁺""#] {
+            assert_eq!(deserialize(json), None);
+        }
+    }
+
     #[test]
     fn test_single_variable_with_coeff() {
-        let single_variable_lf = LinearFunction::single_variable_with_coeff("x".to_string(), 32f32);
+        let single_variable_lf = LinearFunction::single_variable_with_coeff(var("x"), 32f32);
         let expected = LinearFunction::from_str("32x").unwrap();
 
         assert_eq!(single_variable_lf, expected);
     }
 
+    #[test]
+    fn test_new() {
+        let lf = LinearFunction::new(
+            3.0,
+            HashMap::from([(var("x"), 2.0), (var("y"), 0.0)]),
+        );
+
+        assert_eq!(lf, LinearFunction::from_str("2x + 3").unwrap());
+        assert_ne!(lf, LinearFunction::from_str("2x + y + 3").unwrap());
+        assert!(lf.approx_eq(&LinearFunction::from_str("2.0000001x + 3").unwrap(), 1e-5));
+        assert!(!lf.approx_eq(&LinearFunction::from_str("2.1x + 3").unwrap(), 1e-5));
+    }
+
+    #[test]
+    fn test_simplify() {
+        let x = LinearFunction::single_variable(var("x"));
+        let y = LinearFunction::single_variable(var("y"));
+        let lf = x.clone() - x.clone() + y.clone();
+        assert!(lf.non_gap_variables().contains(&var("x")));
+        assert_eq!(lf.simplified().var_iter().collect::<Vec<_>>(), vec!["y"]);
+
+        // Rounding errors are dropped as well
+        let lf = x.clone() * 0.1 * 3.0 - x * 0.3 + y;
+        assert_eq!(lf.simplified().var_iter().collect::<Vec<_>>(), vec!["y"]);
+    }
+
+    #[test]
+    fn test_coefficient_of() {
+        let lf = LinearFunction::from_str("2x + 3y - 4").unwrap();
+
+        assert_eq!(lf.coefficient_of("x"), 2.0);
+        assert_eq!(lf.coefficient_of("y"), 3.0);
+        assert_eq!(lf.coefficient_of("z"), 0.0);
+        assert_eq!(lf.constant_term(), -4.0);
+
+        let gap = var(&format!("{GAP_VARIABLE_IDENTIFIER}0"));
+        let lf = lf + LinearFunction::term(-1.0, gap.clone());
+        assert_eq!(lf.coefficient_of(&gap), -1.0);
+    }
+
     #[test]
     fn test_first_positive_coefficient() {
         let lf = LinearFunction::from_str("200+5x-6z+3y").unwrap();
-        let var = "x".to_string();
+        let x = var("x");
+
+        assert_eq!(lf.first_positive_coefficient(true, 0.0), Some(x));
 
-        assert_eq!(lf.first_positive_coefficient(true), Some(var));
+        // Coefficients below the tolerance don't count as positive
+        let lf = LinearFunction::from_str("0.0001x + 3y").unwrap();
+        assert_eq!(lf.first_positive_coefficient(true, 0.0), Some(var("x")));
+        assert_eq!(lf.first_positive_coefficient(true, 0.001), Some(var("y")));
+    }
+
+    #[test]
+    fn test_is_zero_and_constant() {
+        let f = |s: &str| LinearFunction::from_str(s).unwrap();
+        let tolerance = ZERO_TOLERANCE;
+        assert!(f("0").is_zero(tolerance) && f("0").is_constant(tolerance));
+        assert!(!f("5").is_zero(tolerance) && f("5").is_constant(tolerance));
+        assert!(f("x - x").is_zero(tolerance) && f("x - x").is_constant(tolerance));
+        assert!(!f("x").is_zero(tolerance) && !f("x").is_constant(tolerance));
+        // Within the tolerance of `f32` computations
+        assert!((f("0.1x + 0.2x") - f("0.3x")).is_zero(tolerance));
+        assert!(LinearFunction::zero().is_zero(tolerance));
+        // A looser tolerance ignores larger leftovers
+        assert!(f("0.001x + 0.001").is_zero(1e-2) && !f("0.001x + 0.001").is_zero(1e-4));
+        assert!(f("0.001x").is_constant(1e-2) && !f("0.001x").is_constant(0.0));
+    }
+
+    #[test]
+    fn test_terms() {
+        let lf = LinearFunction::from_str("2x + 3y - 4").unwrap() + LinearFunction::term(0.0, var("z"));
+        assert_eq!(
+            lf.terms().collect::<Vec<_>>(),
+            vec![(&var("x"), 2.0), (&var("y"), 3.0)]
+        );
+        assert_eq!(LinearFunction::constant(4.0).terms().count(), 0);
+    }
+
+    #[test]
+    fn test_split_positive_negative() {
+        let lf = LinearFunction::from_str("2x - 3y + 4").unwrap();
+        let (positive, negative) = lf.split_positive_negative();
+        assert_eq!(positive, LinearFunction::from_str("2x + 4").unwrap());
+        assert_eq!(negative, LinearFunction::from_str("3y").unwrap());
+
+        // The constant goes with the negative terms when it is negative
+        let lf = LinearFunction::from_str("x - 2").unwrap() + LinearFunction::term(0.0, var("z"));
+        let (positive, negative) = lf.split_positive_negative();
+        assert_eq!(positive, LinearFunction::from_str("x").unwrap());
+        assert_eq!(negative, LinearFunction::constant(2.0));
+        assert_eq!(positive - negative, lf.simplified());
+    }
+
+    #[test]
+    fn test_max_coefficient_ties() {
+        for _ in 0..20 {
+            let lf = LinearFunction::from_str("3z + x + 3y + 3w - v").unwrap();
+            assert_eq!(lf.max_coefficient(), Some((var("w"), 3.0)));
+        }
+        assert_eq!(LinearFunction::constant(1.0).max_coefficient(), None);
     }
 
     #[test]
@@ -499,8 +1133,8 @@ mod tests {
         let mut lf = LinearFunction::from_str("3x + 6y - 9z + 150").unwrap();
         let expected = LinearFunction::from_str("x + 2y - 3z + 50").unwrap();
 
-        let var = String::from("x");
-        lf.normalize(&var);
+        let x = var("x");
+        lf.normalize(&x);
 
         assert_eq!(lf, expected);
     }
@@ -522,17 +1156,81 @@ mod tests {
         let lf1 = LinearFunction::from_str("x + 0").unwrap();
         let lf2 = LinearFunction::from_str("y + 0z + 0 ").unwrap();
 
-        assert_eq!(lf1.name_single_variable().unwrap(), "x".to_string());
-        assert_eq!(lf2.name_single_variable().unwrap(), "y".to_string());
+        assert_eq!(lf1.name_single_variable().unwrap(), var("x"));
+        assert_eq!(lf2.name_single_variable().unwrap(), var("y"));
     }
     #[test]
     fn test_variable_name_with_alphanumeric1() {
         let lf = LinearFunction::from_str("3 x0+ 2   y").unwrap();
         let expected = LinearFunction {
             constant: 0.0,
-            coefficients: HashMap::from([(String::from("x0"), 3.0), (String::from("y"), 2.0)]),
+            coefficients: HashMap::from([(var("x0"), 3.0), (var("y"), 2.0)]),
         };
 
         assert_eq!(lf, expected);
     }
+
+    #[test]
+    fn test_rename_variable() {
+        let mut lf = LinearFunction::from_str("2x + y - 3").unwrap();
+        lf.rename_variable("x", "y").unwrap();
+        assert_eq!(lf, LinearFunction::from_str("3y - 3").unwrap());
+
+        // The merged terms cancel out
+        let mut lf = LinearFunction::from_str("x - y").unwrap();
+        lf.rename_variable("x", "y").unwrap();
+        assert_eq!(lf.variables().len(), 0);
+
+        let mut lf = LinearFunction::from_str("x").unwrap();
+        lf.rename_variable("z", "x").unwrap();
+        assert_eq!(lf, LinearFunction::from_str("x").unwrap());
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let round_trip = |f: LinearFunction| {
+            let displayed = f.to_exact_string();
+            assert_eq!(LinearFunction::from_str(&displayed).unwrap(), f, "{displayed}");
+            displayed
+        };
+        assert_eq!(round_trip(LinearFunction::zero()), "0");
+        assert_eq!(round_trip(LinearFunction::constant(-2.5)), "-2.5");
+        assert_eq!(round_trip(LinearFunction::from_str("-x + 3y").unwrap()), "-x + 3.0y");
+        assert_eq!(round_trip(LinearFunction::from_str("-2x - 0.1").unwrap()), "-0.1 - 2.0x");
+        // Coefficients aren't rounded, unlike in `Display`
+        assert_eq!(round_trip(LinearFunction::term(1.0 / 3.0, var("y"))), "0.33333334y");
+        assert_eq!(LinearFunction::term(1.0 / 3.0, var("y")).to_string(), "0.3y");
+        // Variables which could be mistaken for a part of a number
+        assert_eq!(round_trip(LinearFunction::from_str("2e + inf + nan1").unwrap()), "2.0e + inf + nan1");
+        assert_eq!(round_trip(LinearFunction::from_str("2*e1 + 3 * E2x").unwrap()), "3.0*E2x + 2.0*e1");
+        // Exponents are still numbers
+        assert_eq!(LinearFunction::from_str("1e3x - 2.5E-1").unwrap(), LinearFunction::from_str("1000x - 0.25").unwrap());
+    }
+
+    #[test]
+    fn test_parse_fractions() {
+        assert_eq!(LinearFunction::from_str("1/2 x").unwrap(), LinearFunction::term(0.5, var("x")));
+        assert_eq!(LinearFunction::from_str("-3/4 y").unwrap(), LinearFunction::term(-0.75, var("y")));
+        assert_eq!(LinearFunction::from_str("5/1").unwrap(), LinearFunction::constant(5.0));
+        assert_eq!(LinearFunction::from_str("1/3").unwrap(), LinearFunction::constant(1.0 / 3.0));
+        assert_eq!(LinearFunction::from_str("1/2 + 1 / 2").unwrap(), LinearFunction::constant(1.0));
+        assert_eq!(LinearFunction::from_str("1/2x + 1/2x").unwrap(), LinearFunction::term(1.0, var("x")));
+
+        assert!(LinearFunction::from_str("1/0 x").is_err());
+        assert!(LinearFunction::from_str("x + 2/").is_err());
+    }
+
+    #[test]
+    fn test_parse_sign_sequences() {
+        let parse = |s: &str| LinearFunction::from_str(s);
+        assert_eq!(parse("x - -y"), parse("x + y"));
+        assert_eq!(parse("x + -2y"), parse("x - 2y"));
+        assert_eq!(parse("x -+ y"), parse("x - y"));
+        assert_eq!(parse("--3 - - - x"), parse("3 - x"));
+
+        // Signs which don't lead to any term aren't silently dropped
+        assert!(parse("x - -").is_err());
+        assert!(parse("x + y +").is_err());
+        assert!(parse("x + ) y").is_err());
+    }
 }
@@ -98,7 +98,7 @@ impl PolyhedronRenderer {
         self.vertices = constraints.polyhedron();
     }
 
-    pub fn draw(&mut self, gl: &glow::Context, rect_size: [u32; 2], current_point: &[f32; 3]) {
+    pub fn draw(&mut self, gl: &glow::Context, rect_size: [u32; 2], _current_point: &[f32; 3]) {
         unsafe {
             // create buffer with polyhedron
             let data = self.vertices.as_slice();
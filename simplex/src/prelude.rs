@@ -0,0 +1,25 @@
+//! The types needed to write and solve most programs, to be imported all at once
+//! ```rust
+//! use simplex::prelude::*;
+//!
+//! let mut constraints = Constraints::new();
+//! constraints.add_constraint("x + y <= 4".parse::<Constraint>().unwrap());
+//! constraints.add_constraint(Constraint::new(
+//!     LinearFunction::term(1.0, "y".parse().unwrap()),
+//!     Operator::LessEqual,
+//!     LinearFunction::constant(3.0),
+//! ));
+//! let objective: LinearFunction = "x + 2y".parse().unwrap();
+//!
+//! let mut simplex: Simplex = constraints.maximize(&objective).unwrap();
+//! simplex.solve(PivotRule::Bland).unwrap();
+//! let program: &LinearProgram = simplex.current_state();
+//! assert_eq!(program.sense, ObjectiveSense::Max);
+//! let optimum: Coefficient = simplex.current_state().objective_value();
+//! assert_eq!(optimum, 7.0);
+//! let values: Vec<(Variable, Coefficient)> = simplex.current_values();
+//! assert_eq!(values, vec![("x".parse().unwrap(), 1.0), ("y".parse().unwrap(), 3.0)]);
+//! ```
+pub use crate::constraint::{Constraint, Constraints, Operator};
+pub use crate::linear_function::{Coefficient, LinearFunction, Variable};
+pub use crate::{LinearProgram, ObjectiveSense, PivotRule, Simplex, SimplexError};
@@ -0,0 +1,154 @@
+//! Equilibration of linear programs, so that badly scaled problems can be solved in `f32`
+use crate::linear_function::{Coefficient, Variable};
+use crate::{LinearProgram, SimplexError};
+use std::collections::HashMap;
+
+/// The number of alternate passes over the rows and the columns, which is enough for the factors
+/// to settle on usual problems
+const EQUILIBRATION_PASSES: usize = 4;
+
+/// The factors a program was scaled by, see [`LinearProgram::equilibrate`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScalingFactors {
+    /// The factor each row of the tableau was multiplied by, its gap variable standing for its
+    /// slack times this factor
+    pub rows: Vec<Coefficient>,
+    /// For each scaled variable `x`, the factor `c` such that `x = c * x'` where `x'` is the
+    /// variable of the scaled program
+    pub columns: HashMap<Variable, Coefficient>,
+}
+
+impl ScalingFactors {
+    /// Returns the value of `var` in the original program, from its `value` in the scaled one
+    pub fn unscale(&self, var: &str, value: Coefficient) -> Coefficient {
+        value * self.columns.get(var).copied().unwrap_or(1.0)
+    }
+
+    /// Unscales every value of a solution of the scaled program, as given by
+    /// [`LinearProgram::values`]
+    pub fn unscale_values(&self, values: &[(Variable, Coefficient)]) -> Vec<(Variable, Coefficient)> {
+        values
+            .iter()
+            .map(|(var, value)| (var.clone(), self.unscale(var, *value)))
+            .collect()
+    }
+}
+
+/// Returns the power of two closest to `1 / sqrt(min * max)` over the given magnitudes, which
+/// brings them around 1 without any rounding error, or 1 without magnitudes
+fn power_of_two_factor(magnitudes: impl Iterator<Item = Coefficient>) -> Coefficient {
+    let (min, max) = magnitudes
+        .filter(|m| *m > 0.0)
+        .fold((f64::INFINITY, 0f64), |(min, max), m| (min.min(m as f64), max.max(m as f64)));
+    if max == 0.0 {
+        return 1.0;
+    }
+    (-(min * max).sqrt().log2()).round().exp2() as Coefficient
+}
+
+impl LinearProgram {
+    /// Scales the rows and the decision variables of the program so that the magnitudes of their
+    /// coefficients get close to 1, which makes the pivots of badly scaled problems more accurate
+    ///
+    /// The factors are powers of two, hence don't introduce rounding errors themselves. The
+    /// objective value is unchanged, but the values of the variables must be unscaled with the
+    /// returned factors. Bounded variables are left unscaled, and so is an already pivoted
+    /// program, for which `PivotedTableau` is returned
+    /// ```rust
+    /// use simplex::{LinearProgram, PivotRule, Simplex};
+    ///
+    /// let mut program = "max x + y\n 1000x <= 4000\n 0.01y <= 0.02".parse::<LinearProgram>().unwrap();
+    /// let factors = program.equilibrate().unwrap();
+    /// let coefficient = program.constraints[0].right.coefficient_of("x");
+    /// assert!((-2.0..=-0.5).contains(&coefficient));
+    ///
+    /// let mut simplex = Simplex::from(program);
+    /// simplex.solve(PivotRule::Bland).unwrap();
+    /// assert_eq!(simplex.current_state().objective_value(), 6.0);
+    /// let values = factors.unscale_values(&simplex.current_values());
+    /// assert_eq!(values, vec![("x".parse().unwrap(), 4.0), ("y".parse().unwrap(), 2.0)]);
+    /// ```
+    pub fn equilibrate(&mut self) -> Result<ScalingFactors, SimplexError> {
+        if self.constraints.is_pivoted() {
+            return Err(SimplexError::PivotedTableau);
+        }
+        let variables: Vec<Variable> = self
+            .non_gap_variables()
+            .into_iter()
+            .filter(|var| !self.bounds.contains_key(var))
+            .collect();
+
+        let mut rows = vec![1.0; self.constraints.len()];
+        let mut columns: HashMap<Variable, Coefficient> = variables.iter().map(|var| (var.clone(), 1.0)).collect();
+        for _ in 0..EQUILIBRATION_PASSES {
+            for (row, constraint) in rows.iter_mut().zip(self.constraints.iter()) {
+                *row = power_of_two_factor(
+                    variables.iter().map(|var| (constraint.right[var] * columns[var]).abs()),
+                );
+            }
+            for var in variables.iter() {
+                let magnitudes = rows
+                    .iter()
+                    .zip(self.constraints.iter())
+                    .map(|(row, constraint)| (row * constraint.right[var]).abs());
+                columns.insert(var.clone(), power_of_two_factor(magnitudes));
+            }
+        }
+
+        for (i, row) in rows.iter().enumerate() {
+            self.constraints[i].right *= *row;
+        }
+        for (var, factor) in columns.iter() {
+            self.constraints.scale_variable(var, *factor);
+            if self.linear_function.contains(var) {
+                self.linear_function[var] *= *factor;
+            }
+        }
+        Ok(ScalingFactors { rows, columns })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linear_function::var;
+    use crate::{PivotRule, Simplex};
+
+    #[test]
+    fn test_equilibrate() {
+        // The optimum is at x = 2.4, y = 200000, where the objective is 2600
+        let program = "max 1000x + 0.001y\n 1000x + 0.003y <= 3000\n 2000x + 0.001y <= 5000"
+            .parse::<LinearProgram>()
+            .unwrap();
+        let solve = |program: LinearProgram| {
+            let mut simplex = Simplex::from(program);
+            simplex.solve(PivotRule::Bland).unwrap();
+            (simplex.current_state().objective_value(), simplex.current_values())
+        };
+
+        let mut scaled = program.clone();
+        let factors = scaled.equilibrate().unwrap();
+        assert!(factors.rows.iter().chain(factors.columns.values()).all(|f| f.log2().fract() == 0.0));
+        // Each scaled coefficient is exactly the original one times its row and column factors,
+        // and all of them end up around 1
+        for (i, (original, scaled)) in program.constraints.iter().zip(scaled.constraints.iter()).enumerate() {
+            for (var, column) in factors.columns.iter() {
+                assert_eq!(scaled.right[var], original.right[var] * factors.rows[i] * column);
+                assert!((0.25..=4.0).contains(&scaled.right[var].abs()));
+            }
+        }
+
+        let (objective, values) = solve(scaled);
+        assert!((objective - 2600.0).abs() < 1e-3);
+        let values = factors.unscale_values(&values);
+        assert!((values[0].1 - 2.4).abs() < 1e-5);
+        assert!((values[1].1 - 200000.0).abs() < 1e-1);
+    }
+
+    #[test]
+    fn test_equilibrate_pivoted() {
+        let mut program = "max x\n x <= 4".parse::<LinearProgram>().unwrap();
+        program.pivot(var("x")).unwrap();
+        assert_eq!(program.equilibrate(), Err(SimplexError::PivotedTableau));
+    }
+}
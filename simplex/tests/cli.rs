@@ -0,0 +1,65 @@
+//! The command line tool, through the function its binary calls
+#![cfg(feature = "cli")]
+use simplex::cli::{run, CliOptions, OutputFormat};
+
+const PROBLEM: &str = "max x + 6y + 13z\n x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600";
+
+fn options(args: &[&str]) -> CliOptions {
+    CliOptions::from_args(args.iter().map(|arg| arg.to_string())).unwrap()
+}
+
+#[test]
+fn prints_the_optimum() {
+    assert_eq!(
+        run(PROBLEM, &options(&["--bland"])).unwrap(),
+        "objective = 3100\nx = 0\ny = 300\nz = 100\n"
+    );
+    assert_eq!(
+        run(PROBLEM, &options(&["--bland", "--format", "text"])).unwrap(),
+        run(PROBLEM, &options(&["--bland"])).unwrap()
+    );
+    assert_eq!(
+        run(PROBLEM, &options(&["--bland", "--format", "json"])).unwrap(),
+        "{\"objective\":3100.0,\"values\":{\"x\":0.0,\"y\":300.0,\"z\":100.0}}\n"
+    );
+}
+
+#[test]
+fn prints_every_step() {
+    let output = run(PROBLEM, &options(&["--bland", "--steps"])).unwrap();
+    let lines = output.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "step 0: objective = 0, x = 0, y = 0, z = 0");
+    assert!(lines.iter().any(|line| line.ends_with("objective = 3100, x = 0, y = 300, z = 100")));
+
+    let output = run(PROBLEM, &options(&["--bland", "--steps", "--format", "json"])).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+    assert_eq!(json["objective"], 3100.0);
+    assert_eq!(json["steps"][0], serde_json::json!({"objective": 0.0, "values": {"x": 0.0, "y": 0.0, "z": 0.0}}));
+    assert_eq!(json["steps"].as_array().unwrap().last().unwrap()["values"]["z"], 100.0);
+}
+
+#[test]
+fn prints_the_program() {
+    let options = options(&["--bland", "--format", "lp"]);
+    assert_eq!(options.format, OutputFormat::Lp);
+    let output = run(PROBLEM, &options).unwrap();
+    assert!(output.starts_with("\\ objective = 3100\n\\ x = 0\n"), "{output}");
+    assert!(output.contains("Maximize\n obj: x + 6 y + 13 z\nSubject To\n c0: x <= 200\n"));
+
+    let options = self::options(&["--format", "mps"]);
+    assert_eq!(options.format, OutputFormat::Mps);
+    let output = run(PROBLEM, &options).unwrap();
+    assert!(output.starts_with("* objective = 3100\n"));
+    assert!(output.contains("NAME simplex\nOBJSENSE\n    MAX\n"));
+}
+
+#[test]
+fn reports_errors() {
+    assert_eq!(run("max x\n x >= 1", &options(&[])).unwrap_err(), "the program is unbounded");
+    assert_eq!(run("max x\n x <= 1\n x >= 2", &options(&[])).unwrap_err(), "the program is infeasible");
+    assert!(run("max x\n x + 4", &options(&[])).unwrap_err().starts_with("line 2"));
+
+    assert!(CliOptions::from_args(["--format".to_string(), "csv".to_string()]).is_err());
+
+    assert!(CliOptions::from_args(["--fast".to_string()]).is_err());
+}
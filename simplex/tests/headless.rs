@@ -0,0 +1,29 @@
+//! Solving linear programs through the public API only, which must work without the `gui` feature
+use simplex::constraint::Constraints;
+use simplex::linear_function::LinearFunction;
+use simplex::PivotRule;
+
+#[test]
+fn solve_default_problem() {
+    let constraints =
+        Constraints::compile("x <= 200\n y <= 300\n x + y + z <= 400\n y + 3z <= 600").unwrap();
+    let mut simplex = constraints
+        .maximize(&"x + 6y + 13z".parse::<LinearFunction>().unwrap())
+        .unwrap();
+    while simplex.next_step(PivotRule::Bland).is_ok() {}
+
+    assert_eq!(simplex.current_point(), vec![0.0, 300.0, 100.0]);
+    assert_eq!(simplex.current_state().objective_value(), 3100.0);
+}
+
+#[test]
+fn solve_minimization() {
+    let constraints = Constraints::compile("x + y <= 4\n x <= 3").unwrap();
+    let mut simplex = constraints
+        .minimize(&"-x - 2y".parse::<LinearFunction>().unwrap())
+        .unwrap();
+    while simplex.next_step(PivotRule::Bland).is_ok() {}
+
+    assert_eq!(simplex.current_point(), vec![0.0, 4.0]);
+    assert_eq!(simplex.current_state().objective_value(), -8.0);
+}
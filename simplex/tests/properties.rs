@@ -0,0 +1,90 @@
+//! Invariants of the solver checked on randomly generated programs
+use proptest::prelude::*;
+use simplex::constraint::{Constraint, Constraints, Operator};
+use simplex::linear_function::{LinearFunction, Variable};
+use simplex::PivotRule;
+
+/// Number of decision variables of the generated programs
+const VARIABLES: usize = 4;
+
+/// Generates `max objective` under `a·x <= b` constraints with non negative coefficients and
+/// positive right hand sides, plus a constraint bounding every variable: the origin is feasible
+/// and the program is bounded
+fn feasible_program() -> impl Strategy<Value = (LinearFunction, Constraints)> {
+    let objective = prop::collection::vec(1u8..10, VARIABLES);
+    let rows = prop::collection::vec((prop::collection::vec(0u8..10, VARIABLES), 1u8..100), 1..6);
+    (objective, rows).prop_map(|(objective, rows)| {
+        let variable = |j: usize| Variable::new(format!("x{j}")).unwrap();
+        let objective = objective
+            .iter()
+            .enumerate()
+            .fold(LinearFunction::zero(), |acc, (j, c)| {
+                acc + LinearFunction::term(*c as f32, variable(j))
+            });
+
+        let mut constraints = Constraints::new();
+        for (coefficients, bound) in rows.iter() {
+            let left = coefficients
+                .iter()
+                .enumerate()
+                .fold(LinearFunction::zero(), |acc, (j, c)| {
+                    acc + LinearFunction::term(*c as f32, variable(j))
+                });
+            constraints.add_constraint(Constraint::new(
+                left,
+                Operator::LessEqual,
+                LinearFunction::constant(*bound as f32),
+            ));
+        }
+        // Bounds every variable
+        let all = (0..VARIABLES).fold(LinearFunction::zero(), |acc, j| {
+            acc + LinearFunction::single_variable(variable(j))
+        });
+        constraints.add_constraint(Constraint::new(
+            all,
+            Operator::LessEqual,
+            LinearFunction::constant(100.0),
+        ));
+        (objective, constraints)
+    })
+}
+
+/// Generates a linear function with a few terms, whose coefficients may be 1 or -1 as those are
+/// displayed without any number
+fn linear_function() -> impl Strategy<Value = LinearFunction> {
+    let constant = prop_oneof![Just(0.0f32), -1e6f32..1e6];
+    let coefficient = prop_oneof![Just(1.0f32), Just(-1.0), -1e3f32..1e3];
+    let terms = prop::collection::vec(("[a-z][a-z0-9_]{0,3}", coefficient), 0..5);
+    (constant, terms).prop_map(|(constant, terms)| {
+        terms
+            .into_iter()
+            .fold(LinearFunction::constant(constant), |acc, (var, c)| acc + LinearFunction::term(c, Variable::new(var).unwrap()))
+    })
+}
+
+proptest! {
+    #[test]
+    fn exact_string_parses_back(f in linear_function()) {
+        let displayed = f.to_exact_string();
+        prop_assert_eq!(displayed.parse::<LinearFunction>().unwrap(), f, "displayed as {}", displayed);
+    }
+
+    #[test]
+    fn dantzig_objective_is_monotonic((objective, constraints) in feasible_program()) {
+        let mut simplex = constraints.maximize(&objective).unwrap();
+        prop_assert!(simplex.solve(PivotRule::Dantzig).is_ok());
+        simplex.assert_monotonic_objective();
+    }
+
+    #[test]
+    fn bland_terminates_at_the_same_optimum((objective, constraints) in feasible_program()) {
+        let mut dantzig = constraints.maximize(&objective).unwrap();
+        dantzig.solve(PivotRule::Dantzig).unwrap();
+        let mut bland = constraints.maximize(&objective).unwrap();
+        prop_assert!(bland.solve(PivotRule::Bland).is_ok());
+        bland.assert_monotonic_objective();
+
+        let (a, b) = (dantzig.current_state().objective_value(), bland.current_state().objective_value());
+        prop_assert!((a - b).abs() <= 1e-3 * a.abs().max(1.0), "{} != {}", a, b);
+    }
+}